@@ -0,0 +1,15 @@
+-- Add migration script here
+ALTER TABLE slot_data ADD COLUMN exec_blob_gas_used INT;
+ALTER TABLE slot_data ADD COLUMN exec_excess_blob_gas INT;
+ALTER TABLE slot_data ADD COLUMN blob_kzg_commitments_count INT;
+ALTER TABLE slot_data ADD COLUMN parent_beacon_block_root VARCHAR;
+
+CREATE TABLE blob_sidecar_summary (
+  slot INT PRIMARY KEY NOT NULL,
+  epoch INT NOT NULL,
+  blob_count INT NOT NULL,
+  blob_gas_used INT NOT NULL,
+  excess_blob_gas INT NOT NULL,
+  total_blob_size INT NOT NULL,
+  FOREIGN KEY (slot) REFERENCES slot_data (slot)
+);
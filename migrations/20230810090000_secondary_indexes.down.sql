@@ -0,0 +1,8 @@
+-- Add down migration script here
+DROP INDEX idx_epoch_data_finalized_epoch;
+DROP INDEX idx_slot_data_blockroot;
+DROP INDEX idx_slot_data_exec_block_hash;
+DROP INDEX idx_slot_data_exec_block_number;
+DROP INDEX idx_slot_data_exec_fee_recipient_slot;
+DROP INDEX idx_slot_data_proposer_slot;
+DROP INDEX idx_slot_data_epoch_slot;
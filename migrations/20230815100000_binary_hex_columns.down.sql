@@ -0,0 +1,150 @@
+-- no-transaction
+-- Converts the raw byte columns of slot_data back to 0x-prefixed lowercase hex text.
+PRAGMA foreign_keys = OFF;
+
+BEGIN;
+
+CREATE TABLE slot_data_new (
+  attestationscount INT NOT NULL,
+  attesterslashingscount INT NOT NULL,
+  blockroot VARCHAR NOT NULL,
+  depositscount INT NOT NULL,
+  epoch INT NOT NULL,
+  eth1data_blockhash VARCHAR NOT NULL,
+  eth1data_depositcount INT NOT NULL,
+  eth1data_depositroot VARCHAR NOT NULL,
+  exec_base_fee_per_gas INT,
+  exec_block_hash VARCHAR,
+  exec_block_number INT,
+  exec_extra_data VARCHAR,
+  exec_fee_recipient VARCHAR,
+  exec_gas_limit INT,
+  exec_gas_used INT,
+  exec_logs_bloom VARCHAR,
+  exec_parent_hash VARCHAR,
+  exec_random VARCHAR,
+  exec_receipts_root VARCHAR,
+  exec_state_root VARCHAR,
+  exec_timestamp INT,
+  exec_transactions_count INT NOT NULL,
+  graffiti VARCHAR NOT NULL,
+  graffiti_text VARCHAR NOT NULL,
+  parentroot VARCHAR NOT NULL,
+  proposer INT NOT NULL,
+  proposerslashingscount INT NOT NULL,
+  randaoreveal VARCHAR NOT NULL,
+  signature VARCHAR NOT NULL,
+  slot INT PRIMARY KEY NOT NULL,
+  stateroot VARCHAR NOT NULL,
+  status VARCHAR NOT NULL,
+  syncaggregate_bits VARCHAR,
+  syncaggregate_participation REAL NOT NULL,
+  syncaggregate_signature VARCHAR,
+  voluntaryexitscount INT NOT NULL,
+  withdrawalcount INT NOT NULL,
+  exec_blob_gas_used INT,
+  exec_excess_blob_gas INT,
+  blob_kzg_commitments_count INT,
+  parent_beacon_block_root VARCHAR
+);
+
+INSERT INTO slot_data_new (
+  attestationscount,
+  attesterslashingscount,
+  blockroot,
+  depositscount,
+  epoch,
+  eth1data_blockhash,
+  eth1data_depositcount,
+  eth1data_depositroot,
+  exec_base_fee_per_gas,
+  exec_block_hash,
+  exec_block_number,
+  exec_extra_data,
+  exec_fee_recipient,
+  exec_gas_limit,
+  exec_gas_used,
+  exec_logs_bloom,
+  exec_parent_hash,
+  exec_random,
+  exec_receipts_root,
+  exec_state_root,
+  exec_timestamp,
+  exec_transactions_count,
+  graffiti,
+  graffiti_text,
+  parentroot,
+  proposer,
+  proposerslashingscount,
+  randaoreveal,
+  signature,
+  slot,
+  stateroot,
+  status,
+  syncaggregate_bits,
+  syncaggregate_participation,
+  syncaggregate_signature,
+  voluntaryexitscount,
+  withdrawalcount,
+  exec_blob_gas_used,
+  exec_excess_blob_gas,
+  blob_kzg_commitments_count,
+  parent_beacon_block_root
+)
+SELECT
+  attestationscount,
+  attesterslashingscount,
+  CASE WHEN blockroot IS NULL THEN NULL ELSE '0x' || lower(hex(blockroot)) END,
+  depositscount,
+  epoch,
+  CASE WHEN eth1data_blockhash IS NULL THEN NULL ELSE '0x' || lower(hex(eth1data_blockhash)) END,
+  eth1data_depositcount,
+  CASE WHEN eth1data_depositroot IS NULL THEN NULL ELSE '0x' || lower(hex(eth1data_depositroot)) END,
+  exec_base_fee_per_gas,
+  CASE WHEN exec_block_hash IS NULL THEN NULL ELSE '0x' || lower(hex(exec_block_hash)) END,
+  exec_block_number,
+  CASE WHEN exec_extra_data IS NULL THEN NULL ELSE '0x' || lower(hex(exec_extra_data)) END,
+  CASE WHEN exec_fee_recipient IS NULL THEN NULL ELSE '0x' || lower(hex(exec_fee_recipient)) END,
+  exec_gas_limit,
+  exec_gas_used,
+  CASE WHEN exec_logs_bloom IS NULL THEN NULL ELSE '0x' || lower(hex(exec_logs_bloom)) END,
+  CASE WHEN exec_parent_hash IS NULL THEN NULL ELSE '0x' || lower(hex(exec_parent_hash)) END,
+  CASE WHEN exec_random IS NULL THEN NULL ELSE '0x' || lower(hex(exec_random)) END,
+  CASE WHEN exec_receipts_root IS NULL THEN NULL ELSE '0x' || lower(hex(exec_receipts_root)) END,
+  CASE WHEN exec_state_root IS NULL THEN NULL ELSE '0x' || lower(hex(exec_state_root)) END,
+  exec_timestamp,
+  exec_transactions_count,
+  CASE WHEN graffiti IS NULL THEN NULL ELSE '0x' || lower(hex(graffiti)) END,
+  graffiti_text,
+  CASE WHEN parentroot IS NULL THEN NULL ELSE '0x' || lower(hex(parentroot)) END,
+  proposer,
+  proposerslashingscount,
+  CASE WHEN randaoreveal IS NULL THEN NULL ELSE '0x' || lower(hex(randaoreveal)) END,
+  CASE WHEN signature IS NULL THEN NULL ELSE '0x' || lower(hex(signature)) END,
+  slot,
+  CASE WHEN stateroot IS NULL THEN NULL ELSE '0x' || lower(hex(stateroot)) END,
+  status,
+  CASE WHEN syncaggregate_bits IS NULL THEN NULL ELSE '0x' || lower(hex(syncaggregate_bits)) END,
+  syncaggregate_participation,
+  CASE WHEN syncaggregate_signature IS NULL THEN NULL ELSE '0x' || lower(hex(syncaggregate_signature)) END,
+  voluntaryexitscount,
+  withdrawalcount,
+  exec_blob_gas_used,
+  exec_excess_blob_gas,
+  blob_kzg_commitments_count,
+  CASE WHEN parent_beacon_block_root IS NULL THEN NULL ELSE '0x' || lower(hex(parent_beacon_block_root)) END
+FROM slot_data;
+
+DROP TABLE slot_data;
+ALTER TABLE slot_data_new RENAME TO slot_data;
+
+CREATE INDEX idx_slot_data_epoch_slot ON slot_data (epoch, slot);
+CREATE INDEX idx_slot_data_proposer_slot ON slot_data (proposer, slot);
+CREATE INDEX idx_slot_data_exec_fee_recipient_slot ON slot_data (exec_fee_recipient, slot);
+CREATE INDEX idx_slot_data_exec_block_number ON slot_data (exec_block_number);
+CREATE INDEX idx_slot_data_exec_block_hash ON slot_data (exec_block_hash);
+CREATE INDEX idx_slot_data_blockroot ON slot_data (blockroot);
+
+COMMIT;
+
+PRAGMA foreign_keys = ON;
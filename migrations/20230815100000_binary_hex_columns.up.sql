@@ -0,0 +1,154 @@
+-- no-transaction
+-- Stores roots, hashes, signatures and other hex fields of slot_data as raw bytes instead of
+-- 0x-prefixed hex text. SQLite cannot change a column type in place, so the table is rebuilt
+-- with foreign keys off (blob_sidecar_summary references it) and its indexes recreated.
+-- unhex() yields NULL for malformed hex, which fails the NOT NULL columns and aborts the
+-- migration instead of silently storing garbage.
+PRAGMA foreign_keys = OFF;
+
+BEGIN;
+
+CREATE TABLE slot_data_new (
+  attestationscount INT NOT NULL,
+  attesterslashingscount INT NOT NULL,
+  blockroot BLOB NOT NULL,
+  depositscount INT NOT NULL,
+  epoch INT NOT NULL,
+  eth1data_blockhash BLOB NOT NULL,
+  eth1data_depositcount INT NOT NULL,
+  eth1data_depositroot BLOB NOT NULL,
+  exec_base_fee_per_gas INT,
+  exec_block_hash BLOB,
+  exec_block_number INT,
+  exec_extra_data BLOB,
+  exec_fee_recipient BLOB,
+  exec_gas_limit INT,
+  exec_gas_used INT,
+  exec_logs_bloom BLOB,
+  exec_parent_hash BLOB,
+  exec_random BLOB,
+  exec_receipts_root BLOB,
+  exec_state_root BLOB,
+  exec_timestamp INT,
+  exec_transactions_count INT NOT NULL,
+  graffiti BLOB NOT NULL,
+  graffiti_text VARCHAR NOT NULL,
+  parentroot BLOB NOT NULL,
+  proposer INT NOT NULL,
+  proposerslashingscount INT NOT NULL,
+  randaoreveal BLOB NOT NULL,
+  signature BLOB NOT NULL,
+  slot INT PRIMARY KEY NOT NULL,
+  stateroot BLOB NOT NULL,
+  status VARCHAR NOT NULL,
+  syncaggregate_bits BLOB,
+  syncaggregate_participation REAL NOT NULL,
+  syncaggregate_signature BLOB,
+  voluntaryexitscount INT NOT NULL,
+  withdrawalcount INT NOT NULL,
+  exec_blob_gas_used INT,
+  exec_excess_blob_gas INT,
+  blob_kzg_commitments_count INT,
+  parent_beacon_block_root BLOB
+);
+
+INSERT INTO slot_data_new (
+  attestationscount,
+  attesterslashingscount,
+  blockroot,
+  depositscount,
+  epoch,
+  eth1data_blockhash,
+  eth1data_depositcount,
+  eth1data_depositroot,
+  exec_base_fee_per_gas,
+  exec_block_hash,
+  exec_block_number,
+  exec_extra_data,
+  exec_fee_recipient,
+  exec_gas_limit,
+  exec_gas_used,
+  exec_logs_bloom,
+  exec_parent_hash,
+  exec_random,
+  exec_receipts_root,
+  exec_state_root,
+  exec_timestamp,
+  exec_transactions_count,
+  graffiti,
+  graffiti_text,
+  parentroot,
+  proposer,
+  proposerslashingscount,
+  randaoreveal,
+  signature,
+  slot,
+  stateroot,
+  status,
+  syncaggregate_bits,
+  syncaggregate_participation,
+  syncaggregate_signature,
+  voluntaryexitscount,
+  withdrawalcount,
+  exec_blob_gas_used,
+  exec_excess_blob_gas,
+  blob_kzg_commitments_count,
+  parent_beacon_block_root
+)
+SELECT
+  attestationscount,
+  attesterslashingscount,
+  unhex(CASE WHEN blockroot LIKE '0x%' THEN substr(blockroot, 3) ELSE blockroot END),
+  depositscount,
+  epoch,
+  unhex(CASE WHEN eth1data_blockhash LIKE '0x%' THEN substr(eth1data_blockhash, 3) ELSE eth1data_blockhash END),
+  eth1data_depositcount,
+  unhex(CASE WHEN eth1data_depositroot LIKE '0x%' THEN substr(eth1data_depositroot, 3) ELSE eth1data_depositroot END),
+  exec_base_fee_per_gas,
+  unhex(CASE WHEN exec_block_hash LIKE '0x%' THEN substr(exec_block_hash, 3) ELSE exec_block_hash END),
+  exec_block_number,
+  unhex(CASE WHEN exec_extra_data LIKE '0x%' THEN substr(exec_extra_data, 3) ELSE exec_extra_data END),
+  unhex(CASE WHEN exec_fee_recipient LIKE '0x%' THEN substr(exec_fee_recipient, 3) ELSE exec_fee_recipient END),
+  exec_gas_limit,
+  exec_gas_used,
+  unhex(CASE WHEN exec_logs_bloom LIKE '0x%' THEN substr(exec_logs_bloom, 3) ELSE exec_logs_bloom END),
+  unhex(CASE WHEN exec_parent_hash LIKE '0x%' THEN substr(exec_parent_hash, 3) ELSE exec_parent_hash END),
+  unhex(CASE WHEN exec_random LIKE '0x%' THEN substr(exec_random, 3) ELSE exec_random END),
+  unhex(CASE WHEN exec_receipts_root LIKE '0x%' THEN substr(exec_receipts_root, 3) ELSE exec_receipts_root END),
+  unhex(CASE WHEN exec_state_root LIKE '0x%' THEN substr(exec_state_root, 3) ELSE exec_state_root END),
+  exec_timestamp,
+  exec_transactions_count,
+  unhex(CASE WHEN graffiti LIKE '0x%' THEN substr(graffiti, 3) ELSE graffiti END),
+  graffiti_text,
+  unhex(CASE WHEN parentroot LIKE '0x%' THEN substr(parentroot, 3) ELSE parentroot END),
+  proposer,
+  proposerslashingscount,
+  unhex(CASE WHEN randaoreveal LIKE '0x%' THEN substr(randaoreveal, 3) ELSE randaoreveal END),
+  unhex(CASE WHEN signature LIKE '0x%' THEN substr(signature, 3) ELSE signature END),
+  slot,
+  unhex(CASE WHEN stateroot LIKE '0x%' THEN substr(stateroot, 3) ELSE stateroot END),
+  status,
+  unhex(CASE WHEN syncaggregate_bits LIKE '0x%' THEN substr(syncaggregate_bits, 3) ELSE syncaggregate_bits END),
+  syncaggregate_participation,
+  unhex(CASE WHEN syncaggregate_signature LIKE '0x%' THEN substr(syncaggregate_signature, 3) ELSE syncaggregate_signature END),
+  voluntaryexitscount,
+  withdrawalcount,
+  exec_blob_gas_used,
+  exec_excess_blob_gas,
+  blob_kzg_commitments_count,
+  unhex(CASE WHEN parent_beacon_block_root LIKE '0x%' THEN substr(parent_beacon_block_root, 3) ELSE parent_beacon_block_root END)
+FROM slot_data;
+
+DROP TABLE slot_data;
+ALTER TABLE slot_data_new RENAME TO slot_data;
+
+CREATE INDEX idx_slot_data_epoch_slot ON slot_data (epoch, slot);
+CREATE INDEX idx_slot_data_proposer_slot ON slot_data (proposer, slot);
+CREATE INDEX idx_slot_data_exec_fee_recipient_slot ON slot_data (exec_fee_recipient, slot);
+CREATE INDEX idx_slot_data_exec_block_number ON slot_data (exec_block_number);
+CREATE INDEX idx_slot_data_exec_block_hash ON slot_data (exec_block_hash);
+CREATE INDEX idx_slot_data_blockroot ON slot_data (blockroot);
+
+COMMIT;
+
+PRAGMA foreign_keys = ON;
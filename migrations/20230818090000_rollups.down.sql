@@ -0,0 +1,6 @@
+DROP TRIGGER epoch_data_rollup_update;
+DROP TRIGGER epoch_data_rollup_insert;
+DROP TRIGGER slot_data_rollup_update;
+DROP TRIGGER slot_data_rollup_insert;
+DROP TABLE rollup_daily;
+DROP TABLE rollup_hourly;
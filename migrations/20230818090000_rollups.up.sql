@@ -0,0 +1,285 @@
+-- Hourly and daily aggregates of ingested epochs and slots. Buckets hold running sums
+-- (averages are derived when reading) that triggers keep up to date on every insert and
+-- update, an update subtracting the old row before adding the new one. Pruning raw rows
+-- deliberately leaves the rollups untouched.
+--
+-- Bucket times are the mainnet start times of the epoch or slot: genesis 1606824023,
+-- 12 seconds per slot, 384 per epoch.
+
+CREATE TABLE rollup_hourly (
+  bucket_start INT PRIMARY KEY NOT NULL,
+  epochs INT NOT NULL DEFAULT 0,
+  participation_sum REAL NOT NULL DEFAULT 0,
+  slots INT NOT NULL DEFAULT 0,
+  blocks_proposed INT NOT NULL DEFAULT 0,
+  blocks_missed INT NOT NULL DEFAULT 0,
+  blocks_orphaned INT NOT NULL DEFAULT 0,
+  gas_used INT NOT NULL DEFAULT 0,
+  base_fee_sum INT NOT NULL DEFAULT 0,
+  base_fee_count INT NOT NULL DEFAULT 0,
+  withdrawals INT NOT NULL DEFAULT 0
+);
+
+CREATE TABLE rollup_daily (
+  bucket_start INT PRIMARY KEY NOT NULL,
+  epochs INT NOT NULL DEFAULT 0,
+  participation_sum REAL NOT NULL DEFAULT 0,
+  slots INT NOT NULL DEFAULT 0,
+  blocks_proposed INT NOT NULL DEFAULT 0,
+  blocks_missed INT NOT NULL DEFAULT 0,
+  blocks_orphaned INT NOT NULL DEFAULT 0,
+  gas_used INT NOT NULL DEFAULT 0,
+  base_fee_sum INT NOT NULL DEFAULT 0,
+  base_fee_count INT NOT NULL DEFAULT 0,
+  withdrawals INT NOT NULL DEFAULT 0
+);
+
+CREATE TRIGGER slot_data_rollup_insert AFTER INSERT ON slot_data
+BEGIN
+  INSERT INTO rollup_hourly (bucket_start, slots, blocks_proposed, blocks_missed, blocks_orphaned, gas_used, base_fee_sum, base_fee_count, withdrawals)
+  VALUES (
+    (1606824023 + NEW.slot * 12) / 3600 * 3600,
+    1,
+    (NEW.status = '1'),
+    (NEW.status = '2'),
+    (NEW.status = '3'),
+    COALESCE(NEW.exec_gas_used, 0),
+    COALESCE(NEW.exec_base_fee_per_gas, 0),
+    (NEW.exec_base_fee_per_gas IS NOT NULL),
+    NEW.withdrawalcount
+  )
+  ON CONFLICT (bucket_start) DO UPDATE SET
+    slots = slots + excluded.slots,
+    blocks_proposed = blocks_proposed + excluded.blocks_proposed,
+    blocks_missed = blocks_missed + excluded.blocks_missed,
+    blocks_orphaned = blocks_orphaned + excluded.blocks_orphaned,
+    gas_used = gas_used + excluded.gas_used,
+    base_fee_sum = base_fee_sum + excluded.base_fee_sum,
+    base_fee_count = base_fee_count + excluded.base_fee_count,
+    withdrawals = withdrawals + excluded.withdrawals;
+  INSERT INTO rollup_daily (bucket_start, slots, blocks_proposed, blocks_missed, blocks_orphaned, gas_used, base_fee_sum, base_fee_count, withdrawals)
+  VALUES (
+    (1606824023 + NEW.slot * 12) / 86400 * 86400,
+    1,
+    (NEW.status = '1'),
+    (NEW.status = '2'),
+    (NEW.status = '3'),
+    COALESCE(NEW.exec_gas_used, 0),
+    COALESCE(NEW.exec_base_fee_per_gas, 0),
+    (NEW.exec_base_fee_per_gas IS NOT NULL),
+    NEW.withdrawalcount
+  )
+  ON CONFLICT (bucket_start) DO UPDATE SET
+    slots = slots + excluded.slots,
+    blocks_proposed = blocks_proposed + excluded.blocks_proposed,
+    blocks_missed = blocks_missed + excluded.blocks_missed,
+    blocks_orphaned = blocks_orphaned + excluded.blocks_orphaned,
+    gas_used = gas_used + excluded.gas_used,
+    base_fee_sum = base_fee_sum + excluded.base_fee_sum,
+    base_fee_count = base_fee_count + excluded.base_fee_count,
+    withdrawals = withdrawals + excluded.withdrawals;
+END;
+
+CREATE TRIGGER slot_data_rollup_update AFTER UPDATE ON slot_data
+BEGIN
+  INSERT INTO rollup_hourly (bucket_start, slots, blocks_proposed, blocks_missed, blocks_orphaned, gas_used, base_fee_sum, base_fee_count, withdrawals)
+  VALUES (
+    (1606824023 + OLD.slot * 12) / 3600 * 3600,
+    -1,
+    -(OLD.status = '1'),
+    -(OLD.status = '2'),
+    -(OLD.status = '3'),
+    -COALESCE(OLD.exec_gas_used, 0),
+    -COALESCE(OLD.exec_base_fee_per_gas, 0),
+    -(OLD.exec_base_fee_per_gas IS NOT NULL),
+    -OLD.withdrawalcount
+  )
+  ON CONFLICT (bucket_start) DO UPDATE SET
+    slots = slots + excluded.slots,
+    blocks_proposed = blocks_proposed + excluded.blocks_proposed,
+    blocks_missed = blocks_missed + excluded.blocks_missed,
+    blocks_orphaned = blocks_orphaned + excluded.blocks_orphaned,
+    gas_used = gas_used + excluded.gas_used,
+    base_fee_sum = base_fee_sum + excluded.base_fee_sum,
+    base_fee_count = base_fee_count + excluded.base_fee_count,
+    withdrawals = withdrawals + excluded.withdrawals;
+  INSERT INTO rollup_daily (bucket_start, slots, blocks_proposed, blocks_missed, blocks_orphaned, gas_used, base_fee_sum, base_fee_count, withdrawals)
+  VALUES (
+    (1606824023 + OLD.slot * 12) / 86400 * 86400,
+    -1,
+    -(OLD.status = '1'),
+    -(OLD.status = '2'),
+    -(OLD.status = '3'),
+    -COALESCE(OLD.exec_gas_used, 0),
+    -COALESCE(OLD.exec_base_fee_per_gas, 0),
+    -(OLD.exec_base_fee_per_gas IS NOT NULL),
+    -OLD.withdrawalcount
+  )
+  ON CONFLICT (bucket_start) DO UPDATE SET
+    slots = slots + excluded.slots,
+    blocks_proposed = blocks_proposed + excluded.blocks_proposed,
+    blocks_missed = blocks_missed + excluded.blocks_missed,
+    blocks_orphaned = blocks_orphaned + excluded.blocks_orphaned,
+    gas_used = gas_used + excluded.gas_used,
+    base_fee_sum = base_fee_sum + excluded.base_fee_sum,
+    base_fee_count = base_fee_count + excluded.base_fee_count,
+    withdrawals = withdrawals + excluded.withdrawals;
+  INSERT INTO rollup_hourly (bucket_start, slots, blocks_proposed, blocks_missed, blocks_orphaned, gas_used, base_fee_sum, base_fee_count, withdrawals)
+  VALUES (
+    (1606824023 + NEW.slot * 12) / 3600 * 3600,
+    1,
+    (NEW.status = '1'),
+    (NEW.status = '2'),
+    (NEW.status = '3'),
+    COALESCE(NEW.exec_gas_used, 0),
+    COALESCE(NEW.exec_base_fee_per_gas, 0),
+    (NEW.exec_base_fee_per_gas IS NOT NULL),
+    NEW.withdrawalcount
+  )
+  ON CONFLICT (bucket_start) DO UPDATE SET
+    slots = slots + excluded.slots,
+    blocks_proposed = blocks_proposed + excluded.blocks_proposed,
+    blocks_missed = blocks_missed + excluded.blocks_missed,
+    blocks_orphaned = blocks_orphaned + excluded.blocks_orphaned,
+    gas_used = gas_used + excluded.gas_used,
+    base_fee_sum = base_fee_sum + excluded.base_fee_sum,
+    base_fee_count = base_fee_count + excluded.base_fee_count,
+    withdrawals = withdrawals + excluded.withdrawals;
+  INSERT INTO rollup_daily (bucket_start, slots, blocks_proposed, blocks_missed, blocks_orphaned, gas_used, base_fee_sum, base_fee_count, withdrawals)
+  VALUES (
+    (1606824023 + NEW.slot * 12) / 86400 * 86400,
+    1,
+    (NEW.status = '1'),
+    (NEW.status = '2'),
+    (NEW.status = '3'),
+    COALESCE(NEW.exec_gas_used, 0),
+    COALESCE(NEW.exec_base_fee_per_gas, 0),
+    (NEW.exec_base_fee_per_gas IS NOT NULL),
+    NEW.withdrawalcount
+  )
+  ON CONFLICT (bucket_start) DO UPDATE SET
+    slots = slots + excluded.slots,
+    blocks_proposed = blocks_proposed + excluded.blocks_proposed,
+    blocks_missed = blocks_missed + excluded.blocks_missed,
+    blocks_orphaned = blocks_orphaned + excluded.blocks_orphaned,
+    gas_used = gas_used + excluded.gas_used,
+    base_fee_sum = base_fee_sum + excluded.base_fee_sum,
+    base_fee_count = base_fee_count + excluded.base_fee_count,
+    withdrawals = withdrawals + excluded.withdrawals;
+END;
+
+CREATE TRIGGER epoch_data_rollup_insert AFTER INSERT ON epoch_data
+BEGIN
+  INSERT INTO rollup_hourly (bucket_start, epochs, participation_sum)
+  VALUES (
+    (1606824023 + NEW.epoch * 384) / 3600 * 3600,
+    1,
+    NEW.globalparticipationrate
+  )
+  ON CONFLICT (bucket_start) DO UPDATE SET
+    epochs = epochs + excluded.epochs,
+    participation_sum = participation_sum + excluded.participation_sum;
+  INSERT INTO rollup_daily (bucket_start, epochs, participation_sum)
+  VALUES (
+    (1606824023 + NEW.epoch * 384) / 86400 * 86400,
+    1,
+    NEW.globalparticipationrate
+  )
+  ON CONFLICT (bucket_start) DO UPDATE SET
+    epochs = epochs + excluded.epochs,
+    participation_sum = participation_sum + excluded.participation_sum;
+END;
+
+CREATE TRIGGER epoch_data_rollup_update AFTER UPDATE ON epoch_data
+BEGIN
+  INSERT INTO rollup_hourly (bucket_start, epochs, participation_sum)
+  VALUES (
+    (1606824023 + OLD.epoch * 384) / 3600 * 3600,
+    -1,
+    -OLD.globalparticipationrate
+  )
+  ON CONFLICT (bucket_start) DO UPDATE SET
+    epochs = epochs + excluded.epochs,
+    participation_sum = participation_sum + excluded.participation_sum;
+  INSERT INTO rollup_daily (bucket_start, epochs, participation_sum)
+  VALUES (
+    (1606824023 + OLD.epoch * 384) / 86400 * 86400,
+    -1,
+    -OLD.globalparticipationrate
+  )
+  ON CONFLICT (bucket_start) DO UPDATE SET
+    epochs = epochs + excluded.epochs,
+    participation_sum = participation_sum + excluded.participation_sum;
+  INSERT INTO rollup_hourly (bucket_start, epochs, participation_sum)
+  VALUES (
+    (1606824023 + NEW.epoch * 384) / 3600 * 3600,
+    1,
+    NEW.globalparticipationrate
+  )
+  ON CONFLICT (bucket_start) DO UPDATE SET
+    epochs = epochs + excluded.epochs,
+    participation_sum = participation_sum + excluded.participation_sum;
+  INSERT INTO rollup_daily (bucket_start, epochs, participation_sum)
+  VALUES (
+    (1606824023 + NEW.epoch * 384) / 86400 * 86400,
+    1,
+    NEW.globalparticipationrate
+  )
+  ON CONFLICT (bucket_start) DO UPDATE SET
+    epochs = epochs + excluded.epochs,
+    participation_sum = participation_sum + excluded.participation_sum;
+END;
+
+-- Backfill from the rows already stored.
+
+INSERT INTO rollup_hourly (bucket_start, slots, blocks_proposed, blocks_missed, blocks_orphaned, gas_used, base_fee_sum, base_fee_count, withdrawals)
+SELECT
+  (1606824023 + slot * 12) / 3600 * 3600 AS bucket,
+  COUNT(*),
+  SUM(status = '1'),
+  SUM(status = '2'),
+  SUM(status = '3'),
+  SUM(COALESCE(exec_gas_used, 0)),
+  SUM(COALESCE(exec_base_fee_per_gas, 0)),
+  COUNT(exec_base_fee_per_gas),
+  SUM(withdrawalcount)
+FROM slot_data
+GROUP BY bucket;
+
+INSERT INTO rollup_hourly (bucket_start, epochs, participation_sum)
+SELECT
+  (1606824023 + epoch * 384) / 3600 * 3600 AS bucket,
+  COUNT(*),
+  SUM(globalparticipationrate)
+FROM epoch_data
+WHERE true
+GROUP BY bucket
+ON CONFLICT (bucket_start) DO UPDATE SET
+  epochs = epochs + excluded.epochs,
+  participation_sum = participation_sum + excluded.participation_sum;
+
+INSERT INTO rollup_daily (bucket_start, slots, blocks_proposed, blocks_missed, blocks_orphaned, gas_used, base_fee_sum, base_fee_count, withdrawals)
+SELECT
+  (1606824023 + slot * 12) / 86400 * 86400 AS bucket,
+  COUNT(*),
+  SUM(status = '1'),
+  SUM(status = '2'),
+  SUM(status = '3'),
+  SUM(COALESCE(exec_gas_used, 0)),
+  SUM(COALESCE(exec_base_fee_per_gas, 0)),
+  COUNT(exec_base_fee_per_gas),
+  SUM(withdrawalcount)
+FROM slot_data
+GROUP BY bucket;
+
+INSERT INTO rollup_daily (bucket_start, epochs, participation_sum)
+SELECT
+  (1606824023 + epoch * 384) / 86400 * 86400 AS bucket,
+  COUNT(*),
+  SUM(globalparticipationrate)
+FROM epoch_data
+WHERE true
+GROUP BY bucket
+ON CONFLICT (bucket_start) DO UPDATE SET
+  epochs = epochs + excluded.epochs,
+  participation_sum = participation_sum + excluded.participation_sum;
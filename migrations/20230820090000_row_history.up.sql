@@ -0,0 +1,225 @@
+-- Every stored version of an epoch or slot, written by the store in the same transaction as
+-- the insert or update that produced it (and only when the row actually changed).
+-- `recorded_at` is the ingestion time in unix milliseconds, `source` where the data came
+-- from (beaconchain, import, repair or seed). Rows present before this migration are recorded
+-- once with source 'baseline'.
+
+CREATE TABLE epoch_data_history (
+  history_id INTEGER PRIMARY KEY AUTOINCREMENT,
+  recorded_at INT NOT NULL,
+  source VARCHAR NOT NULL,
+  attestationscount INT NOT NULL,
+  attesterslashingscount INT NOT NULL,
+  averagevalidatorbalance INT NOT NULL,
+  blockscount INT NOT NULL,
+  depositscount INT NOT NULL,
+  eligibleether INT NOT NULL,
+  epoch INT NOT NULL,
+  finalized INT NOT NULL,
+  globalparticipationrate REAL NOT NULL,
+  missedblocks INT NOT NULL,
+  orphanedblocks INT NOT NULL,
+  proposedblocks INT NOT NULL,
+  proposerslashingscount INT NOT NULL,
+  rewards_exported INT NOT NULL,
+  scheduledblocks INT NOT NULL,
+  totalvalidatorbalance INT NOT NULL,
+  ts VARCHAR NOT NULL,
+  validatorscount INT NOT NULL,
+  voluntaryexitscount INT NOT NULL,
+  votedether INT NOT NULL,
+  withdrawalcount INT NOT NULL
+);
+
+CREATE INDEX idx_epoch_data_history_epoch_recorded_at ON epoch_data_history (epoch, recorded_at);
+
+CREATE TABLE slot_data_history (
+  history_id INTEGER PRIMARY KEY AUTOINCREMENT,
+  recorded_at INT NOT NULL,
+  source VARCHAR NOT NULL,
+  attestationscount INT NOT NULL,
+  attesterslashingscount INT NOT NULL,
+  blockroot BLOB NOT NULL,
+  depositscount INT NOT NULL,
+  epoch INT NOT NULL,
+  eth1data_blockhash BLOB NOT NULL,
+  eth1data_depositcount INT NOT NULL,
+  eth1data_depositroot BLOB NOT NULL,
+  exec_base_fee_per_gas INT,
+  exec_block_hash BLOB,
+  exec_block_number INT,
+  exec_extra_data BLOB,
+  exec_fee_recipient BLOB,
+  exec_gas_limit INT,
+  exec_gas_used INT,
+  exec_logs_bloom BLOB,
+  exec_parent_hash BLOB,
+  exec_random BLOB,
+  exec_receipts_root BLOB,
+  exec_state_root BLOB,
+  exec_timestamp INT,
+  exec_transactions_count INT NOT NULL,
+  graffiti BLOB NOT NULL,
+  graffiti_text VARCHAR NOT NULL,
+  parentroot BLOB NOT NULL,
+  proposer INT NOT NULL,
+  proposerslashingscount INT NOT NULL,
+  randaoreveal BLOB NOT NULL,
+  signature BLOB NOT NULL,
+  slot INT NOT NULL,
+  stateroot BLOB NOT NULL,
+  status VARCHAR NOT NULL,
+  syncaggregate_bits BLOB,
+  syncaggregate_participation REAL NOT NULL,
+  syncaggregate_signature BLOB,
+  voluntaryexitscount INT NOT NULL,
+  withdrawalcount INT NOT NULL,
+  exec_blob_gas_used INT,
+  exec_excess_blob_gas INT,
+  blob_kzg_commitments_count INT,
+  parent_beacon_block_root BLOB
+);
+
+CREATE INDEX idx_slot_data_history_slot_recorded_at ON slot_data_history (slot, recorded_at);
+
+INSERT INTO epoch_data_history (
+  recorded_at,
+  source,
+  attestationscount,
+  attesterslashingscount,
+  averagevalidatorbalance,
+  blockscount,
+  depositscount,
+  eligibleether,
+  epoch,
+  finalized,
+  globalparticipationrate,
+  missedblocks,
+  orphanedblocks,
+  proposedblocks,
+  proposerslashingscount,
+  rewards_exported,
+  scheduledblocks,
+  totalvalidatorbalance,
+  ts,
+  validatorscount,
+  voluntaryexitscount,
+  votedether,
+  withdrawalcount
+)
+SELECT
+  CAST(strftime('%s', 'now') AS INT) * 1000,
+  'baseline',
+  attestationscount,
+  attesterslashingscount,
+  averagevalidatorbalance,
+  blockscount,
+  depositscount,
+  eligibleether,
+  epoch,
+  finalized,
+  globalparticipationrate,
+  missedblocks,
+  orphanedblocks,
+  proposedblocks,
+  proposerslashingscount,
+  rewards_exported,
+  scheduledblocks,
+  totalvalidatorbalance,
+  ts,
+  validatorscount,
+  voluntaryexitscount,
+  votedether,
+  withdrawalcount
+FROM epoch_data;
+
+INSERT INTO slot_data_history (
+  recorded_at,
+  source,
+  attestationscount,
+  attesterslashingscount,
+  blockroot,
+  depositscount,
+  epoch,
+  eth1data_blockhash,
+  eth1data_depositcount,
+  eth1data_depositroot,
+  exec_base_fee_per_gas,
+  exec_block_hash,
+  exec_block_number,
+  exec_extra_data,
+  exec_fee_recipient,
+  exec_gas_limit,
+  exec_gas_used,
+  exec_logs_bloom,
+  exec_parent_hash,
+  exec_random,
+  exec_receipts_root,
+  exec_state_root,
+  exec_timestamp,
+  exec_transactions_count,
+  graffiti,
+  graffiti_text,
+  parentroot,
+  proposer,
+  proposerslashingscount,
+  randaoreveal,
+  signature,
+  slot,
+  stateroot,
+  status,
+  syncaggregate_bits,
+  syncaggregate_participation,
+  syncaggregate_signature,
+  voluntaryexitscount,
+  withdrawalcount,
+  exec_blob_gas_used,
+  exec_excess_blob_gas,
+  blob_kzg_commitments_count,
+  parent_beacon_block_root
+)
+SELECT
+  CAST(strftime('%s', 'now') AS INT) * 1000,
+  'baseline',
+  attestationscount,
+  attesterslashingscount,
+  blockroot,
+  depositscount,
+  epoch,
+  eth1data_blockhash,
+  eth1data_depositcount,
+  eth1data_depositroot,
+  exec_base_fee_per_gas,
+  exec_block_hash,
+  exec_block_number,
+  exec_extra_data,
+  exec_fee_recipient,
+  exec_gas_limit,
+  exec_gas_used,
+  exec_logs_bloom,
+  exec_parent_hash,
+  exec_random,
+  exec_receipts_root,
+  exec_state_root,
+  exec_timestamp,
+  exec_transactions_count,
+  graffiti,
+  graffiti_text,
+  parentroot,
+  proposer,
+  proposerslashingscount,
+  randaoreveal,
+  signature,
+  slot,
+  stateroot,
+  status,
+  syncaggregate_bits,
+  syncaggregate_participation,
+  syncaggregate_signature,
+  voluntaryexitscount,
+  withdrawalcount,
+  exec_blob_gas_used,
+  exec_excess_blob_gas,
+  blob_kzg_commitments_count,
+  parent_beacon_block_root
+FROM slot_data;
@@ -0,0 +1,4 @@
+DROP TRIGGER slot_data_graffiti_fts_update;
+DROP TRIGGER slot_data_graffiti_fts_delete;
+DROP TRIGGER slot_data_graffiti_fts_insert;
+DROP TABLE slot_graffiti_fts;
@@ -0,0 +1,31 @@
+-- Full-text index over slot_data.graffiti_text. It is an external-content FTS5 table keyed by
+-- slot, so the text itself is not stored twice; the triggers keep it in sync with inserts,
+-- updates and pruning.
+
+CREATE VIRTUAL TABLE slot_graffiti_fts USING fts5(
+  graffiti_text,
+  content = 'slot_data',
+  content_rowid = 'slot',
+  tokenize = 'unicode61 remove_diacritics 2'
+);
+
+INSERT INTO slot_graffiti_fts (slot_graffiti_fts) VALUES ('rebuild');
+
+CREATE TRIGGER slot_data_graffiti_fts_insert AFTER INSERT ON slot_data
+BEGIN
+  INSERT INTO slot_graffiti_fts (rowid, graffiti_text) VALUES (NEW.slot, NEW.graffiti_text);
+END;
+
+CREATE TRIGGER slot_data_graffiti_fts_delete AFTER DELETE ON slot_data
+BEGIN
+  INSERT INTO slot_graffiti_fts (slot_graffiti_fts, rowid, graffiti_text)
+  VALUES ('delete', OLD.slot, OLD.graffiti_text);
+END;
+
+CREATE TRIGGER slot_data_graffiti_fts_update AFTER UPDATE OF slot, graffiti_text ON slot_data
+WHEN OLD.slot IS NOT NEW.slot OR OLD.graffiti_text IS NOT NEW.graffiti_text
+BEGIN
+  INSERT INTO slot_graffiti_fts (slot_graffiti_fts, rowid, graffiti_text)
+  VALUES ('delete', OLD.slot, OLD.graffiti_text);
+  INSERT INTO slot_graffiti_fts (rowid, graffiti_text) VALUES (NEW.slot, NEW.graffiti_text);
+END;
@@ -0,0 +1,202 @@
+DROP TRIGGER epoch_data_rollup_update;
+DROP TRIGGER epoch_data_rollup_insert;
+DROP TRIGGER slot_data_rollup_update;
+DROP TRIGGER slot_data_rollup_insert;
+
+CREATE TRIGGER slot_data_rollup_insert AFTER INSERT ON slot_data
+BEGIN
+  INSERT INTO rollup_hourly (bucket_start, slots, blocks_proposed, blocks_missed, blocks_orphaned, gas_used, base_fee_sum, base_fee_count, withdrawals)
+  VALUES (
+    (1606824023 + NEW.slot * 12) / 3600 * 3600,
+    1,
+    (NEW.status = '1'),
+    (NEW.status = '2'),
+    (NEW.status = '3'),
+    COALESCE(NEW.exec_gas_used, 0),
+    COALESCE(NEW.exec_base_fee_per_gas, 0),
+    (NEW.exec_base_fee_per_gas IS NOT NULL),
+    NEW.withdrawalcount
+  )
+  ON CONFLICT (bucket_start) DO UPDATE SET
+    slots = slots + excluded.slots,
+    blocks_proposed = blocks_proposed + excluded.blocks_proposed,
+    blocks_missed = blocks_missed + excluded.blocks_missed,
+    blocks_orphaned = blocks_orphaned + excluded.blocks_orphaned,
+    gas_used = gas_used + excluded.gas_used,
+    base_fee_sum = base_fee_sum + excluded.base_fee_sum,
+    base_fee_count = base_fee_count + excluded.base_fee_count,
+    withdrawals = withdrawals + excluded.withdrawals;
+  INSERT INTO rollup_daily (bucket_start, slots, blocks_proposed, blocks_missed, blocks_orphaned, gas_used, base_fee_sum, base_fee_count, withdrawals)
+  VALUES (
+    (1606824023 + NEW.slot * 12) / 86400 * 86400,
+    1,
+    (NEW.status = '1'),
+    (NEW.status = '2'),
+    (NEW.status = '3'),
+    COALESCE(NEW.exec_gas_used, 0),
+    COALESCE(NEW.exec_base_fee_per_gas, 0),
+    (NEW.exec_base_fee_per_gas IS NOT NULL),
+    NEW.withdrawalcount
+  )
+  ON CONFLICT (bucket_start) DO UPDATE SET
+    slots = slots + excluded.slots,
+    blocks_proposed = blocks_proposed + excluded.blocks_proposed,
+    blocks_missed = blocks_missed + excluded.blocks_missed,
+    blocks_orphaned = blocks_orphaned + excluded.blocks_orphaned,
+    gas_used = gas_used + excluded.gas_used,
+    base_fee_sum = base_fee_sum + excluded.base_fee_sum,
+    base_fee_count = base_fee_count + excluded.base_fee_count,
+    withdrawals = withdrawals + excluded.withdrawals;
+END;
+
+CREATE TRIGGER slot_data_rollup_update AFTER UPDATE ON slot_data
+BEGIN
+  INSERT INTO rollup_hourly (bucket_start, slots, blocks_proposed, blocks_missed, blocks_orphaned, gas_used, base_fee_sum, base_fee_count, withdrawals)
+  VALUES (
+    (1606824023 + OLD.slot * 12) / 3600 * 3600,
+    -1,
+    -(OLD.status = '1'),
+    -(OLD.status = '2'),
+    -(OLD.status = '3'),
+    -COALESCE(OLD.exec_gas_used, 0),
+    -COALESCE(OLD.exec_base_fee_per_gas, 0),
+    -(OLD.exec_base_fee_per_gas IS NOT NULL),
+    -OLD.withdrawalcount
+  )
+  ON CONFLICT (bucket_start) DO UPDATE SET
+    slots = slots + excluded.slots,
+    blocks_proposed = blocks_proposed + excluded.blocks_proposed,
+    blocks_missed = blocks_missed + excluded.blocks_missed,
+    blocks_orphaned = blocks_orphaned + excluded.blocks_orphaned,
+    gas_used = gas_used + excluded.gas_used,
+    base_fee_sum = base_fee_sum + excluded.base_fee_sum,
+    base_fee_count = base_fee_count + excluded.base_fee_count,
+    withdrawals = withdrawals + excluded.withdrawals;
+  INSERT INTO rollup_daily (bucket_start, slots, blocks_proposed, blocks_missed, blocks_orphaned, gas_used, base_fee_sum, base_fee_count, withdrawals)
+  VALUES (
+    (1606824023 + OLD.slot * 12) / 86400 * 86400,
+    -1,
+    -(OLD.status = '1'),
+    -(OLD.status = '2'),
+    -(OLD.status = '3'),
+    -COALESCE(OLD.exec_gas_used, 0),
+    -COALESCE(OLD.exec_base_fee_per_gas, 0),
+    -(OLD.exec_base_fee_per_gas IS NOT NULL),
+    -OLD.withdrawalcount
+  )
+  ON CONFLICT (bucket_start) DO UPDATE SET
+    slots = slots + excluded.slots,
+    blocks_proposed = blocks_proposed + excluded.blocks_proposed,
+    blocks_missed = blocks_missed + excluded.blocks_missed,
+    blocks_orphaned = blocks_orphaned + excluded.blocks_orphaned,
+    gas_used = gas_used + excluded.gas_used,
+    base_fee_sum = base_fee_sum + excluded.base_fee_sum,
+    base_fee_count = base_fee_count + excluded.base_fee_count,
+    withdrawals = withdrawals + excluded.withdrawals;
+  INSERT INTO rollup_hourly (bucket_start, slots, blocks_proposed, blocks_missed, blocks_orphaned, gas_used, base_fee_sum, base_fee_count, withdrawals)
+  VALUES (
+    (1606824023 + NEW.slot * 12) / 3600 * 3600,
+    1,
+    (NEW.status = '1'),
+    (NEW.status = '2'),
+    (NEW.status = '3'),
+    COALESCE(NEW.exec_gas_used, 0),
+    COALESCE(NEW.exec_base_fee_per_gas, 0),
+    (NEW.exec_base_fee_per_gas IS NOT NULL),
+    NEW.withdrawalcount
+  )
+  ON CONFLICT (bucket_start) DO UPDATE SET
+    slots = slots + excluded.slots,
+    blocks_proposed = blocks_proposed + excluded.blocks_proposed,
+    blocks_missed = blocks_missed + excluded.blocks_missed,
+    blocks_orphaned = blocks_orphaned + excluded.blocks_orphaned,
+    gas_used = gas_used + excluded.gas_used,
+    base_fee_sum = base_fee_sum + excluded.base_fee_sum,
+    base_fee_count = base_fee_count + excluded.base_fee_count,
+    withdrawals = withdrawals + excluded.withdrawals;
+  INSERT INTO rollup_daily (bucket_start, slots, blocks_proposed, blocks_missed, blocks_orphaned, gas_used, base_fee_sum, base_fee_count, withdrawals)
+  VALUES (
+    (1606824023 + NEW.slot * 12) / 86400 * 86400,
+    1,
+    (NEW.status = '1'),
+    (NEW.status = '2'),
+    (NEW.status = '3'),
+    COALESCE(NEW.exec_gas_used, 0),
+    COALESCE(NEW.exec_base_fee_per_gas, 0),
+    (NEW.exec_base_fee_per_gas IS NOT NULL),
+    NEW.withdrawalcount
+  )
+  ON CONFLICT (bucket_start) DO UPDATE SET
+    slots = slots + excluded.slots,
+    blocks_proposed = blocks_proposed + excluded.blocks_proposed,
+    blocks_missed = blocks_missed + excluded.blocks_missed,
+    blocks_orphaned = blocks_orphaned + excluded.blocks_orphaned,
+    gas_used = gas_used + excluded.gas_used,
+    base_fee_sum = base_fee_sum + excluded.base_fee_sum,
+    base_fee_count = base_fee_count + excluded.base_fee_count,
+    withdrawals = withdrawals + excluded.withdrawals;
+END;
+
+CREATE TRIGGER epoch_data_rollup_insert AFTER INSERT ON epoch_data
+BEGIN
+  INSERT INTO rollup_hourly (bucket_start, epochs, participation_sum)
+  VALUES (
+    (1606824023 + NEW.epoch * 384) / 3600 * 3600,
+    1,
+    NEW.globalparticipationrate
+  )
+  ON CONFLICT (bucket_start) DO UPDATE SET
+    epochs = epochs + excluded.epochs,
+    participation_sum = participation_sum + excluded.participation_sum;
+  INSERT INTO rollup_daily (bucket_start, epochs, participation_sum)
+  VALUES (
+    (1606824023 + NEW.epoch * 384) / 86400 * 86400,
+    1,
+    NEW.globalparticipationrate
+  )
+  ON CONFLICT (bucket_start) DO UPDATE SET
+    epochs = epochs + excluded.epochs,
+    participation_sum = participation_sum + excluded.participation_sum;
+END;
+
+CREATE TRIGGER epoch_data_rollup_update AFTER UPDATE ON epoch_data
+BEGIN
+  INSERT INTO rollup_hourly (bucket_start, epochs, participation_sum)
+  VALUES (
+    (1606824023 + OLD.epoch * 384) / 3600 * 3600,
+    -1,
+    -OLD.globalparticipationrate
+  )
+  ON CONFLICT (bucket_start) DO UPDATE SET
+    epochs = epochs + excluded.epochs,
+    participation_sum = participation_sum + excluded.participation_sum;
+  INSERT INTO rollup_daily (bucket_start, epochs, participation_sum)
+  VALUES (
+    (1606824023 + OLD.epoch * 384) / 86400 * 86400,
+    -1,
+    -OLD.globalparticipationrate
+  )
+  ON CONFLICT (bucket_start) DO UPDATE SET
+    epochs = epochs + excluded.epochs,
+    participation_sum = participation_sum + excluded.participation_sum;
+  INSERT INTO rollup_hourly (bucket_start, epochs, participation_sum)
+  VALUES (
+    (1606824023 + NEW.epoch * 384) / 3600 * 3600,
+    1,
+    NEW.globalparticipationrate
+  )
+  ON CONFLICT (bucket_start) DO UPDATE SET
+    epochs = epochs + excluded.epochs,
+    participation_sum = participation_sum + excluded.participation_sum;
+  INSERT INTO rollup_daily (bucket_start, epochs, participation_sum)
+  VALUES (
+    (1606824023 + NEW.epoch * 384) / 86400 * 86400,
+    1,
+    NEW.globalparticipationrate
+  )
+  ON CONFLICT (bucket_start) DO UPDATE SET
+    epochs = epochs + excluded.epochs,
+    participation_sum = participation_sum + excluded.participation_sum;
+END;
+
+DROP TABLE chain_config;
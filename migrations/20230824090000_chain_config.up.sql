@@ -0,0 +1,218 @@
+-- Records which network a database holds. rish keeps one database per network and refuses to
+-- open a database with a different network than the one it was started for. The rollup
+-- triggers take their bucket times from here instead of assuming mainnet.
+--
+-- Databases with data written before this migration were mainnet-only.
+
+CREATE TABLE chain_config (
+  id INTEGER PRIMARY KEY NOT NULL CHECK (id = 1),
+  network VARCHAR NOT NULL,
+  genesis_timestamp INT NOT NULL,
+  seconds_per_slot INT NOT NULL,
+  slots_per_epoch INT NOT NULL
+);
+
+INSERT INTO chain_config (id, network, genesis_timestamp, seconds_per_slot, slots_per_epoch)
+SELECT 1, 'mainnet', 1606824023, 12, 32
+WHERE EXISTS (SELECT 1 FROM epoch_data) OR EXISTS (SELECT 1 FROM slot_data);
+
+DROP TRIGGER epoch_data_rollup_update;
+DROP TRIGGER epoch_data_rollup_insert;
+DROP TRIGGER slot_data_rollup_update;
+DROP TRIGGER slot_data_rollup_insert;
+
+CREATE TRIGGER slot_data_rollup_insert AFTER INSERT ON slot_data
+BEGIN
+  INSERT INTO rollup_hourly (bucket_start, slots, blocks_proposed, blocks_missed, blocks_orphaned, gas_used, base_fee_sum, base_fee_count, withdrawals)
+  VALUES (
+    ((SELECT genesis_timestamp FROM chain_config) + NEW.slot * (SELECT seconds_per_slot FROM chain_config)) / 3600 * 3600,
+    1,
+    (NEW.status = '1'),
+    (NEW.status = '2'),
+    (NEW.status = '3'),
+    COALESCE(NEW.exec_gas_used, 0),
+    COALESCE(NEW.exec_base_fee_per_gas, 0),
+    (NEW.exec_base_fee_per_gas IS NOT NULL),
+    NEW.withdrawalcount
+  )
+  ON CONFLICT (bucket_start) DO UPDATE SET
+    slots = slots + excluded.slots,
+    blocks_proposed = blocks_proposed + excluded.blocks_proposed,
+    blocks_missed = blocks_missed + excluded.blocks_missed,
+    blocks_orphaned = blocks_orphaned + excluded.blocks_orphaned,
+    gas_used = gas_used + excluded.gas_used,
+    base_fee_sum = base_fee_sum + excluded.base_fee_sum,
+    base_fee_count = base_fee_count + excluded.base_fee_count,
+    withdrawals = withdrawals + excluded.withdrawals;
+  INSERT INTO rollup_daily (bucket_start, slots, blocks_proposed, blocks_missed, blocks_orphaned, gas_used, base_fee_sum, base_fee_count, withdrawals)
+  VALUES (
+    ((SELECT genesis_timestamp FROM chain_config) + NEW.slot * (SELECT seconds_per_slot FROM chain_config)) / 86400 * 86400,
+    1,
+    (NEW.status = '1'),
+    (NEW.status = '2'),
+    (NEW.status = '3'),
+    COALESCE(NEW.exec_gas_used, 0),
+    COALESCE(NEW.exec_base_fee_per_gas, 0),
+    (NEW.exec_base_fee_per_gas IS NOT NULL),
+    NEW.withdrawalcount
+  )
+  ON CONFLICT (bucket_start) DO UPDATE SET
+    slots = slots + excluded.slots,
+    blocks_proposed = blocks_proposed + excluded.blocks_proposed,
+    blocks_missed = blocks_missed + excluded.blocks_missed,
+    blocks_orphaned = blocks_orphaned + excluded.blocks_orphaned,
+    gas_used = gas_used + excluded.gas_used,
+    base_fee_sum = base_fee_sum + excluded.base_fee_sum,
+    base_fee_count = base_fee_count + excluded.base_fee_count,
+    withdrawals = withdrawals + excluded.withdrawals;
+END;
+
+CREATE TRIGGER slot_data_rollup_update AFTER UPDATE ON slot_data
+BEGIN
+  INSERT INTO rollup_hourly (bucket_start, slots, blocks_proposed, blocks_missed, blocks_orphaned, gas_used, base_fee_sum, base_fee_count, withdrawals)
+  VALUES (
+    ((SELECT genesis_timestamp FROM chain_config) + OLD.slot * (SELECT seconds_per_slot FROM chain_config)) / 3600 * 3600,
+    -1,
+    -(OLD.status = '1'),
+    -(OLD.status = '2'),
+    -(OLD.status = '3'),
+    -COALESCE(OLD.exec_gas_used, 0),
+    -COALESCE(OLD.exec_base_fee_per_gas, 0),
+    -(OLD.exec_base_fee_per_gas IS NOT NULL),
+    -OLD.withdrawalcount
+  )
+  ON CONFLICT (bucket_start) DO UPDATE SET
+    slots = slots + excluded.slots,
+    blocks_proposed = blocks_proposed + excluded.blocks_proposed,
+    blocks_missed = blocks_missed + excluded.blocks_missed,
+    blocks_orphaned = blocks_orphaned + excluded.blocks_orphaned,
+    gas_used = gas_used + excluded.gas_used,
+    base_fee_sum = base_fee_sum + excluded.base_fee_sum,
+    base_fee_count = base_fee_count + excluded.base_fee_count,
+    withdrawals = withdrawals + excluded.withdrawals;
+  INSERT INTO rollup_daily (bucket_start, slots, blocks_proposed, blocks_missed, blocks_orphaned, gas_used, base_fee_sum, base_fee_count, withdrawals)
+  VALUES (
+    ((SELECT genesis_timestamp FROM chain_config) + OLD.slot * (SELECT seconds_per_slot FROM chain_config)) / 86400 * 86400,
+    -1,
+    -(OLD.status = '1'),
+    -(OLD.status = '2'),
+    -(OLD.status = '3'),
+    -COALESCE(OLD.exec_gas_used, 0),
+    -COALESCE(OLD.exec_base_fee_per_gas, 0),
+    -(OLD.exec_base_fee_per_gas IS NOT NULL),
+    -OLD.withdrawalcount
+  )
+  ON CONFLICT (bucket_start) DO UPDATE SET
+    slots = slots + excluded.slots,
+    blocks_proposed = blocks_proposed + excluded.blocks_proposed,
+    blocks_missed = blocks_missed + excluded.blocks_missed,
+    blocks_orphaned = blocks_orphaned + excluded.blocks_orphaned,
+    gas_used = gas_used + excluded.gas_used,
+    base_fee_sum = base_fee_sum + excluded.base_fee_sum,
+    base_fee_count = base_fee_count + excluded.base_fee_count,
+    withdrawals = withdrawals + excluded.withdrawals;
+  INSERT INTO rollup_hourly (bucket_start, slots, blocks_proposed, blocks_missed, blocks_orphaned, gas_used, base_fee_sum, base_fee_count, withdrawals)
+  VALUES (
+    ((SELECT genesis_timestamp FROM chain_config) + NEW.slot * (SELECT seconds_per_slot FROM chain_config)) / 3600 * 3600,
+    1,
+    (NEW.status = '1'),
+    (NEW.status = '2'),
+    (NEW.status = '3'),
+    COALESCE(NEW.exec_gas_used, 0),
+    COALESCE(NEW.exec_base_fee_per_gas, 0),
+    (NEW.exec_base_fee_per_gas IS NOT NULL),
+    NEW.withdrawalcount
+  )
+  ON CONFLICT (bucket_start) DO UPDATE SET
+    slots = slots + excluded.slots,
+    blocks_proposed = blocks_proposed + excluded.blocks_proposed,
+    blocks_missed = blocks_missed + excluded.blocks_missed,
+    blocks_orphaned = blocks_orphaned + excluded.blocks_orphaned,
+    gas_used = gas_used + excluded.gas_used,
+    base_fee_sum = base_fee_sum + excluded.base_fee_sum,
+    base_fee_count = base_fee_count + excluded.base_fee_count,
+    withdrawals = withdrawals + excluded.withdrawals;
+  INSERT INTO rollup_daily (bucket_start, slots, blocks_proposed, blocks_missed, blocks_orphaned, gas_used, base_fee_sum, base_fee_count, withdrawals)
+  VALUES (
+    ((SELECT genesis_timestamp FROM chain_config) + NEW.slot * (SELECT seconds_per_slot FROM chain_config)) / 86400 * 86400,
+    1,
+    (NEW.status = '1'),
+    (NEW.status = '2'),
+    (NEW.status = '3'),
+    COALESCE(NEW.exec_gas_used, 0),
+    COALESCE(NEW.exec_base_fee_per_gas, 0),
+    (NEW.exec_base_fee_per_gas IS NOT NULL),
+    NEW.withdrawalcount
+  )
+  ON CONFLICT (bucket_start) DO UPDATE SET
+    slots = slots + excluded.slots,
+    blocks_proposed = blocks_proposed + excluded.blocks_proposed,
+    blocks_missed = blocks_missed + excluded.blocks_missed,
+    blocks_orphaned = blocks_orphaned + excluded.blocks_orphaned,
+    gas_used = gas_used + excluded.gas_used,
+    base_fee_sum = base_fee_sum + excluded.base_fee_sum,
+    base_fee_count = base_fee_count + excluded.base_fee_count,
+    withdrawals = withdrawals + excluded.withdrawals;
+END;
+
+CREATE TRIGGER epoch_data_rollup_insert AFTER INSERT ON epoch_data
+BEGIN
+  INSERT INTO rollup_hourly (bucket_start, epochs, participation_sum)
+  VALUES (
+    ((SELECT genesis_timestamp FROM chain_config) + NEW.epoch * (SELECT seconds_per_slot * slots_per_epoch FROM chain_config)) / 3600 * 3600,
+    1,
+    NEW.globalparticipationrate
+  )
+  ON CONFLICT (bucket_start) DO UPDATE SET
+    epochs = epochs + excluded.epochs,
+    participation_sum = participation_sum + excluded.participation_sum;
+  INSERT INTO rollup_daily (bucket_start, epochs, participation_sum)
+  VALUES (
+    ((SELECT genesis_timestamp FROM chain_config) + NEW.epoch * (SELECT seconds_per_slot * slots_per_epoch FROM chain_config)) / 86400 * 86400,
+    1,
+    NEW.globalparticipationrate
+  )
+  ON CONFLICT (bucket_start) DO UPDATE SET
+    epochs = epochs + excluded.epochs,
+    participation_sum = participation_sum + excluded.participation_sum;
+END;
+
+CREATE TRIGGER epoch_data_rollup_update AFTER UPDATE ON epoch_data
+BEGIN
+  INSERT INTO rollup_hourly (bucket_start, epochs, participation_sum)
+  VALUES (
+    ((SELECT genesis_timestamp FROM chain_config) + OLD.epoch * (SELECT seconds_per_slot * slots_per_epoch FROM chain_config)) / 3600 * 3600,
+    -1,
+    -OLD.globalparticipationrate
+  )
+  ON CONFLICT (bucket_start) DO UPDATE SET
+    epochs = epochs + excluded.epochs,
+    participation_sum = participation_sum + excluded.participation_sum;
+  INSERT INTO rollup_daily (bucket_start, epochs, participation_sum)
+  VALUES (
+    ((SELECT genesis_timestamp FROM chain_config) + OLD.epoch * (SELECT seconds_per_slot * slots_per_epoch FROM chain_config)) / 86400 * 86400,
+    -1,
+    -OLD.globalparticipationrate
+  )
+  ON CONFLICT (bucket_start) DO UPDATE SET
+    epochs = epochs + excluded.epochs,
+    participation_sum = participation_sum + excluded.participation_sum;
+  INSERT INTO rollup_hourly (bucket_start, epochs, participation_sum)
+  VALUES (
+    ((SELECT genesis_timestamp FROM chain_config) + NEW.epoch * (SELECT seconds_per_slot * slots_per_epoch FROM chain_config)) / 3600 * 3600,
+    1,
+    NEW.globalparticipationrate
+  )
+  ON CONFLICT (bucket_start) DO UPDATE SET
+    epochs = epochs + excluded.epochs,
+    participation_sum = participation_sum + excluded.participation_sum;
+  INSERT INTO rollup_daily (bucket_start, epochs, participation_sum)
+  VALUES (
+    ((SELECT genesis_timestamp FROM chain_config) + NEW.epoch * (SELECT seconds_per_slot * slots_per_epoch FROM chain_config)) / 86400 * 86400,
+    1,
+    NEW.globalparticipationrate
+  )
+  ON CONFLICT (bucket_start) DO UPDATE SET
+    epochs = epochs + excluded.epochs,
+    participation_sum = participation_sum + excluded.participation_sum;
+END;
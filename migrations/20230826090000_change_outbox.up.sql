@@ -0,0 +1,14 @@
+-- Change-data-capture outbox: one event per insert or update of epoch_data and slot_data,
+-- written by the store in the same transaction as the change. `seq` is AUTOINCREMENT so it
+-- is never reused, and the single writer connection commits events in `seq` order, so a
+-- consumer reading `seq > cursor` never misses one. `kind` is inserted, updated, reorged
+-- (slot block replaced or orphaned) or finalized, `payload` the row as JSON.
+
+CREATE TABLE change_outbox (
+  seq INTEGER PRIMARY KEY AUTOINCREMENT,
+  recorded_at INT NOT NULL,
+  entity VARCHAR NOT NULL,
+  key INT NOT NULL,
+  kind VARCHAR NOT NULL,
+  payload TEXT NOT NULL
+);
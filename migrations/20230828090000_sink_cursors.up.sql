@@ -0,0 +1,8 @@
+-- Last change_outbox `seq` each output sink has delivered. The relay only advances it after
+-- the sink acknowledged the batch, so a restart resumes where delivery stopped.
+
+CREATE TABLE sink_cursors (
+  sink VARCHAR PRIMARY KEY NOT NULL,
+  seq INT NOT NULL,
+  updated_at INT NOT NULL
+);
@@ -0,0 +1,49 @@
+DROP TRIGGER slot_data_rollup_insert;
+
+CREATE TRIGGER slot_data_rollup_insert AFTER INSERT ON slot_data
+BEGIN
+  INSERT INTO rollup_hourly (bucket_start, slots, blocks_proposed, blocks_missed, blocks_orphaned, gas_used, base_fee_sum, base_fee_count, withdrawals)
+  VALUES (
+    ((SELECT genesis_timestamp FROM chain_config) + NEW.slot * (SELECT seconds_per_slot FROM chain_config)) / 3600 * 3600,
+    1,
+    (NEW.status = '1'),
+    (NEW.status = '2'),
+    (NEW.status = '3'),
+    COALESCE(NEW.exec_gas_used, 0),
+    COALESCE(NEW.exec_base_fee_per_gas, 0),
+    (NEW.exec_base_fee_per_gas IS NOT NULL),
+    NEW.withdrawalcount
+  )
+  ON CONFLICT (bucket_start) DO UPDATE SET
+    slots = slots + excluded.slots,
+    blocks_proposed = blocks_proposed + excluded.blocks_proposed,
+    blocks_missed = blocks_missed + excluded.blocks_missed,
+    blocks_orphaned = blocks_orphaned + excluded.blocks_orphaned,
+    gas_used = gas_used + excluded.gas_used,
+    base_fee_sum = base_fee_sum + excluded.base_fee_sum,
+    base_fee_count = base_fee_count + excluded.base_fee_count,
+    withdrawals = withdrawals + excluded.withdrawals;
+  INSERT INTO rollup_daily (bucket_start, slots, blocks_proposed, blocks_missed, blocks_orphaned, gas_used, base_fee_sum, base_fee_count, withdrawals)
+  VALUES (
+    ((SELECT genesis_timestamp FROM chain_config) + NEW.slot * (SELECT seconds_per_slot FROM chain_config)) / 86400 * 86400,
+    1,
+    (NEW.status = '1'),
+    (NEW.status = '2'),
+    (NEW.status = '3'),
+    COALESCE(NEW.exec_gas_used, 0),
+    COALESCE(NEW.exec_base_fee_per_gas, 0),
+    (NEW.exec_base_fee_per_gas IS NOT NULL),
+    NEW.withdrawalcount
+  )
+  ON CONFLICT (bucket_start) DO UPDATE SET
+    slots = slots + excluded.slots,
+    blocks_proposed = blocks_proposed + excluded.blocks_proposed,
+    blocks_missed = blocks_missed + excluded.blocks_missed,
+    blocks_orphaned = blocks_orphaned + excluded.blocks_orphaned,
+    gas_used = gas_used + excluded.gas_used,
+    base_fee_sum = base_fee_sum + excluded.base_fee_sum,
+    base_fee_count = base_fee_count + excluded.base_fee_count,
+    withdrawals = withdrawals + excluded.withdrawals;
+END;
+
+ALTER TABLE chain_config DROP COLUMN pruned_before_slot;
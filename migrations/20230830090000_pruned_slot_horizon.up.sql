@@ -0,0 +1,61 @@
+-- Pruning deletes slots but keeps their share of the rollups. A pruned slot that is fetched and
+-- inserted again must not be counted a second time, so chain_config records the slot below
+-- which everything was pruned and the insert trigger skips those slots.
+--
+-- Databases pruned before this migration start from their earliest stored slot if the rollups
+-- count more slots than are stored.
+
+ALTER TABLE chain_config ADD COLUMN pruned_before_slot INT NOT NULL DEFAULT 0;
+
+UPDATE chain_config
+SET pruned_before_slot = COALESCE((SELECT MIN(slot) FROM slot_data), 0)
+WHERE (SELECT COALESCE(SUM(slots), 0) FROM rollup_daily) > (SELECT COUNT(*) FROM slot_data);
+
+DROP TRIGGER slot_data_rollup_insert;
+
+CREATE TRIGGER slot_data_rollup_insert AFTER INSERT ON slot_data
+WHEN NEW.slot >= COALESCE((SELECT pruned_before_slot FROM chain_config), 0)
+BEGIN
+  INSERT INTO rollup_hourly (bucket_start, slots, blocks_proposed, blocks_missed, blocks_orphaned, gas_used, base_fee_sum, base_fee_count, withdrawals)
+  VALUES (
+    ((SELECT genesis_timestamp FROM chain_config) + NEW.slot * (SELECT seconds_per_slot FROM chain_config)) / 3600 * 3600,
+    1,
+    (NEW.status = '1'),
+    (NEW.status = '2'),
+    (NEW.status = '3'),
+    COALESCE(NEW.exec_gas_used, 0),
+    COALESCE(NEW.exec_base_fee_per_gas, 0),
+    (NEW.exec_base_fee_per_gas IS NOT NULL),
+    NEW.withdrawalcount
+  )
+  ON CONFLICT (bucket_start) DO UPDATE SET
+    slots = slots + excluded.slots,
+    blocks_proposed = blocks_proposed + excluded.blocks_proposed,
+    blocks_missed = blocks_missed + excluded.blocks_missed,
+    blocks_orphaned = blocks_orphaned + excluded.blocks_orphaned,
+    gas_used = gas_used + excluded.gas_used,
+    base_fee_sum = base_fee_sum + excluded.base_fee_sum,
+    base_fee_count = base_fee_count + excluded.base_fee_count,
+    withdrawals = withdrawals + excluded.withdrawals;
+  INSERT INTO rollup_daily (bucket_start, slots, blocks_proposed, blocks_missed, blocks_orphaned, gas_used, base_fee_sum, base_fee_count, withdrawals)
+  VALUES (
+    ((SELECT genesis_timestamp FROM chain_config) + NEW.slot * (SELECT seconds_per_slot FROM chain_config)) / 86400 * 86400,
+    1,
+    (NEW.status = '1'),
+    (NEW.status = '2'),
+    (NEW.status = '3'),
+    COALESCE(NEW.exec_gas_used, 0),
+    COALESCE(NEW.exec_base_fee_per_gas, 0),
+    (NEW.exec_base_fee_per_gas IS NOT NULL),
+    NEW.withdrawalcount
+  )
+  ON CONFLICT (bucket_start) DO UPDATE SET
+    slots = slots + excluded.slots,
+    blocks_proposed = blocks_proposed + excluded.blocks_proposed,
+    blocks_missed = blocks_missed + excluded.blocks_missed,
+    blocks_orphaned = blocks_orphaned + excluded.blocks_orphaned,
+    gas_used = gas_used + excluded.gas_used,
+    base_fee_sum = base_fee_sum + excluded.base_fee_sum,
+    base_fee_count = base_fee_count + excluded.base_fee_count,
+    withdrawals = withdrawals + excluded.withdrawals;
+END;
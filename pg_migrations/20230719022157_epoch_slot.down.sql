@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP TABLE epoch_data;
+DROP TABLE slot_data;
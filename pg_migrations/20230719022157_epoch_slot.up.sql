@@ -0,0 +1,64 @@
+-- Add migration script here
+CREATE TABLE epoch_data (
+  attestationscount BIGINT NOT NULL,
+  attesterslashingscount BIGINT NOT NULL,
+  averagevalidatorbalance BIGINT NOT NULL,
+  blockscount BIGINT NOT NULL,
+  depositscount BIGINT NOT NULL,
+  eligibleether BIGINT NOT NULL,
+  epoch BIGINT PRIMARY KEY NOT NULL,
+  finalized BOOLEAN NOT NULL,
+  globalparticipationrate DOUBLE PRECISION NOT NULL,
+  missedblocks BIGINT NOT NULL,
+  orphanedblocks BIGINT NOT NULL,
+  proposedblocks BIGINT NOT NULL,
+  proposerslashingscount BIGINT NOT NULL,
+  rewards_exported BOOLEAN NOT NULL,
+  scheduledblocks BIGINT NOT NULL,
+  totalvalidatorbalance BIGINT NOT NULL,
+  ts TIMESTAMPTZ NOT NULL,
+  validatorscount BIGINT NOT NULL,
+  voluntaryexitscount BIGINT NOT NULL,
+  votedether BIGINT NOT NULL,
+  withdrawalcount BIGINT NOT NULL
+);
+
+CREATE TABLE slot_data (
+  attestationscount BIGINT NOT NULL,
+  attesterslashingscount BIGINT NOT NULL,
+  blockroot BYTEA NOT NULL,
+  depositscount BIGINT NOT NULL,
+  epoch BIGINT NOT NULL,
+  eth1data_blockhash BYTEA NOT NULL,
+  eth1data_depositcount BIGINT NOT NULL,
+  eth1data_depositroot BYTEA NOT NULL,
+  exec_base_fee_per_gas BIGINT,
+  exec_block_hash BYTEA,
+  exec_block_number BIGINT,
+  exec_extra_data BYTEA,
+  exec_fee_recipient BYTEA,
+  exec_gas_limit BIGINT,
+  exec_gas_used BIGINT,
+  exec_logs_bloom BYTEA,
+  exec_parent_hash BYTEA,
+  exec_random BYTEA,
+  exec_receipts_root BYTEA,
+  exec_state_root BYTEA,
+  exec_timestamp BIGINT,
+  exec_transactions_count BIGINT NOT NULL,
+  graffiti BYTEA NOT NULL,
+  graffiti_text TEXT NOT NULL,
+  parentroot BYTEA NOT NULL,
+  proposer BIGINT NOT NULL,
+  proposerslashingscount BIGINT NOT NULL,
+  randaoreveal BYTEA NOT NULL,
+  signature BYTEA NOT NULL,
+  slot BIGINT PRIMARY KEY NOT NULL,
+  stateroot BYTEA NOT NULL,
+  status VARCHAR NOT NULL,
+  syncaggregate_bits BYTEA,
+  syncaggregate_participation DOUBLE PRECISION NOT NULL,
+  syncaggregate_signature BYTEA,
+  voluntaryexitscount BIGINT NOT NULL,
+  withdrawalcount BIGINT NOT NULL
+);
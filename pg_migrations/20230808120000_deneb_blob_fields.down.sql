@@ -0,0 +1,6 @@
+-- Add down migration script here
+DROP TABLE blob_sidecar_summary;
+ALTER TABLE slot_data DROP COLUMN parent_beacon_block_root;
+ALTER TABLE slot_data DROP COLUMN blob_kzg_commitments_count;
+ALTER TABLE slot_data DROP COLUMN exec_excess_blob_gas;
+ALTER TABLE slot_data DROP COLUMN exec_blob_gas_used;
@@ -0,0 +1,14 @@
+-- Add migration script here
+ALTER TABLE slot_data ADD COLUMN exec_blob_gas_used BIGINT;
+ALTER TABLE slot_data ADD COLUMN exec_excess_blob_gas BIGINT;
+ALTER TABLE slot_data ADD COLUMN blob_kzg_commitments_count BIGINT;
+ALTER TABLE slot_data ADD COLUMN parent_beacon_block_root BYTEA;
+
+CREATE TABLE blob_sidecar_summary (
+  slot BIGINT PRIMARY KEY NOT NULL REFERENCES slot_data (slot),
+  epoch BIGINT NOT NULL,
+  blob_count BIGINT NOT NULL,
+  blob_gas_used BIGINT NOT NULL,
+  excess_blob_gas BIGINT NOT NULL,
+  total_blob_size BIGINT NOT NULL
+);
@@ -0,0 +1,8 @@
+-- Add migration script here
+CREATE INDEX idx_slot_data_epoch_slot ON slot_data (epoch, slot);
+CREATE INDEX idx_slot_data_proposer_slot ON slot_data (proposer, slot);
+CREATE INDEX idx_slot_data_exec_fee_recipient_slot ON slot_data (exec_fee_recipient, slot);
+CREATE INDEX idx_slot_data_exec_block_number ON slot_data (exec_block_number);
+CREATE INDEX idx_slot_data_exec_block_hash ON slot_data (exec_block_hash);
+CREATE INDEX idx_slot_data_blockroot ON slot_data (blockroot);
+CREATE INDEX idx_epoch_data_finalized_epoch ON epoch_data (finalized, epoch);
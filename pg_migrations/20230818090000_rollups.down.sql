@@ -0,0 +1,10 @@
+DROP TRIGGER epoch_data_rollup ON epoch_data;
+DROP TRIGGER slot_data_rollup ON slot_data;
+DROP FUNCTION epoch_data_rollup();
+DROP FUNCTION slot_data_rollup();
+DROP FUNCTION add_to_rollups(
+  BIGINT, BIGINT, BIGINT, DOUBLE PRECISION, BIGINT, BIGINT, BIGINT, BIGINT, BIGINT, BIGINT,
+  BIGINT, BIGINT
+);
+DROP TABLE rollup_daily;
+DROP TABLE rollup_hourly;
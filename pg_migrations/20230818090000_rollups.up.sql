@@ -0,0 +1,128 @@
+-- Hourly and daily aggregates of ingested epochs and slots, see the SQLite migration of the
+-- same version. Buckets hold running sums that the triggers below keep up to date, an update
+-- subtracting the old row before adding the new one. Pruning leaves the rollups untouched.
+
+CREATE TABLE rollup_hourly (
+  bucket_start BIGINT PRIMARY KEY NOT NULL,
+  epochs BIGINT NOT NULL DEFAULT 0,
+  participation_sum DOUBLE PRECISION NOT NULL DEFAULT 0,
+  slots BIGINT NOT NULL DEFAULT 0,
+  blocks_proposed BIGINT NOT NULL DEFAULT 0,
+  blocks_missed BIGINT NOT NULL DEFAULT 0,
+  blocks_orphaned BIGINT NOT NULL DEFAULT 0,
+  gas_used BIGINT NOT NULL DEFAULT 0,
+  base_fee_sum BIGINT NOT NULL DEFAULT 0,
+  base_fee_count BIGINT NOT NULL DEFAULT 0,
+  withdrawals BIGINT NOT NULL DEFAULT 0
+);
+
+CREATE TABLE rollup_daily (LIKE rollup_hourly INCLUDING ALL);
+
+-- Adds one row's contribution (or removes it, with d_sign = -1) to both rollup tables.
+CREATE FUNCTION add_to_rollups(
+  d_ts BIGINT,
+  d_sign BIGINT,
+  d_epochs BIGINT,
+  d_participation DOUBLE PRECISION,
+  d_slots BIGINT,
+  d_blocks_proposed BIGINT,
+  d_blocks_missed BIGINT,
+  d_blocks_orphaned BIGINT,
+  d_gas_used BIGINT,
+  d_base_fee BIGINT,
+  d_base_fee_count BIGINT,
+  d_withdrawals BIGINT
+) RETURNS void AS $$
+BEGIN
+  INSERT INTO rollup_hourly AS r
+  VALUES (
+    d_ts - d_ts % 3600,
+    d_sign * d_epochs, d_sign * d_participation, d_sign * d_slots, d_sign * d_blocks_proposed,
+    d_sign * d_blocks_missed, d_sign * d_blocks_orphaned, d_sign * d_gas_used, d_sign * d_base_fee,
+    d_sign * d_base_fee_count, d_sign * d_withdrawals
+  )
+  ON CONFLICT (bucket_start) DO UPDATE SET
+    epochs = r.epochs + EXCLUDED.epochs,
+    participation_sum = r.participation_sum + EXCLUDED.participation_sum,
+    slots = r.slots + EXCLUDED.slots,
+    blocks_proposed = r.blocks_proposed + EXCLUDED.blocks_proposed,
+    blocks_missed = r.blocks_missed + EXCLUDED.blocks_missed,
+    blocks_orphaned = r.blocks_orphaned + EXCLUDED.blocks_orphaned,
+    gas_used = r.gas_used + EXCLUDED.gas_used,
+    base_fee_sum = r.base_fee_sum + EXCLUDED.base_fee_sum,
+    base_fee_count = r.base_fee_count + EXCLUDED.base_fee_count,
+    withdrawals = r.withdrawals + EXCLUDED.withdrawals;
+
+  INSERT INTO rollup_daily AS r
+  VALUES (
+    d_ts - d_ts % 86400,
+    d_sign * d_epochs, d_sign * d_participation, d_sign * d_slots, d_sign * d_blocks_proposed,
+    d_sign * d_blocks_missed, d_sign * d_blocks_orphaned, d_sign * d_gas_used, d_sign * d_base_fee,
+    d_sign * d_base_fee_count, d_sign * d_withdrawals
+  )
+  ON CONFLICT (bucket_start) DO UPDATE SET
+    epochs = r.epochs + EXCLUDED.epochs,
+    participation_sum = r.participation_sum + EXCLUDED.participation_sum,
+    slots = r.slots + EXCLUDED.slots,
+    blocks_proposed = r.blocks_proposed + EXCLUDED.blocks_proposed,
+    blocks_missed = r.blocks_missed + EXCLUDED.blocks_missed,
+    blocks_orphaned = r.blocks_orphaned + EXCLUDED.blocks_orphaned,
+    gas_used = r.gas_used + EXCLUDED.gas_used,
+    base_fee_sum = r.base_fee_sum + EXCLUDED.base_fee_sum,
+    base_fee_count = r.base_fee_count + EXCLUDED.base_fee_count,
+    withdrawals = r.withdrawals + EXCLUDED.withdrawals;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE FUNCTION slot_data_rollup() RETURNS trigger AS $$
+BEGIN
+  IF TG_OP = 'UPDATE' THEN
+    PERFORM add_to_rollups(
+      1606824023 + OLD.slot * 12, -1, 0, 0, 1,
+      (OLD.status = '1')::int, (OLD.status = '2')::int, (OLD.status = '3')::int,
+      COALESCE(OLD.exec_gas_used, 0), COALESCE(OLD.exec_base_fee_per_gas, 0),
+      (OLD.exec_base_fee_per_gas IS NOT NULL)::int, OLD.withdrawalcount
+    );
+  END IF;
+  PERFORM add_to_rollups(
+    1606824023 + NEW.slot * 12, 1, 0, 0, 1,
+    (NEW.status = '1')::int, (NEW.status = '2')::int, (NEW.status = '3')::int,
+    COALESCE(NEW.exec_gas_used, 0), COALESCE(NEW.exec_base_fee_per_gas, 0),
+    (NEW.exec_base_fee_per_gas IS NOT NULL)::int, NEW.withdrawalcount
+  );
+  RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE FUNCTION epoch_data_rollup() RETURNS trigger AS $$
+BEGIN
+  IF TG_OP = 'UPDATE' THEN
+    PERFORM add_to_rollups(
+      1606824023 + OLD.epoch * 384, -1, 1, OLD.globalparticipationrate, 0, 0, 0, 0, 0, 0, 0, 0
+    );
+  END IF;
+  PERFORM add_to_rollups(
+    1606824023 + NEW.epoch * 384, 1, 1, NEW.globalparticipationrate, 0, 0, 0, 0, 0, 0, 0, 0
+  );
+  RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER slot_data_rollup AFTER INSERT OR UPDATE ON slot_data
+  FOR EACH ROW EXECUTE FUNCTION slot_data_rollup();
+CREATE TRIGGER epoch_data_rollup AFTER INSERT OR UPDATE ON epoch_data
+  FOR EACH ROW EXECUTE FUNCTION epoch_data_rollup();
+
+-- Backfill from the rows already stored.
+SELECT add_to_rollups(
+  1606824023 + slot * 12, 1, 0, 0, 1,
+  (status = '1')::int, (status = '2')::int, (status = '3')::int,
+  COALESCE(exec_gas_used, 0), COALESCE(exec_base_fee_per_gas, 0),
+  (exec_base_fee_per_gas IS NOT NULL)::int, withdrawalcount
+)
+FROM slot_data;
+
+SELECT add_to_rollups(
+  1606824023 + epoch * 384, 1, 1, globalparticipationrate, 0, 0, 0, 0, 0, 0, 0, 0
+)
+FROM epoch_data;
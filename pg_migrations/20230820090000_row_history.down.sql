@@ -0,0 +1,2 @@
+DROP TABLE slot_data_history;
+DROP TABLE epoch_data_history;
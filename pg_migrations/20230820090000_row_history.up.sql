@@ -0,0 +1,162 @@
+-- Every stored version of an epoch or slot, see the SQLite migration. Written by PgStore in
+-- the same statement as the upsert that produced it.
+
+CREATE TABLE epoch_data_history (
+  history_id BIGSERIAL PRIMARY KEY,
+  recorded_at BIGINT NOT NULL,
+  source VARCHAR NOT NULL,
+  LIKE epoch_data
+);
+
+CREATE INDEX idx_epoch_data_history_epoch_recorded_at ON epoch_data_history (epoch, recorded_at);
+
+CREATE TABLE slot_data_history (
+  history_id BIGSERIAL PRIMARY KEY,
+  recorded_at BIGINT NOT NULL,
+  source VARCHAR NOT NULL,
+  LIKE slot_data
+);
+
+CREATE INDEX idx_slot_data_history_slot_recorded_at ON slot_data_history (slot, recorded_at);
+
+INSERT INTO epoch_data_history (
+  recorded_at,
+  source,
+  attestationscount,
+  attesterslashingscount,
+  averagevalidatorbalance,
+  blockscount,
+  depositscount,
+  eligibleether,
+  epoch,
+  finalized,
+  globalparticipationrate,
+  missedblocks,
+  orphanedblocks,
+  proposedblocks,
+  proposerslashingscount,
+  rewards_exported,
+  scheduledblocks,
+  totalvalidatorbalance,
+  ts,
+  validatorscount,
+  voluntaryexitscount,
+  votedether,
+  withdrawalcount
+)
+SELECT
+  (extract(epoch FROM now()) * 1000)::BIGINT,
+  'baseline',
+  attestationscount,
+  attesterslashingscount,
+  averagevalidatorbalance,
+  blockscount,
+  depositscount,
+  eligibleether,
+  epoch,
+  finalized,
+  globalparticipationrate,
+  missedblocks,
+  orphanedblocks,
+  proposedblocks,
+  proposerslashingscount,
+  rewards_exported,
+  scheduledblocks,
+  totalvalidatorbalance,
+  ts,
+  validatorscount,
+  voluntaryexitscount,
+  votedether,
+  withdrawalcount
+FROM epoch_data;
+
+INSERT INTO slot_data_history (
+  recorded_at,
+  source,
+  attestationscount,
+  attesterslashingscount,
+  blob_kzg_commitments_count,
+  blockroot,
+  depositscount,
+  epoch,
+  eth1data_blockhash,
+  eth1data_depositcount,
+  eth1data_depositroot,
+  exec_base_fee_per_gas,
+  exec_blob_gas_used,
+  exec_block_hash,
+  exec_block_number,
+  exec_excess_blob_gas,
+  exec_extra_data,
+  exec_fee_recipient,
+  exec_gas_limit,
+  exec_gas_used,
+  exec_logs_bloom,
+  exec_parent_hash,
+  exec_random,
+  exec_receipts_root,
+  exec_state_root,
+  exec_timestamp,
+  exec_transactions_count,
+  graffiti,
+  graffiti_text,
+  parent_beacon_block_root,
+  parentroot,
+  proposer,
+  proposerslashingscount,
+  randaoreveal,
+  signature,
+  slot,
+  stateroot,
+  status,
+  syncaggregate_bits,
+  syncaggregate_participation,
+  syncaggregate_signature,
+  voluntaryexitscount,
+  withdrawalcount
+)
+SELECT
+  (extract(epoch FROM now()) * 1000)::BIGINT,
+  'baseline',
+  attestationscount,
+  attesterslashingscount,
+  blob_kzg_commitments_count,
+  blockroot,
+  depositscount,
+  epoch,
+  eth1data_blockhash,
+  eth1data_depositcount,
+  eth1data_depositroot,
+  exec_base_fee_per_gas,
+  exec_blob_gas_used,
+  exec_block_hash,
+  exec_block_number,
+  exec_excess_blob_gas,
+  exec_extra_data,
+  exec_fee_recipient,
+  exec_gas_limit,
+  exec_gas_used,
+  exec_logs_bloom,
+  exec_parent_hash,
+  exec_random,
+  exec_receipts_root,
+  exec_state_root,
+  exec_timestamp,
+  exec_transactions_count,
+  graffiti,
+  graffiti_text,
+  parent_beacon_block_root,
+  parentroot,
+  proposer,
+  proposerslashingscount,
+  randaoreveal,
+  signature,
+  slot,
+  stateroot,
+  status,
+  syncaggregate_bits,
+  syncaggregate_participation,
+  syncaggregate_signature,
+  voluntaryexitscount,
+  withdrawalcount
+FROM slot_data;
@@ -0,0 +1 @@
+DROP INDEX idx_slot_data_graffiti_text_fts;
@@ -0,0 +1,5 @@
+-- Full-text index over slot_data.graffiti_text, the counterpart of the SQLite FTS5 table.
+-- The 'simple' configuration keeps client names and version strings as they are instead of
+-- stemming them.
+CREATE INDEX idx_slot_data_graffiti_text_fts ON slot_data
+  USING GIN (to_tsvector('simple', graffiti_text));
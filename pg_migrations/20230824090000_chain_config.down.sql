@@ -0,0 +1,37 @@
+CREATE OR REPLACE FUNCTION slot_data_rollup() RETURNS trigger AS $$
+BEGIN
+  IF TG_OP = 'UPDATE' THEN
+    PERFORM add_to_rollups(
+      1606824023 + OLD.slot * 12, -1, 0, 0, 1,
+      (OLD.status = '1')::int, (OLD.status = '2')::int, (OLD.status = '3')::int,
+      COALESCE(OLD.exec_gas_used, 0), COALESCE(OLD.exec_base_fee_per_gas, 0),
+      (OLD.exec_base_fee_per_gas IS NOT NULL)::int, OLD.withdrawalcount
+    );
+  END IF;
+  PERFORM add_to_rollups(
+    1606824023 + NEW.slot * 12, 1, 0, 0, 1,
+    (NEW.status = '1')::int, (NEW.status = '2')::int, (NEW.status = '3')::int,
+    COALESCE(NEW.exec_gas_used, 0), COALESCE(NEW.exec_base_fee_per_gas, 0),
+    (NEW.exec_base_fee_per_gas IS NOT NULL)::int, NEW.withdrawalcount
+  );
+  RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION epoch_data_rollup() RETURNS trigger AS $$
+BEGIN
+  IF TG_OP = 'UPDATE' THEN
+    PERFORM add_to_rollups(
+      1606824023 + OLD.epoch * 384, -1, 1, OLD.globalparticipationrate, 0, 0, 0, 0, 0, 0, 0, 0
+    );
+  END IF;
+  PERFORM add_to_rollups(
+    1606824023 + NEW.epoch * 384, 1, 1, NEW.globalparticipationrate, 0, 0, 0, 0, 0, 0, 0, 0
+  );
+  RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP FUNCTION epoch_start(BIGINT);
+DROP FUNCTION slot_start(BIGINT);
+DROP TABLE chain_config;
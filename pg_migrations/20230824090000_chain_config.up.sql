@@ -0,0 +1,56 @@
+-- See the SQLite migration: one row naming the network this database holds, used by the
+-- rollup triggers for bucket times.
+
+CREATE TABLE chain_config (
+  id INTEGER PRIMARY KEY NOT NULL CHECK (id = 1),
+  network VARCHAR NOT NULL,
+  genesis_timestamp BIGINT NOT NULL,
+  seconds_per_slot BIGINT NOT NULL,
+  slots_per_epoch BIGINT NOT NULL
+);
+
+INSERT INTO chain_config (id, network, genesis_timestamp, seconds_per_slot, slots_per_epoch)
+SELECT 1, 'mainnet', 1606824023, 12, 32
+WHERE EXISTS (SELECT 1 FROM epoch_data) OR EXISTS (SELECT 1 FROM slot_data);
+
+CREATE FUNCTION slot_start(slot BIGINT) RETURNS BIGINT AS $$
+  SELECT genesis_timestamp + slot * seconds_per_slot FROM chain_config
+$$ LANGUAGE sql STABLE;
+
+CREATE FUNCTION epoch_start(epoch BIGINT) RETURNS BIGINT AS $$
+  SELECT genesis_timestamp + epoch * seconds_per_slot * slots_per_epoch FROM chain_config
+$$ LANGUAGE sql STABLE;
+
+CREATE OR REPLACE FUNCTION slot_data_rollup() RETURNS trigger AS $$
+BEGIN
+  IF TG_OP = 'UPDATE' THEN
+    PERFORM add_to_rollups(
+      slot_start(OLD.slot), -1, 0, 0, 1,
+      (OLD.status = '1')::int, (OLD.status = '2')::int, (OLD.status = '3')::int,
+      COALESCE(OLD.exec_gas_used, 0), COALESCE(OLD.exec_base_fee_per_gas, 0),
+      (OLD.exec_base_fee_per_gas IS NOT NULL)::int, OLD.withdrawalcount
+    );
+  END IF;
+  PERFORM add_to_rollups(
+    slot_start(NEW.slot), 1, 0, 0, 1,
+    (NEW.status = '1')::int, (NEW.status = '2')::int, (NEW.status = '3')::int,
+    COALESCE(NEW.exec_gas_used, 0), COALESCE(NEW.exec_base_fee_per_gas, 0),
+    (NEW.exec_base_fee_per_gas IS NOT NULL)::int, NEW.withdrawalcount
+  );
+  RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION epoch_data_rollup() RETURNS trigger AS $$
+BEGIN
+  IF TG_OP = 'UPDATE' THEN
+    PERFORM add_to_rollups(
+      epoch_start(OLD.epoch), -1, 1, OLD.globalparticipationrate, 0, 0, 0, 0, 0, 0, 0, 0
+    );
+  END IF;
+  PERFORM add_to_rollups(
+    epoch_start(NEW.epoch), 1, 1, NEW.globalparticipationrate, 0, 0, 0, 0, 0, 0, 0, 0
+  );
+  RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
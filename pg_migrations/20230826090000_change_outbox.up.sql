@@ -0,0 +1,11 @@
+-- See the SQLite migration. Writers take a SHARE ROW EXCLUSIVE lock on the table before
+-- inserting, so events commit in `seq` order here too.
+
+CREATE TABLE change_outbox (
+  seq BIGSERIAL PRIMARY KEY,
+  recorded_at BIGINT NOT NULL,
+  entity VARCHAR NOT NULL,
+  key BIGINT NOT NULL,
+  kind VARCHAR NOT NULL,
+  payload JSONB NOT NULL
+);
@@ -0,0 +1 @@
+DROP TABLE sink_cursors;
@@ -0,0 +1,7 @@
+-- See the SQLite migration.
+
+CREATE TABLE sink_cursors (
+  sink VARCHAR PRIMARY KEY NOT NULL,
+  seq BIGINT NOT NULL,
+  updated_at BIGINT NOT NULL
+);
@@ -0,0 +1,21 @@
+CREATE OR REPLACE FUNCTION slot_data_rollup() RETURNS trigger AS $$
+BEGIN
+  IF TG_OP = 'UPDATE' THEN
+    PERFORM add_to_rollups(
+      slot_start(OLD.slot), -1, 0, 0, 1,
+      (OLD.status = '1')::int, (OLD.status = '2')::int, (OLD.status = '3')::int,
+      COALESCE(OLD.exec_gas_used, 0), COALESCE(OLD.exec_base_fee_per_gas, 0),
+      (OLD.exec_base_fee_per_gas IS NOT NULL)::int, OLD.withdrawalcount
+    );
+  END IF;
+  PERFORM add_to_rollups(
+    slot_start(NEW.slot), 1, 0, 0, 1,
+    (NEW.status = '1')::int, (NEW.status = '2')::int, (NEW.status = '3')::int,
+    COALESCE(NEW.exec_gas_used, 0), COALESCE(NEW.exec_base_fee_per_gas, 0),
+    (NEW.exec_base_fee_per_gas IS NOT NULL)::int, NEW.withdrawalcount
+  );
+  RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+ALTER TABLE chain_config DROP COLUMN pruned_before_slot;
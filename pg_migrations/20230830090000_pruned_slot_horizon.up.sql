@@ -0,0 +1,31 @@
+-- See the SQLite migration: chain_config records the slot below which everything was pruned,
+-- the rollup trigger does not count those slots again when they are re-inserted.
+
+ALTER TABLE chain_config ADD COLUMN pruned_before_slot BIGINT NOT NULL DEFAULT 0;
+
+UPDATE chain_config
+SET pruned_before_slot = COALESCE((SELECT MIN(slot) FROM slot_data), 0)
+WHERE (SELECT COALESCE(SUM(slots), 0) FROM rollup_daily) > (SELECT COUNT(*) FROM slot_data);
+
+CREATE OR REPLACE FUNCTION slot_data_rollup() RETURNS trigger AS $$
+BEGIN
+  IF TG_OP = 'INSERT' AND NEW.slot < (SELECT pruned_before_slot FROM chain_config) THEN
+    RETURN NULL;
+  END IF;
+  IF TG_OP = 'UPDATE' THEN
+    PERFORM add_to_rollups(
+      slot_start(OLD.slot), -1, 0, 0, 1,
+      (OLD.status = '1')::int, (OLD.status = '2')::int, (OLD.status = '3')::int,
+      COALESCE(OLD.exec_gas_used, 0), COALESCE(OLD.exec_base_fee_per_gas, 0),
+      (OLD.exec_base_fee_per_gas IS NOT NULL)::int, OLD.withdrawalcount
+    );
+  END IF;
+  PERFORM add_to_rollups(
+    slot_start(NEW.slot), 1, 0, 0, 1,
+    (NEW.status = '1')::int, (NEW.status = '2')::int, (NEW.status = '3')::int,
+    COALESCE(NEW.exec_gas_used, 0), COALESCE(NEW.exec_base_fee_per_gas, 0),
+    (NEW.exec_base_fee_per_gas IS NOT NULL)::int, NEW.withdrawalcount
+  );
+  RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
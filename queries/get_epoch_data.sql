@@ -0,0 +1,3 @@
+SELECT *
+FROM epoch_data
+WHERE epoch = ?
@@ -0,0 +1,4 @@
+SELECT *
+FROM slot_data
+WHERE epoch = ?
+ORDER BY slot ASC
@@ -0,0 +1,4 @@
+SELECT *
+FROM epoch_data
+WHERE epoch BETWEEN ? AND ?
+ORDER BY epoch ASC
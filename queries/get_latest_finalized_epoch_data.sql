@@ -0,0 +1,5 @@
+SELECT *
+FROM epoch_data
+WHERE finalized = 1
+ORDER BY epoch DESC
+LIMIT 1
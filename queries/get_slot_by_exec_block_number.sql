@@ -0,0 +1,3 @@
+SELECT *
+FROM slot_data
+WHERE exec_block_number = ?
@@ -0,0 +1,5 @@
+SELECT *
+FROM slot_data
+WHERE exec_fee_recipient = ?
+ORDER BY slot DESC
+LIMIT ?
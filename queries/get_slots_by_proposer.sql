@@ -0,0 +1,5 @@
+SELECT *
+FROM slot_data
+WHERE proposer = ?
+ORDER BY slot DESC
+LIMIT ?
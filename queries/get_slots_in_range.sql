@@ -0,0 +1,4 @@
+SELECT *
+FROM slot_data
+WHERE slot BETWEEN ? AND ?
+ORDER BY slot ASC
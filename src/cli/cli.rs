@@ -0,0 +1,204 @@
+use crate::AppResult;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffRange {
+    Epochs(i64, i64),
+    Slots(i64, i64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportMode {
+    /// Rewrite every day partition.
+    Full,
+    /// Only write finalized days that have not been exported yet.
+    Incremental,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackupCompression {
+    None,
+    Gzip,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifyArgs {
+    /// Inclusive epoch range to check.
+    pub epochs: (i64, i64),
+    /// Re-ingest epochs that fail a check.
+    pub repair: bool,
+    pub output: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    /// Default mode: run the schedulers and serve the HTTP API.
+    Serve,
+    /// Re-fetch a range from upstream and report differences with the stored rows,
+    /// written as JSON to the given file or stdout.
+    Diff(DiffRange, Option<String>),
+    /// Check invariants of the stored epochs and slots, optionally re-ingesting bad ranges.
+    Verify(VerifyArgs),
+    /// Bulk-load archived JSON responses and CSV exports from files or directories.
+    Import(Vec<String>),
+    /// Export `epoch_data` and `slot_data` as Parquet, partitioned by UTC day, into a directory.
+    Export(String, ExportMode),
+    /// Snapshot the live SQLite database to a file, with a checksum manifest next to it.
+    Backup(String, BackupCompression),
+    /// Replace the SQLite database with a backup after validating it.
+    Restore(String),
+    /// Apply pending schema migrations and exit.
+    MigrateOnly,
+    /// Revert schema migrations down to the given version and exit.
+    MigrateDown(i64),
+}
+
+pub const USAGE: &str = r#"Usage:
+    rish                                  Run the schedulers and the HTTP API
+    rish diff --epochs <from>..<to> [--output <file>]
+                                          Compare stored epochs (and their slots) with upstream
+    rish diff --slots <from>..<to> [--output <file>]
+                                          Compare stored slots with upstream
+    rish verify --epochs <from>..<to> [--repair] [--output <file>]
+                                          Check stored rows for broken invariants
+    rish import <path>...                 Import Epoch/EpochInfo JSON and slot_data CSV files
+    rish export --output <dir> [--incremental]
+                                          Write epoch_data and slot_data as daily Parquet partitions
+    rish backup <file> [--gzip]           Snapshot the SQLite database while rish is running
+    rish restore <file>                   Validate a backup and restore it over the SQLite database
+    rish --migrate-only                   Apply pending schema migrations and exit
+    rish migrate down <version>           Revert schema migrations newer than <version>
+
+Environment:
+    DATABASE_URL                          sqlite:// or postgres:// database of the primary network
+    RISH_NETWORKS                         Networks to follow: mainnet, holesky, sepolia, gnosis
+                                          (comma separated, default mainnet, first is primary)
+    DATABASE_URL_<NETWORK>                Database of one network, e.g. DATABASE_URL_HOLESKY
+                                          (default sqlite://rish-<network>.sqlite)
+    RISH_RETAIN_SLOT_DATA_DAYS            Prune finalized slots older than this many days
+    RISH_RETAIN_BLOB_SIDECAR_SUMMARY_DAYS Prune finalized blob summaries older than this many days
+    RISH_RETAIN_CHANGE_OUTBOX_DAYS        Prune change events older than this many days once every
+                                          sink has read them, newer ones are kept for sinks
+                                          added later
+    RISH_PRUNE_BATCH_SIZE                 Rows deleted per pruning batch (default 500)
+    RISH_PRUNE_INTERVAL_SECS              Seconds between pruning runs (default 3600)
+    RISH_CACHE_CAPACITY                   Slots and epochs kept in the read-through cache (default 10000, 0 disables)
+    RISH_CACHE_HEAD_TTL_SECS              How long unfinalized data stays cached (default one slot)
+    RISH_SINK_NDJSON_DIR                  Write changes to rotating NDJSON files in this directory
+    RISH_SINK_NDJSON_MAX_BYTES            Size at which NDJSON files rotate (default 64 MiB)
+    RISH_SINK_KAFKA_BROKERS               Publish changes to Kafka (`kafka` feature)
+    RISH_SINK_KAFKA_TOPIC                 Kafka topic (default rish.changes)
+    RISH_SINK_NATS_URL                    Publish changes to NATS JetStream (`nats` feature)
+    RISH_SINK_NATS_SUBJECT                Subject prefix, <subject>.<network>.<entity> (default rish.changes)
+    RISH_SINK_REDIS_URL                   Append changes to a Redis stream (`redis` feature)
+    RISH_SINK_REDIS_STREAM                Redis stream key (default rish:changes)
+    RISH_SINK_REDIS_MAXLEN                Trim the Redis stream to about this many entries
+    RISH_SINK_BATCH_SIZE                  Changes published per batch (default 500)
+    RISH_SINK_POLL_INTERVAL_MS            Outbox polling interval when idle (default 1000)
+    RISH_SINK_RETRY_MAX_SECS              Longest backoff between delivery retries (default 60)"#;
+
+fn usage_error(message: String) -> Box<dyn std::error::Error + Send + Sync> {
+    Box::new(std::io::Error::new(
+        std::io::ErrorKind::InvalidInput,
+        format!("{message}\n\n{USAGE}"),
+    ))
+}
+
+/// Parses `<from>..<to>` (inclusive) or a single number.
+pub fn parse_range(value: &str) -> AppResult<(i64, i64)> {
+    let (from, to) = match value.split_once("..") {
+        Some((from, to)) => (from.parse::<i64>()?, to.parse::<i64>()?),
+        None => {
+            let single = value.parse::<i64>()?;
+            (single, single)
+        }
+    };
+
+    if from > to {
+        return Err(usage_error(format!("Invalid range {value}: {from} > {to}")));
+    }
+
+    Ok((from, to))
+}
+
+pub fn parse_args<I: IntoIterator<Item = String>>(args: I) -> AppResult<Command> {
+    let args: Vec<String> = args.into_iter().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    match args.as_slice() {
+        [] => Ok(Command::Serve),
+        ["diff", kind, range, rest @ ..] => {
+            let (from, to) = parse_range(range)?;
+            let range = match *kind {
+                "--epochs" => DiffRange::Epochs(from, to),
+                "--slots" => DiffRange::Slots(from, to),
+                _ => return Err(usage_error(format!("Unknown diff range kind: {kind}"))),
+            };
+            let output = match rest {
+                [] => None,
+                ["--output", path] => Some(path.to_string()),
+                _ => {
+                    return Err(usage_error(format!(
+                        "Unrecognised arguments: {}",
+                        rest.join(" ")
+                    )))
+                }
+            };
+            Ok(Command::Diff(range, output))
+        }
+        ["export", "--output", dir, rest @ ..] => {
+            let mode = match rest {
+                [] => ExportMode::Full,
+                ["--incremental"] => ExportMode::Incremental,
+                _ => {
+                    return Err(usage_error(format!(
+                        "Unrecognised arguments: {}",
+                        rest.join(" ")
+                    )))
+                }
+            };
+            Ok(Command::Export(dir.to_string(), mode))
+        }
+        ["backup", path] => Ok(Command::Backup(path.to_string(), BackupCompression::None)),
+        ["backup", path, "--gzip"] => {
+            Ok(Command::Backup(path.to_string(), BackupCompression::Gzip))
+        }
+        ["restore", path] => Ok(Command::Restore(path.to_string())),
+        ["verify", "--epochs", range, rest @ ..] => {
+            let mut args = VerifyArgs {
+                epochs: parse_range(range)?,
+                repair: false,
+                output: None,
+            };
+            let mut rest = rest;
+            loop {
+                match rest {
+                    [] => break,
+                    ["--repair", tail @ ..] => {
+                        args.repair = true;
+                        rest = tail;
+                    }
+                    ["--output", path, tail @ ..] => {
+                        args.output = Some(path.to_string());
+                        rest = tail;
+                    }
+                    _ => {
+                        return Err(usage_error(format!(
+                            "Unrecognised arguments: {}",
+                            rest.join(" ")
+                        )))
+                    }
+                }
+            }
+            Ok(Command::Verify(args))
+        }
+        ["--migrate-only"] => Ok(Command::MigrateOnly),
+        ["migrate", "down", version] => Ok(Command::MigrateDown(version.parse::<i64>()?)),
+        ["import", paths @ ..] if !paths.is_empty() => Ok(Command::Import(
+            paths.iter().map(|path| path.to_string()).collect(),
+        )),
+        _ => Err(usage_error(format!(
+            "Unrecognised arguments: {}",
+            args.join(" ")
+        ))),
+    }
+}
@@ -0,0 +1,283 @@
+use std::{
+    ffi::{CStr, CString},
+    fs::File,
+    io::{BufReader, BufWriter, Read},
+    os::raw::c_int,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::Duration,
+};
+
+use chrono::{SecondsFormat, Utc};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use libsqlite3_sys as ffi;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::{sqlite::SqliteConnectOptions, ConnectOptions, Connection, Row};
+
+use super::{migrations::ensure_not_newer, pools::BUSY_TIMEOUT, store::is_postgres_url};
+use crate::{cli::BackupCompression, AppResult};
+
+/// Wait between attempts while another connection holds a lock the backup needs.
+const BACKUP_RETRY_DELAY: Duration = Duration::from_millis(100);
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Written next to every backup as `<backup>.manifest.json`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BackupManifest {
+    pub file: String,
+    pub created_at: String,
+    pub schema_version: i64,
+    pub size_bytes: u64,
+    pub sha256: String,
+    pub compressed: bool,
+}
+
+pub fn manifest_path(backup_path: &Path) -> PathBuf {
+    let mut path = backup_path.as_os_str().to_owned();
+    path.push(".manifest.json");
+    PathBuf::from(path)
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(suffix);
+    PathBuf::from(path)
+}
+
+/// A database opened directly through the C API. The backup API works on raw handles and
+/// blocks, so both sides of a copy are opened this way on a blocking thread.
+struct RawDb(*mut ffi::sqlite3);
+
+impl RawDb {
+    fn open(path: &Path, flags: c_int) -> AppResult<Self> {
+        let c_path = CString::new(path.to_string_lossy().as_bytes())?;
+        let mut handle = std::ptr::null_mut();
+        let rc =
+            unsafe { ffi::sqlite3_open_v2(c_path.as_ptr(), &mut handle, flags, std::ptr::null()) };
+        // A handle is returned even when opening fails and must be closed either way.
+        let db = RawDb(handle);
+
+        if rc != ffi::SQLITE_OK {
+            return Err(
+                format!("Cannot open {}: {}", path.display(), error_message(handle)).into(),
+            );
+        }
+        unsafe {
+            ffi::sqlite3_busy_timeout(handle, BUSY_TIMEOUT.as_millis() as c_int);
+        }
+
+        Ok(db)
+    }
+}
+
+impl Drop for RawDb {
+    fn drop(&mut self) {
+        unsafe {
+            ffi::sqlite3_close(self.0);
+        }
+    }
+}
+
+fn error_message(handle: *mut ffi::sqlite3) -> String {
+    unsafe { CStr::from_ptr(ffi::sqlite3_errmsg(handle)) }
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Copies the `main` database of `source` into `destination` with SQLite's online backup API.
+/// The copy runs in a single step, i.e. one read transaction on `source`, so the result is a
+/// consistent snapshot. Under WAL the ingestion keeps writing while it runs.
+fn copy_database(source: *mut ffi::sqlite3, destination: *mut ffi::sqlite3) -> AppResult<()> {
+    let main = CString::new("main")?;
+
+    unsafe {
+        let backup = ffi::sqlite3_backup_init(destination, main.as_ptr(), source, main.as_ptr());
+        if backup.is_null() {
+            return Err(format!("Backup failed: {}", error_message(destination)).into());
+        }
+
+        // Any other result is done, or an error that `sqlite3_backup_finish` reports below.
+        while let ffi::SQLITE_BUSY | ffi::SQLITE_LOCKED = ffi::sqlite3_backup_step(backup, -1) {
+            std::thread::sleep(BACKUP_RETRY_DELAY);
+        }
+
+        if ffi::sqlite3_backup_finish(backup) != ffi::SQLITE_OK {
+            return Err(format!("Backup failed: {}", error_message(destination)).into());
+        }
+    }
+
+    Ok(())
+}
+
+/// Copies the database file at `source` over the one at `destination`, creating only the
+/// destination if missing. Runs on a blocking thread since the copy sleeps while another
+/// connection holds a lock.
+async fn copy_database_file(source: PathBuf, destination: PathBuf) -> AppResult<()> {
+    tokio::task::spawn_blocking(move || {
+        // Read-write so a WAL database can be read without its `-shm` file being present.
+        let source = RawDb::open(&source, ffi::SQLITE_OPEN_READWRITE)?;
+        let destination = RawDb::open(
+            &destination,
+            ffi::SQLITE_OPEN_READWRITE | ffi::SQLITE_OPEN_CREATE,
+        )?;
+        copy_database(source.0, destination.0)
+    })
+    .await?
+}
+
+fn sqlite_options(db_url: &str) -> AppResult<SqliteConnectOptions> {
+    if is_postgres_url(db_url) {
+        return Err(
+            "Backup and restore only support SQLite, use pg_dump/pg_restore for PostgreSQL".into(),
+        );
+    }
+
+    Ok(SqliteConnectOptions::from_str(db_url)?.busy_timeout(BUSY_TIMEOUT))
+}
+
+/// Migration versions applied to the database file at `path`. Errors when it is not a rish
+/// database at all.
+async fn schema_versions_of(path: &Path) -> AppResult<Vec<i64>> {
+    let mut conn = SqliteConnectOptions::new().filename(path).connect().await?;
+
+    let rows =
+        sqlx::query(r#"SELECT version FROM _sqlx_migrations WHERE success = 1 ORDER BY version"#)
+            .fetch_all(&mut conn)
+            .await
+            .map_err(|err| format!("{} is not a rish database: {err}", path.display()))?;
+
+    let integrity: String = sqlx::query_scalar("PRAGMA quick_check")
+        .fetch_one(&mut conn)
+        .await?;
+    if integrity != "ok" {
+        return Err(format!("{} is corrupt: {integrity}", path.display()).into());
+    }
+
+    conn.close().await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| row.get::<i64, _>("version"))
+        .collect())
+}
+
+fn sha256_of(path: &Path) -> AppResult<String> {
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut BufReader::new(File::open(path)?), &mut hasher)?;
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Writes a consistent snapshot of the live database to `destination`, gzipped when asked to,
+/// plus a manifest with its schema version and SHA-256. Safe to run while rish is serving.
+pub async fn backup_database(
+    db_url: &str,
+    destination: &str,
+    compression: BackupCompression,
+) -> AppResult<BackupManifest> {
+    let destination = Path::new(destination);
+    if destination.exists() {
+        return Err(format!("{} already exists", destination.display()).into());
+    }
+    let snapshot_path = with_suffix(destination, ".snapshot.tmp");
+
+    let source = sqlite_options(db_url)?.get_filename().to_path_buf();
+    copy_database_file(source, snapshot_path.clone()).await?;
+
+    let schema_version = schema_versions_of(&snapshot_path)
+        .await?
+        .last()
+        .copied()
+        .unwrap_or(0);
+
+    match compression {
+        BackupCompression::None => std::fs::rename(&snapshot_path, destination)?,
+        BackupCompression::Gzip => {
+            let mut encoder = GzEncoder::new(
+                BufWriter::new(File::create(destination)?),
+                Compression::default(),
+            );
+            std::io::copy(&mut File::open(&snapshot_path)?, &mut encoder)?;
+            encoder.finish()?;
+            std::fs::remove_file(&snapshot_path)?;
+        }
+    }
+
+    let manifest = BackupManifest {
+        file: destination
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        created_at: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+        schema_version,
+        size_bytes: std::fs::metadata(destination)?.len(),
+        sha256: sha256_of(destination)?,
+        compressed: compression == BackupCompression::Gzip,
+    };
+    std::fs::write(
+        manifest_path(destination),
+        serde_json::to_string_pretty(&manifest)?,
+    )?;
+
+    Ok(manifest)
+}
+
+/// Replaces the live database with the backup at `backup`. The checksum is verified when a
+/// manifest is present and the backup must pass `PRAGMA quick_check` and carry a schema this
+/// binary knows before anything is overwritten. Pending migrations are applied on next start.
+pub async fn restore_database(db_url: &str, backup: &str) -> AppResult<i64> {
+    let live_path = sqlite_options(db_url)?.get_filename().to_path_buf();
+    let backup = Path::new(backup);
+
+    let manifest_path = manifest_path(backup);
+    let compressed = if manifest_path.exists() {
+        let manifest: BackupManifest =
+            serde_json::from_str(&std::fs::read_to_string(&manifest_path)?)?;
+        let sha256 = sha256_of(backup)?;
+        if sha256 != manifest.sha256 {
+            return Err(format!(
+                "Checksum mismatch for {}: manifest has {}, file has {sha256}",
+                backup.display(),
+                manifest.sha256
+            )
+            .into());
+        }
+        manifest.compressed
+    } else {
+        println!(
+            "No manifest found for {}, skipping checksum verification",
+            backup.display()
+        );
+        let mut magic = [0u8; 2];
+        File::open(backup)?.read_exact(&mut magic)?;
+        magic == GZIP_MAGIC
+    };
+
+    let restore_path = with_suffix(&live_path, ".restore.tmp");
+    if compressed {
+        let mut decoder = GzDecoder::new(BufReader::new(File::open(backup)?));
+        std::io::copy(&mut decoder, &mut File::create(&restore_path)?)?;
+    } else {
+        std::fs::copy(backup, &restore_path)?;
+    }
+
+    let restored = async {
+        let applied = schema_versions_of(&restore_path).await?;
+        ensure_not_newer(&applied)?;
+
+        copy_database_file(restore_path.clone(), live_path).await?;
+
+        AppResult::Ok(applied.last().copied().unwrap_or(0))
+    }
+    .await;
+
+    std::fs::remove_file(&restore_path)?;
+
+    let schema_version = restored?;
+    println!(
+        "Restored {} (schema version {schema_version})",
+        backup.display()
+    );
+
+    Ok(schema_version)
+}
@@ -0,0 +1,522 @@
+use std::{
+    sync::{
+        atomic::{AtomicI64, AtomicU64, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use chrono::Utc;
+use hashlink::LruCache;
+use serde::Serialize;
+
+use super::store::{
+    ChangeStore, EpochStore, HistoryStore, NetworkScoped, RollupStore, SharedStore, SlotStore,
+};
+use crate::{
+    models::{
+        BlobSidecarSummary, ChangeEvent, EpochData, GraffitiMatch, GraffitiQuery, Rollup,
+        RollupGranularity, RowVersion, SlotData, SlotQuery,
+    },
+    utils::{retention::env_number, Network},
+    AppResult,
+};
+
+pub const DEFAULT_CACHE_CAPACITY: usize = 10_000;
+
+/// Size and lifetimes of the [`CachedStore`] entries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheSettings {
+    /// Entries kept per kind (slots, epochs), 0 disables the cache.
+    pub capacity: usize,
+    /// How long data that may still change is served from the cache.
+    pub head_ttl: Duration,
+}
+
+impl CacheSettings {
+    /// Head data lives for one slot of `network` by default.
+    pub fn for_network(network: Network) -> Self {
+        CacheSettings {
+            capacity: DEFAULT_CACHE_CAPACITY,
+            head_ttl: Duration::from_secs(network.seconds_per_slot as u64),
+        }
+    }
+
+    /// Reads `RISH_CACHE_CAPACITY` and `RISH_CACHE_HEAD_TTL_SECS`.
+    pub fn from_env(network: Network) -> AppResult<Self> {
+        let defaults = CacheSettings::for_network(network);
+
+        Ok(CacheSettings {
+            capacity: env_number("RISH_CACHE_CAPACITY")?
+                .map(|capacity| capacity as usize)
+                .unwrap_or(defaults.capacity),
+            head_ttl: env_number("RISH_CACHE_HEAD_TTL_SECS")?
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.head_ttl),
+        })
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct CacheMetrics {
+    pub hits: AtomicU64,
+    pub misses: AtomicU64,
+    /// Hits on a cached "no such row".
+    pub negative_hits: AtomicU64,
+    pub evictions: AtomicU64,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct CacheSnapshot {
+    pub hits: u64,
+    pub misses: u64,
+    pub negative_hits: u64,
+    pub evictions: u64,
+}
+
+impl CacheMetrics {
+    pub fn snapshot(&self) -> CacheSnapshot {
+        CacheSnapshot {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            negative_hits: self.negative_hits.load(Ordering::Relaxed),
+            evictions: self.evictions.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// A cached lookup, `None` caching that the row does not exist.
+struct Entry<T> {
+    value: Option<T>,
+    /// `None` for finalized rows, which never change.
+    expires_at: Option<Instant>,
+}
+
+impl<T> Entry<T> {
+    fn is_fresh(&self, now: Instant) -> bool {
+        !matches!(self.expires_at, Some(expires_at) if expires_at <= now)
+    }
+}
+
+/// An LRU map of entries plus a write counter. A lookup only caches what it read when no write
+/// happened meanwhile, so a read racing an update never caches the old row.
+struct Lru<T> {
+    entries: Mutex<LruCache<i64, Entry<T>>>,
+    writes: AtomicU64,
+}
+
+impl<T: Clone> Lru<T> {
+    fn new(capacity: usize) -> Self {
+        Lru {
+            entries: Mutex::new(LruCache::new(capacity)),
+            writes: AtomicU64::new(0),
+        }
+    }
+
+    fn get(&self, key: i64, metrics: &CacheMetrics) -> Option<Option<T>> {
+        let mut entries = self.entries.lock().expect("cache lock poisoned");
+        let value = match entries.get(&key) {
+            Some(entry) if entry.is_fresh(Instant::now()) => entry.value.clone(),
+            Some(_) => {
+                entries.remove(&key);
+                return None;
+            }
+            None => return None,
+        };
+
+        let counter = match value {
+            Some(_) => &metrics.hits,
+            None => &metrics.negative_hits,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        Some(value)
+    }
+
+    fn put(&self, key: i64, entry: Entry<T>, writes_before: u64, metrics: &CacheMetrics) {
+        let mut entries = self.entries.lock().expect("cache lock poisoned");
+        if self.writes.load(Ordering::Acquire) != writes_before {
+            return;
+        }
+
+        let full = entries.len() >= entries.capacity();
+        if entries.insert(key, entry).is_none() && full {
+            metrics.evictions.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn invalidate(&self, keys: impl FnOnce(&LruCache<i64, Entry<T>>) -> Vec<i64>) {
+        let mut entries = self.entries.lock().expect("cache lock poisoned");
+        self.writes.fetch_add(1, Ordering::Release);
+        for key in keys(&entries) {
+            entries.remove(&key);
+        }
+    }
+
+    fn invalidate_key(&self, key: i64) {
+        self.invalidate(|_| vec![key]);
+    }
+}
+
+/// Read-through cache in front of another store, for the point lookups the API serves most:
+/// [`get_slot`](SlotStore::get_slot) and [`get_epoch`](EpochStore::get_epoch).
+///
+/// Rows of finalized epochs are cached until evicted, rows that may still change for
+/// [`CacheSettings::head_ttl`]. Missing rows are cached too, those of slots and epochs that
+/// have not started yet until they start. Writes through the cache invalidate what they touch,
+/// writes by other processes show up once the head TTL ran out.
+pub struct CachedStore {
+    inner: SharedStore,
+    head_ttl: Duration,
+    slots: Lru<SlotData>,
+    epochs: Lru<EpochData>,
+    /// Newest finalized epoch seen, -1 before the first one.
+    finalized_epoch: AtomicI64,
+    /// When `finalized_epoch` was last read from the inner store.
+    finalized_checked_at: Mutex<Option<Instant>>,
+    metrics: CacheMetrics,
+}
+
+impl CachedStore {
+    pub fn new(inner: SharedStore, settings: CacheSettings) -> Self {
+        CachedStore {
+            inner,
+            head_ttl: settings.head_ttl,
+            slots: Lru::new(settings.capacity),
+            epochs: Lru::new(settings.capacity),
+            finalized_epoch: AtomicI64::new(-1),
+            finalized_checked_at: Mutex::new(None),
+            metrics: CacheMetrics::default(),
+        }
+    }
+
+    pub fn metrics(&self) -> &CacheMetrics {
+        &self.metrics
+    }
+
+    fn observe_epoch(&self, epoch_data: &EpochData) {
+        if epoch_data.finalized != 0 {
+            self.finalized_epoch
+                .fetch_max(epoch_data.epoch, Ordering::Relaxed);
+        }
+    }
+
+    /// Whether `epoch` is known to be finalized, asking the inner store at most once per head
+    /// TTL when it is newer than the finalized epoch seen so far.
+    async fn is_finalized(&self, epoch: i64) -> AppResult<bool> {
+        if epoch <= self.finalized_epoch.load(Ordering::Relaxed) {
+            return Ok(true);
+        }
+
+        {
+            let mut checked_at = self
+                .finalized_checked_at
+                .lock()
+                .expect("cache lock poisoned");
+            if checked_at.is_some_and(|checked_at| checked_at.elapsed() < self.head_ttl) {
+                return Ok(false);
+            }
+            *checked_at = Some(Instant::now());
+        }
+
+        if let Some(finalized) = self.inner.get_latest_finalized_epoch().await? {
+            self.observe_epoch(&finalized);
+        }
+        Ok(epoch <= self.finalized_epoch.load(Ordering::Relaxed))
+    }
+
+    /// Expiry of a missing row: the start of a slot still in the future, the head TTL otherwise.
+    fn missing_until(&self, starts_at: i64) -> Option<Instant> {
+        let until_start = starts_at - Utc::now().timestamp();
+        let ttl = match until_start > 0 {
+            true => Duration::from_secs(until_start as u64),
+            false => self.head_ttl,
+        };
+        Some(Instant::now() + ttl)
+    }
+
+    async fn entry_for<T>(
+        &self,
+        value: Option<T>,
+        epoch: i64,
+        starts_at: i64,
+    ) -> AppResult<Entry<T>> {
+        let expires_at = match &value {
+            None => self.missing_until(starts_at),
+            Some(_) if self.is_finalized(epoch).await? => None,
+            Some(_) => Some(Instant::now() + self.head_ttl),
+        };
+
+        Ok(Entry { value, expires_at })
+    }
+}
+
+fn keys_where<T>(entries: &LruCache<i64, Entry<T>>, matches: impl Fn(&T) -> bool) -> Vec<i64> {
+    entries
+        .iter()
+        .filter(|(_, entry)| entry.value.as_ref().is_some_and(&matches))
+        .map(|(key, _)| *key)
+        .collect()
+}
+
+impl NetworkScoped for CachedStore {
+    fn network(&self) -> Network {
+        self.inner.network()
+    }
+}
+
+#[async_trait]
+impl EpochStore for CachedStore {
+    async fn insert_epoch(&self, epoch_data: EpochData) -> AppResult<()> {
+        let epoch_number = epoch_data.epoch;
+        let result = self.inner.insert_epoch(epoch_data.clone()).await;
+        self.epochs.invalidate_key(epoch_number);
+        if result.is_ok() {
+            self.observe_epoch(&epoch_data);
+        }
+        result
+    }
+
+    async fn update_epoch(&self, epoch_number: i64, epoch_data: EpochData) -> AppResult<()> {
+        let result = self
+            .inner
+            .update_epoch(epoch_number, epoch_data.clone())
+            .await;
+        self.epochs.invalidate_key(epoch_number);
+        if result.is_ok() {
+            self.observe_epoch(&epoch_data);
+        }
+        result
+    }
+
+    async fn get_epoch(&self, epoch_number: i64) -> AppResult<Option<EpochData>> {
+        if let Some(cached) = self.epochs.get(epoch_number, &self.metrics) {
+            return Ok(cached);
+        }
+        self.metrics.misses.fetch_add(1, Ordering::Relaxed);
+
+        let writes_before = self.epochs.writes.load(Ordering::Acquire);
+        let epoch_data = self.inner.get_epoch(epoch_number).await?;
+        if let Some(epoch_data) = &epoch_data {
+            self.observe_epoch(epoch_data);
+        }
+        let network = self.network();
+        let entry = self
+            .entry_for(
+                epoch_data.clone(),
+                epoch_number,
+                network.epoch_timestamp(epoch_number),
+            )
+            .await?;
+        self.epochs
+            .put(epoch_number, entry, writes_before, &self.metrics);
+
+        Ok(epoch_data)
+    }
+
+    async fn get_latest_epoch(&self) -> AppResult<EpochData> {
+        self.inner.get_latest_epoch().await
+    }
+
+    async fn get_latest_epochs(&self, count: i64) -> AppResult<Vec<EpochData>> {
+        self.inner.get_latest_epochs(count).await
+    }
+
+    async fn get_earliest_epoch(&self) -> AppResult<Option<EpochData>> {
+        self.inner.get_earliest_epoch().await
+    }
+
+    async fn get_epochs_in_range(
+        &self,
+        from_epoch: i64,
+        to_epoch: i64,
+    ) -> AppResult<Vec<EpochData>> {
+        self.inner.get_epochs_in_range(from_epoch, to_epoch).await
+    }
+
+    async fn get_latest_finalized_epoch(&self) -> AppResult<Option<EpochData>> {
+        let finalized = self.inner.get_latest_finalized_epoch().await?;
+        if let Some(finalized) = &finalized {
+            self.observe_epoch(finalized);
+        }
+        Ok(finalized)
+    }
+}
+
+#[async_trait]
+impl SlotStore for CachedStore {
+    async fn insert_slot(&self, slot: &SlotData) -> AppResult<()> {
+        let result = self.inner.insert_slot(slot).await;
+        self.slots.invalidate_key(slot.slot);
+        result
+    }
+
+    async fn update_slot(&self, slot: SlotData) -> AppResult<()> {
+        let slot_number = slot.slot;
+        let result = self.inner.update_slot(slot).await;
+        self.slots.invalidate_key(slot_number);
+        result
+    }
+
+    async fn get_slot(&self, slot_number: i64) -> AppResult<Option<SlotData>> {
+        if let Some(cached) = self.slots.get(slot_number, &self.metrics) {
+            return Ok(cached);
+        }
+        self.metrics.misses.fetch_add(1, Ordering::Relaxed);
+
+        let writes_before = self.slots.writes.load(Ordering::Acquire);
+        let slot = self.inner.get_slot(slot_number).await?;
+        let network = self.network();
+        let entry = self
+            .entry_for(
+                slot.clone(),
+                network.epoch_of_slot(slot_number),
+                network.slot_timestamp(slot_number),
+            )
+            .await?;
+        self.slots
+            .put(slot_number, entry, writes_before, &self.metrics);
+
+        Ok(slot)
+    }
+
+    async fn get_epoch_slots(&self, epoch_number: i64) -> AppResult<Vec<SlotData>> {
+        self.inner.get_epoch_slots(epoch_number).await
+    }
+
+    async fn get_slots_in_range(&self, from_slot: i64, to_slot: i64) -> AppResult<Vec<SlotData>> {
+        self.inner.get_slots_in_range(from_slot, to_slot).await
+    }
+
+    async fn get_slots_by_proposer(&self, proposer: i64, limit: i64) -> AppResult<Vec<SlotData>> {
+        self.inner.get_slots_by_proposer(proposer, limit).await
+    }
+
+    async fn get_slots_by_fee_recipient(
+        &self,
+        fee_recipient: &str,
+        limit: i64,
+    ) -> AppResult<Vec<SlotData>> {
+        self.inner
+            .get_slots_by_fee_recipient(fee_recipient, limit)
+            .await
+    }
+
+    async fn query_slots(
+        &self,
+        query: &SlotQuery,
+        cursor: Option<i64>,
+        limit: i64,
+    ) -> AppResult<Vec<SlotData>> {
+        self.inner.query_slots(query, cursor, limit).await
+    }
+
+    async fn get_slot_by_exec_block_number(
+        &self,
+        exec_block_number: i64,
+    ) -> AppResult<Option<SlotData>> {
+        self.inner
+            .get_slot_by_exec_block_number(exec_block_number)
+            .await
+    }
+
+    async fn get_slot_by_exec_block_hash(
+        &self,
+        exec_block_hash: &str,
+    ) -> AppResult<Option<SlotData>> {
+        self.inner
+            .get_slot_by_exec_block_hash(exec_block_hash)
+            .await
+    }
+
+    async fn get_slot_by_blockroot(&self, blockroot: &str) -> AppResult<Option<SlotData>> {
+        self.inner.get_slot_by_blockroot(blockroot).await
+    }
+
+    async fn get_latest_unexecuted_slot(&self) -> AppResult<SlotData> {
+        self.inner.get_latest_unexecuted_slot().await
+    }
+
+    async fn search_graffiti(
+        &self,
+        query: &GraffitiQuery,
+        before_slot: Option<i64>,
+        limit: i64,
+    ) -> AppResult<Vec<GraffitiMatch>> {
+        self.inner.search_graffiti(query, before_slot, limit).await
+    }
+
+    async fn get_blob_sidecar_summary(
+        &self,
+        slot_number: i64,
+    ) -> AppResult<Option<BlobSidecarSummary>> {
+        self.inner.get_blob_sidecar_summary(slot_number).await
+    }
+
+    async fn prune_slots(&self, before_epoch: i64, limit: i64) -> AppResult<u64> {
+        let result = self.inner.prune_slots(before_epoch, limit).await;
+        self.slots
+            .invalidate(|entries| keys_where(entries, |slot| slot.epoch < before_epoch));
+        result
+    }
+
+    async fn prune_blob_sidecar_summaries(&self, before_epoch: i64, limit: i64) -> AppResult<u64> {
+        self.inner
+            .prune_blob_sidecar_summaries(before_epoch, limit)
+            .await
+    }
+
+    async fn reclaim_space(&self, max_pages: i64) -> AppResult<()> {
+        self.inner.reclaim_space(max_pages).await
+    }
+}
+
+#[async_trait]
+impl RollupStore for CachedStore {
+    async fn get_rollups(
+        &self,
+        granularity: RollupGranularity,
+        from_ts: i64,
+        to_ts: i64,
+    ) -> AppResult<Vec<Rollup>> {
+        self.inner.get_rollups(granularity, from_ts, to_ts).await
+    }
+}
+
+#[async_trait]
+impl HistoryStore for CachedStore {
+    async fn get_epoch_as_of(&self, epoch_number: i64, as_of: i64) -> AppResult<Option<EpochData>> {
+        self.inner.get_epoch_as_of(epoch_number, as_of).await
+    }
+
+    async fn get_slot_as_of(&self, slot_number: i64, as_of: i64) -> AppResult<Option<SlotData>> {
+        self.inner.get_slot_as_of(slot_number, as_of).await
+    }
+
+    async fn get_epoch_history(&self, epoch_number: i64) -> AppResult<Vec<RowVersion<EpochData>>> {
+        self.inner.get_epoch_history(epoch_number).await
+    }
+
+    async fn get_slot_history(&self, slot_number: i64) -> AppResult<Vec<RowVersion<SlotData>>> {
+        self.inner.get_slot_history(slot_number).await
+    }
+}
+
+#[async_trait]
+impl ChangeStore for CachedStore {
+    async fn get_changes(&self, since: i64, limit: i64) -> AppResult<Vec<ChangeEvent>> {
+        self.inner.get_changes(since, limit).await
+    }
+
+    async fn get_sink_cursor(&self, sink: &str) -> AppResult<i64> {
+        self.inner.get_sink_cursor(sink).await
+    }
+
+    async fn set_sink_cursor(&self, sink: &str, seq: i64) -> AppResult<()> {
+        self.inner.set_sink_cursor(sink, seq).await
+    }
+
+    async fn prune_changes(&self, before: i64, limit: i64) -> AppResult<u64> {
+        self.inner.prune_changes(before, limit).await
+    }
+}
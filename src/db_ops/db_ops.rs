@@ -1,24 +1,31 @@
 use std::sync::Arc;
 
+use super::{migrations, pools::DbPools};
 use crate::{
-    models::{EpochData, SlotData},
-    utils, AppResult,
+    models::{
+        hex_to_bytes, now_millis, BlobSidecarSummary, ChangeEntity, ChangeEvent, ChangeKind,
+        ChangeSource, EpochData, GraffitiMatch, GraffitiQuery, Rollup, RollupGranularity,
+        RowVersion, SlotData, SlotDataRow, SlotQuery,
+    },
+    utils::{Network, MAINNET},
+    AppResult,
 };
-use sqlx::{migrate::MigrateDatabase, Sqlite, SqlitePool};
-use tokio::sync::Mutex;
+use chrono::{SecondsFormat, TimeZone, Utc};
+use serde::Serialize;
+use sqlx::{migrate::MigrateDatabase, QueryBuilder, Row, Sqlite, SqliteConnection};
 
-pub async fn table_exists(db_conn: Arc<Mutex<SqlitePool>>, table_name: &str) -> AppResult<bool> {
+pub async fn table_exists(db_conn: Arc<DbPools>, table_name: &str) -> AppResult<bool> {
     let query = r#"SELECT name FROM sqlite_master WHERE type='table' AND name=?"#;
 
     let result = sqlx::query(query)
         .bind(table_name)
-        .fetch_optional(&*db_conn.lock().await)
+        .fetch_optional(&db_conn.reader)
         .await?;
 
     Ok(result.is_some())
 }
 
-async fn init_default_values(db_conn: Arc<Mutex<SqlitePool>>) -> AppResult<()> {
+async fn init_default_values(db_conn: Arc<DbPools>, network: Network) -> AppResult<()> {
     let default_epoch = sqlx::query!(
         r#"
             SELECT *
@@ -27,7 +34,7 @@ async fn init_default_values(db_conn: Arc<Mutex<SqlitePool>>) -> AppResult<()> {
         "#,
         0
     )
-    .fetch_optional(&*db_conn.lock().await)
+    .fetch_optional(&db_conn.writer)
     .await?;
 
     let default_slot = sqlx::query!(
@@ -38,7 +45,7 @@ async fn init_default_values(db_conn: Arc<Mutex<SqlitePool>>) -> AppResult<()> {
         "#,
         0
     )
-    .fetch_optional(&*db_conn.lock().await)
+    .fetch_optional(&db_conn.writer)
     .await?;
 
     let default_epoch_data = EpochData {
@@ -67,6 +74,7 @@ async fn init_default_values(db_conn: Arc<Mutex<SqlitePool>>) -> AppResult<()> {
     let default_slot_data = SlotData {
         attestationscount: 0,
         attesterslashingscount: 0,
+        blob_kzg_commitments_count: None,
         blockroot: "0x4d611d5b93fdab69013a7f0a2f961caca0c853f87cfe9595fe50038163079360".to_string(),
         depositscount: 21063,
         epoch: 0,
@@ -74,8 +82,10 @@ async fn init_default_values(db_conn: Arc<Mutex<SqlitePool>>) -> AppResult<()> {
         eth1data_depositcount: 0,
         eth1data_depositroot: "0x0000000000000000000000000000000000000000000000000000000000000000".to_string(),
         exec_base_fee_per_gas: None,
+        exec_blob_gas_used: None,
         exec_block_hash: None,
         exec_block_number: None,
+        exec_excess_blob_gas: None,
         exec_extra_data: None,
         exec_fee_recipient: None,
         exec_gas_limit: None,
@@ -89,6 +99,7 @@ async fn init_default_values(db_conn: Arc<Mutex<SqlitePool>>) -> AppResult<()> {
         exec_transactions_count: 0,
         graffiti: "0x0000000000000000000000000000000000000000000000000000000000000000".to_string(),
         graffiti_text: "".to_string(),
+        parent_beacon_block_root: None,
         parentroot: "0x0000000000000000000000000000000000000000000000000000000000000000".to_string(),
         proposer: 2147483647,
         proposerslashingscount: 0,
@@ -104,24 +115,59 @@ async fn init_default_values(db_conn: Arc<Mutex<SqlitePool>>) -> AppResult<()> {
         withdrawalcount: 0
     };
 
+    // The placeholders above are mainnet's genesis epoch and slot, other networks get empty ones.
+    let (default_epoch_data, default_slot_data) = if network == MAINNET {
+        (default_epoch_data, default_slot_data)
+    } else {
+        let zero_hash = default_slot_data.parentroot.clone();
+        (
+            EpochData {
+                attestationscount: 0,
+                averagevalidatorbalance: 0,
+                blockscount: 0,
+                eligibleether: 0,
+                globalparticipationrate: 0.0,
+                missedblocks: 0,
+                proposedblocks: 0,
+                totalvalidatorbalance: 0,
+                ts: Utc
+                    .timestamp_opt(network.genesis_timestamp, 0)
+                    .single()
+                    .map(|genesis| genesis.to_rfc3339_opts(SecondsFormat::Secs, true))
+                    .unwrap_or_default(),
+                validatorscount: 0,
+                votedether: 0,
+                ..default_epoch_data
+            },
+            SlotData {
+                blockroot: zero_hash.clone(),
+                depositscount: 0,
+                stateroot: zero_hash,
+                ..default_slot_data
+            },
+        )
+    };
+
     match (default_epoch.is_some(), default_slot.is_some()) {
         (false, false) => {
-            insert_epoch(Arc::clone(&db_conn), default_epoch_data).await?;
+            insert_epoch(Arc::clone(&db_conn), default_epoch_data, ChangeSource::Seed).await?;
             insert_slot(
                 Arc::clone(&db_conn),
                 default_slot_data.slot,
                 &default_slot_data,
+                ChangeSource::Seed,
             )
             .await?;
         }
         (false, true) => {
-            insert_epoch(Arc::clone(&db_conn), default_epoch_data).await?;
+            insert_epoch(Arc::clone(&db_conn), default_epoch_data, ChangeSource::Seed).await?;
         }
         (true, false) => {
             insert_slot(
                 Arc::clone(&db_conn),
                 default_slot_data.slot,
                 &default_slot_data,
+                ChangeSource::Seed,
             )
             .await?;
         }
@@ -133,8 +179,8 @@ async fn init_default_values(db_conn: Arc<Mutex<SqlitePool>>) -> AppResult<()> {
     Ok(())
 }
 
-pub async fn setup_db(db_url: &str, db_conn: Arc<Mutex<SqlitePool>>) -> AppResult<()> {
-    // let db_conn = db_conn.lock().await;
+/// Creates the database file when missing, connects to it and brings its schema up to date.
+pub async fn open_db(db_url: &str) -> AppResult<Arc<DbPools>> {
     if !Sqlite::database_exists(db_url).await.unwrap_or(false) {
         println!("Creating Database: {}", db_url);
 
@@ -144,25 +190,72 @@ pub async fn setup_db(db_url: &str, db_conn: Arc<Mutex<SqlitePool>>) -> AppResul
         }
     } else {
         println!("Using the existing database");
+    }
+
+    let db_conn = Arc::new(DbPools::connect(db_url).await?);
+    migrations::run_migrations(Arc::clone(&db_conn)).await?;
 
-        let slot_table_exists = table_exists(Arc::clone(&db_conn), "slot_data").await?;
-        let epoch_table_exists = table_exists(Arc::clone(&db_conn), "epoch_data").await?;
+    Ok(db_conn)
+}
 
-        if slot_table_exists && epoch_table_exists {
-            println!("Tables {} & {} already exists", "slot_data", "epoch_data");
+/// Records which network the database follows, refusing to open one that holds another network.
+pub async fn ensure_chain_config(db_conn: Arc<DbPools>, network: Network) -> AppResult<()> {
+    let stored = sqlx::query!(
+        r#"
+            SELECT network
+            FROM chain_config
+            WHERE id = 1
+        "#
+    )
+    .fetch_optional(&db_conn.writer)
+    .await?;
 
-            init_default_values(db_conn).await?;
-        } else {
-            sqlx::migrate!("./migrations")
-                .run(&*db_conn.lock().await)
-                .await?;
+    match stored {
+        Some(stored) if stored.network != network.name => Err(format!(
+            "The database holds {}, not {}",
+            stored.network, network.name
+        )
+        .into()),
+        Some(_) => Ok(()),
+        None => {
+            sqlx::query!(
+                r#"
+                    INSERT INTO chain_config (
+                        id,
+                        network,
+                        genesis_timestamp,
+                        seconds_per_slot,
+                        slots_per_epoch
+                    )
+                    VALUES (1, ?, ?, ?, ?)
+                "#,
+                network.name,
+                network.genesis_timestamp,
+                network.seconds_per_slot,
+                network.slots_per_epoch
+            )
+            .execute(&db_conn.writer)
+            .await?;
+
+            Ok(())
         }
     }
+}
 
-    Ok(())
+pub async fn setup_db(db_url: &str, network: Network) -> AppResult<Arc<DbPools>> {
+    let db_conn = open_db(db_url).await?;
+
+    ensure_chain_config(Arc::clone(&db_conn), network).await?;
+    init_default_values(Arc::clone(&db_conn), network).await?;
+
+    Ok(db_conn)
 }
 
-pub async fn insert_epoch(db_conn: Arc<Mutex<SqlitePool>>, epoch_data: EpochData) -> AppResult<()> {
+pub async fn insert_epoch(
+    db_conn: Arc<DbPools>,
+    epoch_data: EpochData,
+    source: ChangeSource,
+) -> AppResult<()> {
     let existing_epoch = sqlx::query!(
         r#"
             SELECT * 
@@ -171,12 +264,14 @@ pub async fn insert_epoch(db_conn: Arc<Mutex<SqlitePool>>, epoch_data: EpochData
         "#,
         epoch_data.epoch
     )
-    .fetch_optional(&*db_conn.lock().await)
+    .fetch_optional(&db_conn.writer)
     .await?;
 
     if existing_epoch.is_some() {
         return Ok(());
     } else {
+        let mut tx = db_conn.writer.begin().await?;
+
         sqlx::query!(
             r#"
                 INSERT INTO epoch_data (
@@ -226,18 +321,31 @@ pub async fn insert_epoch(db_conn: Arc<Mutex<SqlitePool>>, epoch_data: EpochData
             epoch_data.votedether,
             epoch_data.withdrawalcount
         )
-        .execute(&*db_conn.lock().await)
+        .execute(&mut *tx)
         .await?;
+
+        record_epoch_version(&mut tx, epoch_data.epoch, source).await?;
+        record_change(
+            &mut tx,
+            ChangeEntity::Epoch,
+            epoch_data.epoch,
+            ChangeKind::Inserted,
+            &epoch_data,
+        )
+        .await?;
+        tx.commit().await?;
     }
     Ok(())
 }
 
 pub async fn update_epoch(
-    db_conn: Arc<Mutex<SqlitePool>>,
+    db_conn: Arc<DbPools>,
     epoch_number: i64,
     updated_epoch_data: EpochData,
+    source: ChangeSource,
 ) -> AppResult<()> {
-    let existence_check = sqlx::query!(
+    let existing_epoch = sqlx::query_as!(
+        EpochData,
         r#"
             SELECT *
             FROM epoch_data 
@@ -245,10 +353,17 @@ pub async fn update_epoch(
         "#,
         epoch_number
     )
-    .fetch_optional(&*db_conn.lock().await)
+    .fetch_optional(&db_conn.writer)
     .await?;
 
-    if existence_check.is_some() {
+    if let Some(existing_epoch) = existing_epoch {
+        // Unchanged re-fetches are skipped so they do not pile up in the history.
+        if existing_epoch == updated_epoch_data {
+            return Ok(());
+        }
+
+        let mut tx = db_conn.writer.begin().await?;
+
         sqlx::query!(
             r#"
             UPDATE epoch_data
@@ -299,8 +414,19 @@ pub async fn update_epoch(
             updated_epoch_data.withdrawalcount,
             epoch_number
         )
-        .execute(&*db_conn.lock().await)
+        .execute(&mut *tx)
         .await?;
+
+        record_epoch_version(&mut tx, updated_epoch_data.epoch, source).await?;
+        record_change(
+            &mut tx,
+            ChangeEntity::Epoch,
+            updated_epoch_data.epoch,
+            ChangeKind::of_epoch_update(&existing_epoch, &updated_epoch_data),
+            &updated_epoch_data,
+        )
+        .await?;
+        tx.commit().await?;
     } else {
         return Err(Box::new(std::io::Error::new(
             std::io::ErrorKind::NotFound,
@@ -312,22 +438,27 @@ pub async fn update_epoch(
 }
 
 pub async fn insert_slot(
-    db_conn: Arc<Mutex<SqlitePool>>,
+    db_conn: Arc<DbPools>,
     slot_number: i64,
     slot: &SlotData,
+    source: ChangeSource,
 ) -> AppResult<()> {
     let existing_slot = sqlx::query!(r#"SELECT * FROM slot_data WHERE slot = ?"#, slot_number)
-        .fetch_optional(&*db_conn.lock().await)
+        .fetch_optional(&db_conn.writer)
         .await?;
 
     if existing_slot.is_some() {
         return Ok(());
     } else {
+        let row = SlotDataRow::try_from(slot)?;
+        let mut tx = db_conn.writer.begin().await?;
+
         sqlx::query!(
             r#"
                 INSERT INTO slot_data (
                     attestationscount,
                     attesterslashingscount,
+                    blob_kzg_commitments_count,
                     blockroot,
                     depositscount,
                     epoch,
@@ -335,8 +466,10 @@ pub async fn insert_slot(
                     eth1data_depositcount,
                     eth1data_depositroot,
                     exec_base_fee_per_gas,
+                    exec_blob_gas_used,
                     exec_block_hash,
                     exec_block_number,
+                    exec_excess_blob_gas,
                     exec_extra_data,
                     exec_fee_recipient,
                     exec_gas_limit,
@@ -350,6 +483,7 @@ pub async fn insert_slot(
                     exec_transactions_count,
                     graffiti,
                     graffiti_text,
+                    parent_beacon_block_root,
                     parentroot,
                     proposer,
                     proposerslashingscount,
@@ -364,59 +498,77 @@ pub async fn insert_slot(
                     voluntaryexitscount,
                     withdrawalcount
                 )
-                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
-                slot.attestationscount,
-                slot.attesterslashingscount,
-                slot.blockroot,
-                slot.depositscount,
-                slot.epoch,
-                slot.eth1data_blockhash,
-                slot.eth1data_depositcount,
-                slot.eth1data_depositroot,
-                slot.exec_base_fee_per_gas,
-                slot.exec_block_hash,
-                slot.exec_block_number,
-                slot.exec_extra_data,
-                slot.exec_fee_recipient,
-                slot.exec_gas_limit,
-                slot.exec_gas_used,
-                slot.exec_logs_bloom,
-                slot.exec_parent_hash,
-                slot.exec_random,
-                slot.exec_receipts_root,
-                slot.exec_state_root,
-                slot.exec_timestamp,
-                slot.exec_transactions_count,
-                slot.graffiti,
-                slot.graffiti_text,
-                slot.parentroot,
-                slot.proposer,
-                slot.proposerslashingscount,
-                slot.randaoreveal,
-                slot.signature,
-                slot.slot,
-                slot.stateroot,
-                slot.status,
-                slot.syncaggregate_bits,
-                slot.syncaggregate_participation,
-                slot.syncaggregate_signature,
-                slot.voluntaryexitscount,
-                slot.withdrawalcount,
+                row.attestationscount,
+                row.attesterslashingscount,
+                row.blob_kzg_commitments_count,
+                row.blockroot,
+                row.depositscount,
+                row.epoch,
+                row.eth1data_blockhash,
+                row.eth1data_depositcount,
+                row.eth1data_depositroot,
+                row.exec_base_fee_per_gas,
+                row.exec_blob_gas_used,
+                row.exec_block_hash,
+                row.exec_block_number,
+                row.exec_excess_blob_gas,
+                row.exec_extra_data,
+                row.exec_fee_recipient,
+                row.exec_gas_limit,
+                row.exec_gas_used,
+                row.exec_logs_bloom,
+                row.exec_parent_hash,
+                row.exec_random,
+                row.exec_receipts_root,
+                row.exec_state_root,
+                row.exec_timestamp,
+                row.exec_transactions_count,
+                row.graffiti,
+                row.graffiti_text,
+                row.parent_beacon_block_root,
+                row.parentroot,
+                row.proposer,
+                row.proposerslashingscount,
+                row.randaoreveal,
+                row.signature,
+                row.slot,
+                row.stateroot,
+                row.status,
+                row.syncaggregate_bits,
+                row.syncaggregate_participation,
+                row.syncaggregate_signature,
+                row.voluntaryexitscount,
+                row.withdrawalcount,
         )
-        .execute(&*db_conn.lock().await)
+        .execute(&mut *tx)
         .await?;
+
+        record_slot_version(&mut tx, slot_number, source).await?;
+        record_change(
+            &mut tx,
+            ChangeEntity::Slot,
+            slot_number,
+            ChangeKind::Inserted,
+            slot,
+        )
+        .await?;
+        write_blob_sidecar_summary(&mut tx, slot).await?;
+        tx.commit().await?;
     }
 
     Ok(())
 }
 
 pub async fn update_slot(
-    db_conn: Arc<Mutex<SqlitePool>>,
+    db_conn: Arc<DbPools>,
     slot_number: i64,
     updated_slot: SlotData,
+    source: ChangeSource,
 ) -> AppResult<()> {
-    let existing_slot = sqlx::query!(
+    let existing_slot = sqlx::query_as!(
+        SlotDataRow,
         r#"
             SELECT * 
             FROM slot_data 
@@ -424,15 +576,24 @@ pub async fn update_slot(
         "#,
         slot_number
     )
-    .fetch_optional(&*db_conn.lock().await)
+    .fetch_optional(&db_conn.writer)
     .await?;
 
-    if existing_slot.is_some() {
+    if let Some(existing_slot) = existing_slot {
+        let row = SlotDataRow::try_from(&updated_slot)?;
+        // Unchanged re-fetches are skipped so they do not pile up in the history.
+        if existing_slot == row {
+            return Ok(());
+        }
+
+        let mut tx = db_conn.writer.begin().await?;
+
         sqlx::query!(
             r#"
                 UPDATE slot_data
                 SET attestationscount = ?,
                     attesterslashingscount = ?,
+                    blob_kzg_commitments_count = ?,
                     blockroot = ?,
                     depositscount = ?,
                     epoch = ?,
@@ -440,8 +601,10 @@ pub async fn update_slot(
                     eth1data_depositcount = ?,
                     eth1data_depositroot = ?,
                     exec_base_fee_per_gas = ?,
+                    exec_blob_gas_used = ?,
                     exec_block_hash = ?,
                     exec_block_number = ?,
+                    exec_excess_blob_gas = ?,
                     exec_extra_data = ?,
                     exec_fee_recipient = ?,
                     exec_gas_limit = ?,
@@ -455,6 +618,7 @@ pub async fn update_slot(
                     exec_transactions_count = ?,
                     graffiti = ?,
                     graffiti_text = ?,
+                    parent_beacon_block_root = ?,
                     parentroot = ?,
                     proposer = ?,
                     proposerslashingscount = ?,
@@ -469,46 +633,62 @@ pub async fn update_slot(
                     withdrawalcount = ?
                 WHERE slot = ?;
             "#,
-            updated_slot.attestationscount,
-            updated_slot.attesterslashingscount,
-            updated_slot.blockroot,
-            updated_slot.depositscount,
-            updated_slot.epoch,
-            updated_slot.eth1data_blockhash,
-            updated_slot.eth1data_depositcount,
-            updated_slot.eth1data_depositroot,
-            updated_slot.exec_base_fee_per_gas,
-            updated_slot.exec_block_hash,
-            updated_slot.exec_block_number,
-            updated_slot.exec_extra_data,
-            updated_slot.exec_fee_recipient,
-            updated_slot.exec_gas_limit,
-            updated_slot.exec_gas_used,
-            updated_slot.exec_logs_bloom,
-            updated_slot.exec_parent_hash,
-            updated_slot.exec_random,
-            updated_slot.exec_receipts_root,
-            updated_slot.exec_state_root,
-            updated_slot.exec_timestamp,
-            updated_slot.exec_transactions_count,
-            updated_slot.graffiti,
-            updated_slot.graffiti_text,
-            updated_slot.parentroot,
-            updated_slot.proposer,
-            updated_slot.proposerslashingscount,
-            updated_slot.randaoreveal,
-            updated_slot.signature,
-            updated_slot.stateroot,
-            updated_slot.status,
-            updated_slot.syncaggregate_bits,
-            updated_slot.syncaggregate_participation,
-            updated_slot.syncaggregate_signature,
-            updated_slot.voluntaryexitscount,
-            updated_slot.withdrawalcount,
+            row.attestationscount,
+            row.attesterslashingscount,
+            row.blob_kzg_commitments_count,
+            row.blockroot,
+            row.depositscount,
+            row.epoch,
+            row.eth1data_blockhash,
+            row.eth1data_depositcount,
+            row.eth1data_depositroot,
+            row.exec_base_fee_per_gas,
+            row.exec_blob_gas_used,
+            row.exec_block_hash,
+            row.exec_block_number,
+            row.exec_excess_blob_gas,
+            row.exec_extra_data,
+            row.exec_fee_recipient,
+            row.exec_gas_limit,
+            row.exec_gas_used,
+            row.exec_logs_bloom,
+            row.exec_parent_hash,
+            row.exec_random,
+            row.exec_receipts_root,
+            row.exec_state_root,
+            row.exec_timestamp,
+            row.exec_transactions_count,
+            row.graffiti,
+            row.graffiti_text,
+            row.parent_beacon_block_root,
+            row.parentroot,
+            row.proposer,
+            row.proposerslashingscount,
+            row.randaoreveal,
+            row.signature,
+            row.stateroot,
+            row.status,
+            row.syncaggregate_bits,
+            row.syncaggregate_participation,
+            row.syncaggregate_signature,
+            row.voluntaryexitscount,
+            row.withdrawalcount,
             slot_number
         )
-        .execute(&*db_conn.lock().await)
+        .execute(&mut *tx)
+        .await?;
+
+        record_slot_version(&mut tx, slot_number, source).await?;
+        record_change(
+            &mut tx,
+            ChangeEntity::Slot,
+            slot_number,
+            ChangeKind::of_slot_update(&SlotData::from(existing_slot), &updated_slot),
+            &updated_slot,
+        )
         .await?;
+        write_blob_sidecar_summary(&mut tx, &updated_slot).await?;
+        tx.commit().await?;
 
         Ok(())
     } else {
@@ -519,9 +699,606 @@ pub async fn update_slot(
     }
 }
 
+/// Appends an event to `change_outbox`, called inside the transaction writing the row.
+async fn record_change<T: Serialize>(
+    conn: &mut SqliteConnection,
+    entity: ChangeEntity,
+    key: i64,
+    kind: ChangeKind,
+    row: &T,
+) -> AppResult<()> {
+    let recorded_at = now_millis();
+    let entity = entity.as_str();
+    let kind = kind.as_str();
+    let payload = serde_json::to_string(row)?;
+
+    sqlx::query!(
+        r#"
+            INSERT INTO change_outbox (recorded_at, entity, key, kind, payload)
+            VALUES (?, ?, ?, ?, ?)
+        "#,
+        recorded_at,
+        entity,
+        key,
+        kind,
+        payload
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Events with `seq` above `since`, oldest first.
+pub async fn get_changes(
+    db_conn: Arc<DbPools>,
+    since: i64,
+    limit: i64,
+) -> AppResult<Vec<ChangeEvent>> {
+    let rows = sqlx::query!(
+        r#"
+            SELECT seq, recorded_at, entity, key, kind, payload
+            FROM change_outbox
+            WHERE seq > ?
+            ORDER BY seq
+            LIMIT ?
+        "#,
+        since,
+        limit
+    )
+    .fetch_all(&db_conn.reader)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            ChangeEvent::new(
+                row.seq,
+                row.recorded_at,
+                row.entity,
+                row.key,
+                row.kind,
+                &row.payload,
+            )
+        })
+        .collect())
+}
+
+pub async fn get_sink_cursor(db_conn: Arc<DbPools>, sink: &str) -> AppResult<i64> {
+    let cursor = sqlx::query!(
+        r#"
+            SELECT seq
+            FROM sink_cursors
+            WHERE sink = ?
+        "#,
+        sink
+    )
+    .fetch_optional(&db_conn.reader)
+    .await?;
+
+    Ok(cursor.map_or(0, |cursor| cursor.seq))
+}
+
+pub async fn set_sink_cursor(db_conn: Arc<DbPools>, sink: &str, seq: i64) -> AppResult<()> {
+    let updated_at = now_millis();
+
+    sqlx::query!(
+        r#"
+            INSERT INTO sink_cursors (sink, seq, updated_at)
+            VALUES (?, ?, ?)
+            ON CONFLICT (sink) DO UPDATE SET
+                seq = excluded.seq,
+                updated_at = excluded.updated_at
+        "#,
+        sink,
+        seq,
+        updated_at
+    )
+    .execute(&db_conn.writer)
+    .await?;
+
+    Ok(())
+}
+
+/// Copies the stored epoch into `epoch_data_history` as a new version.
+async fn record_epoch_version(
+    conn: &mut SqliteConnection,
+    epoch_number: i64,
+    source: ChangeSource,
+) -> AppResult<()> {
+    let recorded_at = now_millis();
+    let source = source.as_str();
+
+    sqlx::query!(
+        r#"
+            INSERT INTO epoch_data_history (
+                recorded_at,
+                source,
+                attestationscount,
+                attesterslashingscount,
+                averagevalidatorbalance,
+                blockscount,
+                depositscount,
+                eligibleether,
+                epoch,
+                finalized,
+                globalparticipationrate,
+                missedblocks,
+                orphanedblocks,
+                proposedblocks,
+                proposerslashingscount,
+                rewards_exported,
+                scheduledblocks,
+                totalvalidatorbalance,
+                ts,
+                validatorscount,
+                voluntaryexitscount,
+                votedether,
+                withdrawalcount
+            )
+            SELECT
+                ?,
+                ?,
+                attestationscount,
+                attesterslashingscount,
+                averagevalidatorbalance,
+                blockscount,
+                depositscount,
+                eligibleether,
+                epoch,
+                finalized,
+                globalparticipationrate,
+                missedblocks,
+                orphanedblocks,
+                proposedblocks,
+                proposerslashingscount,
+                rewards_exported,
+                scheduledblocks,
+                totalvalidatorbalance,
+                ts,
+                validatorscount,
+                voluntaryexitscount,
+                votedether,
+                withdrawalcount
+            FROM epoch_data
+            WHERE epoch = ?
+        "#,
+        recorded_at,
+        source,
+        epoch_number
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Copies the stored slot into `slot_data_history` as a new version.
+async fn record_slot_version(
+    conn: &mut SqliteConnection,
+    slot_number: i64,
+    source: ChangeSource,
+) -> AppResult<()> {
+    let recorded_at = now_millis();
+    let source = source.as_str();
+
+    sqlx::query!(
+        r#"
+            INSERT INTO slot_data_history (
+                recorded_at,
+                source,
+                attestationscount,
+                attesterslashingscount,
+                blob_kzg_commitments_count,
+                blockroot,
+                depositscount,
+                epoch,
+                eth1data_blockhash,
+                eth1data_depositcount,
+                eth1data_depositroot,
+                exec_base_fee_per_gas,
+                exec_blob_gas_used,
+                exec_block_hash,
+                exec_block_number,
+                exec_excess_blob_gas,
+                exec_extra_data,
+                exec_fee_recipient,
+                exec_gas_limit,
+                exec_gas_used,
+                exec_logs_bloom,
+                exec_parent_hash,
+                exec_random,
+                exec_receipts_root,
+                exec_state_root,
+                exec_timestamp,
+                exec_transactions_count,
+                graffiti,
+                graffiti_text,
+                parent_beacon_block_root,
+                parentroot,
+                proposer,
+                proposerslashingscount,
+                randaoreveal,
+                signature,
+                slot,
+                stateroot,
+                status,
+                syncaggregate_bits,
+                syncaggregate_participation,
+                syncaggregate_signature,
+                voluntaryexitscount,
+                withdrawalcount
+            )
+            SELECT
+                ?,
+                ?,
+                attestationscount,
+                attesterslashingscount,
+                blob_kzg_commitments_count,
+                blockroot,
+                depositscount,
+                epoch,
+                eth1data_blockhash,
+                eth1data_depositcount,
+                eth1data_depositroot,
+                exec_base_fee_per_gas,
+                exec_blob_gas_used,
+                exec_block_hash,
+                exec_block_number,
+                exec_excess_blob_gas,
+                exec_extra_data,
+                exec_fee_recipient,
+                exec_gas_limit,
+                exec_gas_used,
+                exec_logs_bloom,
+                exec_parent_hash,
+                exec_random,
+                exec_receipts_root,
+                exec_state_root,
+                exec_timestamp,
+                exec_transactions_count,
+                graffiti,
+                graffiti_text,
+                parent_beacon_block_root,
+                parentroot,
+                proposer,
+                proposerslashingscount,
+                randaoreveal,
+                signature,
+                slot,
+                stateroot,
+                status,
+                syncaggregate_bits,
+                syncaggregate_participation,
+                syncaggregate_signature,
+                voluntaryexitscount,
+                withdrawalcount
+            FROM slot_data
+            WHERE slot = ?
+        "#,
+        recorded_at,
+        source,
+        slot_number
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// The version of the epoch rish had stored at `as_of` (unix milliseconds).
+pub async fn get_epoch_data_as_of(
+    db_conn: Arc<DbPools>,
+    epoch_number: i64,
+    as_of: i64,
+) -> AppResult<Option<EpochData>> {
+    let epoch_data = sqlx::query_as!(
+        EpochData,
+        r#"
+            SELECT
+                attestationscount,
+                attesterslashingscount,
+                averagevalidatorbalance,
+                blockscount,
+                depositscount,
+                eligibleether,
+                epoch,
+                finalized,
+                globalparticipationrate,
+                missedblocks,
+                orphanedblocks,
+                proposedblocks,
+                proposerslashingscount,
+                rewards_exported,
+                scheduledblocks,
+                totalvalidatorbalance,
+                ts,
+                validatorscount,
+                voluntaryexitscount,
+                votedether,
+                withdrawalcount
+            FROM epoch_data_history
+            WHERE epoch = ? AND recorded_at <= ?
+            ORDER BY recorded_at DESC, history_id DESC
+            LIMIT 1
+        "#,
+        epoch_number,
+        as_of
+    )
+    .fetch_optional(&db_conn.reader)
+    .await?;
+
+    Ok(epoch_data)
+}
+
+/// The version of the slot rish had stored at `as_of` (unix milliseconds).
+pub async fn get_slot_as_of(
+    db_conn: Arc<DbPools>,
+    slot_number: i64,
+    as_of: i64,
+) -> AppResult<Option<SlotData>> {
+    let slot = sqlx::query_as!(
+        SlotDataRow,
+        r#"
+            SELECT
+                attestationscount,
+                attesterslashingscount,
+                blob_kzg_commitments_count,
+                blockroot,
+                depositscount,
+                epoch,
+                eth1data_blockhash,
+                eth1data_depositcount,
+                eth1data_depositroot,
+                exec_base_fee_per_gas,
+                exec_blob_gas_used,
+                exec_block_hash,
+                exec_block_number,
+                exec_excess_blob_gas,
+                exec_extra_data,
+                exec_fee_recipient,
+                exec_gas_limit,
+                exec_gas_used,
+                exec_logs_bloom,
+                exec_parent_hash,
+                exec_random,
+                exec_receipts_root,
+                exec_state_root,
+                exec_timestamp,
+                exec_transactions_count,
+                graffiti,
+                graffiti_text,
+                parent_beacon_block_root,
+                parentroot,
+                proposer,
+                proposerslashingscount,
+                randaoreveal,
+                signature,
+                slot,
+                stateroot,
+                status,
+                syncaggregate_bits,
+                syncaggregate_participation,
+                syncaggregate_signature,
+                voluntaryexitscount,
+                withdrawalcount
+            FROM slot_data_history
+            WHERE slot = ? AND recorded_at <= ?
+            ORDER BY recorded_at DESC, history_id DESC
+            LIMIT 1
+        "#,
+        slot_number,
+        as_of
+    )
+    .fetch_optional(&db_conn.reader)
+    .await?;
+
+    Ok(slot.map(SlotData::from))
+}
+
+/// Every recorded version of the epoch, oldest first.
+pub async fn get_epoch_data_history(
+    db_conn: Arc<DbPools>,
+    epoch_number: i64,
+) -> AppResult<Vec<RowVersion<EpochData>>> {
+    let mut tx = db_conn.reader.begin().await?;
+
+    let versions = sqlx::query!(
+        r#"
+            SELECT recorded_at, source
+            FROM epoch_data_history
+            WHERE epoch = ?
+            ORDER BY recorded_at ASC, history_id ASC
+        "#,
+        epoch_number
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let epochs = sqlx::query_as!(
+        EpochData,
+        r#"
+            SELECT
+                attestationscount,
+                attesterslashingscount,
+                averagevalidatorbalance,
+                blockscount,
+                depositscount,
+                eligibleether,
+                epoch,
+                finalized,
+                globalparticipationrate,
+                missedblocks,
+                orphanedblocks,
+                proposedblocks,
+                proposerslashingscount,
+                rewards_exported,
+                scheduledblocks,
+                totalvalidatorbalance,
+                ts,
+                validatorscount,
+                voluntaryexitscount,
+                votedether,
+                withdrawalcount
+            FROM epoch_data_history
+            WHERE epoch = ?
+            ORDER BY recorded_at ASC, history_id ASC
+        "#,
+        epoch_number
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(versions
+        .into_iter()
+        .zip(epochs)
+        .map(|(version, epoch_data)| {
+            RowVersion::new(version.recorded_at, version.source, epoch_data)
+        })
+        .collect())
+}
+
+/// Every recorded version of the slot, oldest first.
+pub async fn get_slot_history(
+    db_conn: Arc<DbPools>,
+    slot_number: i64,
+) -> AppResult<Vec<RowVersion<SlotData>>> {
+    let mut tx = db_conn.reader.begin().await?;
+
+    let versions = sqlx::query!(
+        r#"
+            SELECT recorded_at, source
+            FROM slot_data_history
+            WHERE slot = ?
+            ORDER BY recorded_at ASC, history_id ASC
+        "#,
+        slot_number
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let slots = sqlx::query_as!(
+        SlotDataRow,
+        r#"
+            SELECT
+                attestationscount,
+                attesterslashingscount,
+                blob_kzg_commitments_count,
+                blockroot,
+                depositscount,
+                epoch,
+                eth1data_blockhash,
+                eth1data_depositcount,
+                eth1data_depositroot,
+                exec_base_fee_per_gas,
+                exec_blob_gas_used,
+                exec_block_hash,
+                exec_block_number,
+                exec_excess_blob_gas,
+                exec_extra_data,
+                exec_fee_recipient,
+                exec_gas_limit,
+                exec_gas_used,
+                exec_logs_bloom,
+                exec_parent_hash,
+                exec_random,
+                exec_receipts_root,
+                exec_state_root,
+                exec_timestamp,
+                exec_transactions_count,
+                graffiti,
+                graffiti_text,
+                parent_beacon_block_root,
+                parentroot,
+                proposer,
+                proposerslashingscount,
+                randaoreveal,
+                signature,
+                slot,
+                stateroot,
+                status,
+                syncaggregate_bits,
+                syncaggregate_participation,
+                syncaggregate_signature,
+                voluntaryexitscount,
+                withdrawalcount
+            FROM slot_data_history
+            WHERE slot = ?
+            ORDER BY recorded_at ASC, history_id ASC
+        "#,
+        slot_number
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(versions
+        .into_iter()
+        .zip(slots)
+        .map(|(version, slot)| RowVersion::new(version.recorded_at, version.source, slot.into()))
+        .collect())
+}
+
+/// Upserts the slot's `blob_sidecar_summary` row, or deletes it when the slot no longer carries
+/// blob data, called inside the transaction writing the slot.
+async fn write_blob_sidecar_summary(conn: &mut SqliteConnection, slot: &SlotData) -> AppResult<()> {
+    let Some(blob_summary) = BlobSidecarSummary::from_slot(slot) else {
+        sqlx::query!("DELETE FROM blob_sidecar_summary WHERE slot = ?", slot.slot)
+            .execute(&mut *conn)
+            .await?;
+        return Ok(());
+    };
+
+    sqlx::query!(
+        r#"
+            INSERT OR REPLACE INTO blob_sidecar_summary (
+                slot,
+                epoch,
+                blob_count,
+                blob_gas_used,
+                excess_blob_gas,
+                total_blob_size
+            )
+            VALUES (?, ?, ?, ?, ?, ?)
+        "#,
+        blob_summary.slot,
+        blob_summary.epoch,
+        blob_summary.blob_count,
+        blob_summary.blob_gas_used,
+        blob_summary.excess_blob_gas,
+        blob_summary.total_blob_size
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_blob_sidecar_summary(
+    db_conn: Arc<DbPools>,
+    slot_number: i64,
+) -> AppResult<Option<BlobSidecarSummary>> {
+    let blob_summary = sqlx::query_as!(
+        BlobSidecarSummary,
+        r#"
+            SELECT *
+            FROM blob_sidecar_summary
+            WHERE slot = ?
+        "#,
+        slot_number
+    )
+    .fetch_optional(&db_conn.reader)
+    .await?;
+
+    Ok(blob_summary)
+}
+
 // Create Models for Database, and handle bool <-> i64 relationship
 // of database and dto
-pub async fn get_latest_epoch_data(db_conn: Arc<Mutex<SqlitePool>>) -> AppResult<EpochData> {
+pub async fn get_latest_epoch_data(db_conn: Arc<DbPools>) -> AppResult<EpochData> {
     let latest_epoch_data = sqlx::query_as!(
         EpochData,
         r#"
@@ -531,15 +1308,250 @@ pub async fn get_latest_epoch_data(db_conn: Arc<Mutex<SqlitePool>>) -> AppResult
             LIMIT 1;
         "#
     )
-    .fetch_one(&*db_conn.lock().await)
+    .fetch_one(&db_conn.reader)
     .await?;
 
     Ok(latest_epoch_data)
 }
 
-pub async fn get_latest_unexecuted_slot(db_conn: Arc<Mutex<SqlitePool>>) -> AppResult<SlotData> {
+pub async fn get_latest_epochs(db_conn: Arc<DbPools>, count: i64) -> AppResult<Vec<EpochData>> {
+    let epochs = sqlx::query_as!(
+        EpochData,
+        r#"
+            SELECT *
+            FROM epoch_data
+            ORDER BY epoch DESC
+            LIMIT ?;
+        "#,
+        count
+    )
+    .fetch_all(&db_conn.reader)
+    .await?;
+
+    Ok(epochs)
+}
+
+pub async fn get_epoch_data(
+    db_conn: Arc<DbPools>,
+    epoch_number: i64,
+) -> AppResult<Option<EpochData>> {
+    let epoch_data = sqlx::query_file_as!(EpochData, "queries/get_epoch_data.sql", epoch_number)
+        .fetch_optional(&db_conn.reader)
+        .await?;
+
+    Ok(epoch_data)
+}
+
+pub async fn get_earliest_epoch_data(db_conn: Arc<DbPools>) -> AppResult<Option<EpochData>> {
+    let earliest_epoch_data = sqlx::query_as!(
+        EpochData,
+        r#"
+            SELECT *
+            FROM epoch_data
+            ORDER BY epoch ASC
+            LIMIT 1;
+        "#
+    )
+    .fetch_optional(&db_conn.reader)
+    .await?;
+
+    Ok(earliest_epoch_data)
+}
+
+pub async fn get_epochs_in_range(
+    db_conn: Arc<DbPools>,
+    from_epoch: i64,
+    to_epoch: i64,
+) -> AppResult<Vec<EpochData>> {
+    let epochs = sqlx::query_file_as!(
+        EpochData,
+        "queries/get_epochs_in_range.sql",
+        from_epoch,
+        to_epoch
+    )
+    .fetch_all(&db_conn.reader)
+    .await?;
+
+    Ok(epochs)
+}
+
+pub async fn get_slots_in_range(
+    db_conn: Arc<DbPools>,
+    from_slot: i64,
+    to_slot: i64,
+) -> AppResult<Vec<SlotData>> {
+    let slots = sqlx::query_file_as!(
+        SlotDataRow,
+        "queries/get_slots_in_range.sql",
+        from_slot,
+        to_slot
+    )
+    .fetch_all(&db_conn.reader)
+    .await?;
+
+    Ok(slots.into_iter().map(SlotData::from).collect())
+}
+
+pub async fn get_slot(db_conn: Arc<DbPools>, slot_number: i64) -> AppResult<Option<SlotData>> {
+    let slot = sqlx::query_file_as!(SlotDataRow, "queries/get_slot.sql", slot_number)
+        .fetch_optional(&db_conn.reader)
+        .await?;
+
+    Ok(slot.map(SlotData::from))
+}
+
+pub async fn get_slots_by_proposer(
+    db_conn: Arc<DbPools>,
+    proposer: i64,
+    limit: i64,
+) -> AppResult<Vec<SlotData>> {
+    let slots = sqlx::query_file_as!(
+        SlotDataRow,
+        "queries/get_slots_by_proposer.sql",
+        proposer,
+        limit
+    )
+    .fetch_all(&db_conn.reader)
+    .await?;
+
+    Ok(slots.into_iter().map(SlotData::from).collect())
+}
+
+pub async fn get_slots_by_fee_recipient(
+    db_conn: Arc<DbPools>,
+    fee_recipient: &str,
+    limit: i64,
+) -> AppResult<Vec<SlotData>> {
+    let fee_recipient_bytes = hex_to_bytes(fee_recipient)?;
+    let slots = sqlx::query_file_as!(
+        SlotDataRow,
+        "queries/get_slots_by_fee_recipient.sql",
+        fee_recipient_bytes,
+        limit
+    )
+    .fetch_all(&db_conn.reader)
+    .await?;
+
+    Ok(slots.into_iter().map(SlotData::from).collect())
+}
+
+pub async fn query_slots(
+    db_conn: Arc<DbPools>,
+    query: &SlotQuery,
+    cursor: Option<i64>,
+    limit: i64,
+) -> AppResult<Vec<SlotData>> {
+    let Some(bounds) = query.bounds(cursor) else {
+        return Ok(Vec::new());
+    };
+    let mut builder = QueryBuilder::<Sqlite>::new("");
+    push_slot_query(&mut builder, query, bounds, limit)?;
+
+    let slots = builder
+        .build_query_as::<SlotDataRow>()
+        .fetch_all(&db_conn.reader)
+        .await?;
+
+    Ok(slots.into_iter().map(SlotData::from).collect())
+}
+
+/// Appends the `SELECT` of [`query_slots`] within the slot `bounds` of [`SlotQuery::bounds`] to
+/// `builder`. It is built at runtime so that only the given filters end up in the WHERE clause
+/// and SQLite can pick the matching index.
+pub(crate) fn push_slot_query(
+    builder: &mut QueryBuilder<'_, Sqlite>,
+    query: &SlotQuery,
+    (from_slot, to_slot): (Option<i64>, Option<i64>),
+    limit: i64,
+) -> AppResult<()> {
+    builder.push("SELECT * FROM slot_data WHERE 1 = 1");
+    if let Some(from_slot) = from_slot {
+        builder.push(" AND slot >= ").push_bind(from_slot);
+    }
+    if let Some(to_slot) = to_slot {
+        builder.push(" AND slot <= ").push_bind(to_slot);
+    }
+    if let Some(epoch) = query.epoch {
+        builder.push(" AND epoch = ").push_bind(epoch);
+    }
+    if let Some(proposer) = query.proposer {
+        builder.push(" AND proposer = ").push_bind(proposer);
+    }
+    if let Some(status) = &query.status {
+        builder.push(" AND status = ").push_bind(status.clone());
+    }
+    if let Some(fee_recipient) = &query.fee_recipient {
+        builder
+            .push(" AND exec_fee_recipient = ")
+            .push_bind(hex_to_bytes(fee_recipient)?);
+    }
+    builder
+        .push(" ORDER BY slot ")
+        .push(query.order.as_sql())
+        .push(" LIMIT ")
+        .push_bind(limit);
+
+    Ok(())
+}
+
+pub async fn get_slot_by_exec_block_number(
+    db_conn: Arc<DbPools>,
+    exec_block_number: i64,
+) -> AppResult<Option<SlotData>> {
+    let slot = sqlx::query_file_as!(
+        SlotDataRow,
+        "queries/get_slot_by_exec_block_number.sql",
+        exec_block_number
+    )
+    .fetch_optional(&db_conn.reader)
+    .await?;
+
+    Ok(slot.map(SlotData::from))
+}
+
+pub async fn get_slot_by_exec_block_hash(
+    db_conn: Arc<DbPools>,
+    exec_block_hash: &str,
+) -> AppResult<Option<SlotData>> {
+    let exec_block_hash_bytes = hex_to_bytes(exec_block_hash)?;
+    let slot = sqlx::query_file_as!(
+        SlotDataRow,
+        "queries/get_slot_by_exec_block_hash.sql",
+        exec_block_hash_bytes
+    )
+    .fetch_optional(&db_conn.reader)
+    .await?;
+
+    Ok(slot.map(SlotData::from))
+}
+
+pub async fn get_slot_by_blockroot(
+    db_conn: Arc<DbPools>,
+    blockroot: &str,
+) -> AppResult<Option<SlotData>> {
+    let blockroot_bytes = hex_to_bytes(blockroot)?;
+    let slot = sqlx::query_file_as!(
+        SlotDataRow,
+        "queries/get_slot_by_blockroot.sql",
+        blockroot_bytes
+    )
+    .fetch_optional(&db_conn.reader)
+    .await?;
+
+    Ok(slot.map(SlotData::from))
+}
+
+pub async fn get_epoch_slots(db_conn: Arc<DbPools>, epoch_number: i64) -> AppResult<Vec<SlotData>> {
+    let slots = sqlx::query_file_as!(SlotDataRow, "queries/get_epoch_slots.sql", epoch_number)
+        .fetch_all(&db_conn.reader)
+        .await?;
+
+    Ok(slots.into_iter().map(SlotData::from).collect())
+}
+
+pub async fn get_latest_unexecuted_slot(db_conn: Arc<DbPools>) -> AppResult<SlotData> {
     let latest_slot_data = sqlx::query_as!(
-        SlotData,
+        SlotDataRow,
         r#"
             SELECT *
             FROM slot_data
@@ -548,35 +1560,264 @@ pub async fn get_latest_unexecuted_slot(db_conn: Arc<Mutex<SqlitePool>>) -> AppR
             LIMIT 1;
         "#
     )
-    .fetch_one(&*db_conn.lock().await)
+    .fetch_one(&db_conn.reader)
     .await?;
 
-    Ok(latest_slot_data)
+    Ok(latest_slot_data.into())
 }
 
-pub async fn api_get_specific_slot(
-    db_conn: Arc<Mutex<SqlitePool>>,
-    slot_number: i64,
-) -> AppResult<SlotData> {
-    let existing_slot = sqlx::query_as!(
-        SlotData,
+/// Full-text search over `graffiti_text` through the `slot_graffiti_fts` index, newest first.
+pub async fn search_graffiti(
+    db_conn: Arc<DbPools>,
+    network: Network,
+    query: &GraffitiQuery,
+    before_slot: Option<i64>,
+    limit: i64,
+) -> AppResult<Vec<GraffitiMatch>> {
+    // Runtime query, the `query!` macros cannot describe FTS5 virtual tables.
+    let rows = sqlx::query(
         r#"
-            SELECT *
-            FROM slot_data
-            WHERE slot = ?
+            SELECT slot_data.slot, slot_data.epoch, slot_data.proposer, slot_data.graffiti_text
+            FROM slot_graffiti_fts
+            JOIN slot_data ON slot_data.slot = slot_graffiti_fts.rowid
+            WHERE slot_graffiti_fts MATCH ? AND slot_data.slot < ?
+            ORDER BY slot_data.slot DESC
+            LIMIT ?
         "#,
-        slot_number
     )
-    .fetch_optional(&*db_conn.lock().await)
+    .bind(query.to_fts5())
+    .bind(before_slot.unwrap_or(i64::MAX))
+    .bind(limit)
+    .fetch_all(&db_conn.reader)
     .await?;
-    if let Some(slot) = existing_slot {
-        Ok(slot)
-    } else {
-        println!("Slot {slot_number} not found in DB, fetching slot from beacon chain...");
-        Ok(utils::external_api::get_specific_slot(slot_number)
-            .await?
-            .into())
+
+    rows.iter()
+        .map(|row| -> AppResult<GraffitiMatch> {
+            Ok(GraffitiMatch::new(
+                network,
+                row.try_get("slot")?,
+                row.try_get("epoch")?,
+                row.try_get("proposer")?,
+                row.try_get("graffiti_text")?,
+            ))
+        })
+        .collect()
+}
+
+pub async fn get_latest_finalized_epoch_data(
+    db_conn: Arc<DbPools>,
+) -> AppResult<Option<EpochData>> {
+    let finalized_epoch_data =
+        sqlx::query_file_as!(EpochData, "queries/get_latest_finalized_epoch_data.sql")
+            .fetch_optional(&db_conn.reader)
+            .await?;
+
+    Ok(finalized_epoch_data)
+}
+
+/// Deletes up to `limit` of the oldest slots before `before_epoch`, together with their blob
+/// sidecar summaries and recorded versions. Returns the number of slots deleted.
+pub async fn prune_slots(db_conn: Arc<DbPools>, before_epoch: i64, limit: i64) -> AppResult<u64> {
+    let mut tx = db_conn.writer.begin().await?;
+
+    // The rollups keep counting the pruned slots, the insert trigger skips them if they are
+    // stored again.
+    sqlx::query!(
+        r#"
+            UPDATE chain_config
+            SET pruned_before_slot = MAX(pruned_before_slot, COALESCE((
+                SELECT MAX(slot) + 1
+                FROM (
+                    SELECT slot
+                    FROM slot_data
+                    WHERE epoch < ?
+                    ORDER BY slot ASC
+                    LIMIT ?
+                )
+            ), 0))
+        "#,
+        before_epoch,
+        limit
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query!(
+        r#"
+            DELETE FROM slot_data_history
+            WHERE slot IN (
+                SELECT slot
+                FROM slot_data
+                WHERE epoch < ?
+                ORDER BY slot ASC
+                LIMIT ?
+            )
+        "#,
+        before_epoch,
+        limit
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query!(
+        r#"
+            DELETE FROM blob_sidecar_summary
+            WHERE slot IN (
+                SELECT slot
+                FROM slot_data
+                WHERE epoch < ?
+                ORDER BY slot ASC
+                LIMIT ?
+            )
+        "#,
+        before_epoch,
+        limit
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    let result = sqlx::query!(
+        r#"
+            DELETE FROM slot_data
+            WHERE slot IN (
+                SELECT slot
+                FROM slot_data
+                WHERE epoch < ?
+                ORDER BY slot ASC
+                LIMIT ?
+            )
+        "#,
+        before_epoch,
+        limit
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Deletes up to `limit` of the oldest blob sidecar summaries before `before_epoch`.
+pub async fn prune_blob_sidecar_summaries(
+    db_conn: Arc<DbPools>,
+    before_epoch: i64,
+    limit: i64,
+) -> AppResult<u64> {
+    let result = sqlx::query!(
+        r#"
+            DELETE FROM blob_sidecar_summary
+            WHERE slot IN (
+                SELECT slot
+                FROM blob_sidecar_summary
+                WHERE epoch < ?
+                ORDER BY slot ASC
+                LIMIT ?
+            )
+        "#,
+        before_epoch,
+        limit
+    )
+    .execute(&db_conn.writer)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Deletes the oldest events recorded before `before`, keeping those a sink has yet to read.
+/// Without any sink cursor only the age applies.
+pub async fn prune_changes(db_conn: Arc<DbPools>, before: i64, limit: i64) -> AppResult<u64> {
+    let result = sqlx::query!(
+        r#"
+            DELETE FROM change_outbox
+            WHERE seq IN (
+                SELECT seq
+                FROM change_outbox
+                WHERE recorded_at < ?
+                    AND (
+                        NOT EXISTS (SELECT 1 FROM sink_cursors)
+                        OR seq <= (SELECT MIN(seq) FROM sink_cursors)
+                    )
+                ORDER BY seq ASC
+                LIMIT ?
+            )
+        "#,
+        before,
+        limit
+    )
+    .execute(&db_conn.writer)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Returns up to `max_pages` free pages to the filesystem. Databases created before
+/// incremental auto-vacuum was enabled are converted with a one-off full `VACUUM` first.
+pub async fn reclaim_space(db_conn: Arc<DbPools>, max_pages: i64) -> AppResult<()> {
+    // 2 = INCREMENTAL, see https://www.sqlite.org/pragma.html#pragma_auto_vacuum
+    let auto_vacuum: i64 = sqlx::query_scalar("PRAGMA auto_vacuum")
+        .fetch_one(&db_conn.writer)
+        .await?;
+
+    if auto_vacuum != 2 {
+        println!(
+            "Converting the database to incremental auto-vacuum, this rewrites the whole file"
+        );
+        sqlx::query("PRAGMA auto_vacuum = INCREMENTAL")
+            .execute(&db_conn.writer)
+            .await?;
+        sqlx::query("VACUUM").execute(&db_conn.writer).await?;
+        return Ok(());
     }
+
+    sqlx::query(&format!("PRAGMA incremental_vacuum({max_pages})"))
+        .execute(&db_conn.writer)
+        .await?;
+
+    Ok(())
+}
+
+/// Rollup buckets starting within `from_ts..=to_ts`, oldest first.
+pub async fn get_rollups(
+    db_conn: Arc<DbPools>,
+    granularity: RollupGranularity,
+    from_ts: i64,
+    to_ts: i64,
+) -> AppResult<Vec<Rollup>> {
+    let rollups = match granularity {
+        RollupGranularity::Hourly => {
+            sqlx::query_as!(
+                Rollup,
+                r#"
+                    SELECT *
+                    FROM rollup_hourly
+                    WHERE bucket_start BETWEEN ? AND ?
+                    ORDER BY bucket_start ASC
+                "#,
+                from_ts,
+                to_ts
+            )
+            .fetch_all(&db_conn.reader)
+            .await?
+        }
+        RollupGranularity::Daily => {
+            sqlx::query_as!(
+                Rollup,
+                r#"
+                    SELECT *
+                    FROM rollup_daily
+                    WHERE bucket_start BETWEEN ? AND ?
+                    ORDER BY bucket_start ASC
+                "#,
+                from_ts,
+                to_ts
+            )
+            .fetch_all(&db_conn.reader)
+            .await?
+        }
+    };
+
+    Ok(rollups)
 }
 
 // pub async fn api_get_five_recent_epoch_slots(db_conn: &SqlitePool) -> AppResult<Vec<SlotData>> {
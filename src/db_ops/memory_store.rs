@@ -0,0 +1,658 @@
+use std::collections::BTreeMap;
+
+use async_trait::async_trait;
+use serde::Serialize;
+use tokio::sync::RwLock;
+
+use super::store::{
+    not_found, ChangeStore, EpochStore, HistoryStore, NetworkScoped, RollupStore, SlotStore,
+};
+use crate::{
+    models::{
+        hex_eq, now_millis, BlobSidecarSummary, ChangeEntity, ChangeEvent, ChangeKind,
+        ChangeSource, EpochData, GraffitiMatch, GraffitiQuery, Rollup, RollupGranularity,
+        RowVersion, SlotData, SlotOrder, SlotQuery,
+    },
+    utils::Network,
+    AppResult,
+};
+
+/// Keeps everything in ordered maps, mirroring the SQLite store semantics. Used for tests and
+/// for running rish without a database file.
+#[derive(Default)]
+pub struct MemoryStore {
+    epochs: RwLock<BTreeMap<i64, EpochData>>,
+    slots: RwLock<BTreeMap<i64, SlotData>>,
+    blob_summaries: RwLock<BTreeMap<i64, BlobSidecarSummary>>,
+    hourly_rollups: RwLock<BTreeMap<i64, Rollup>>,
+    daily_rollups: RwLock<BTreeMap<i64, Rollup>>,
+    epoch_history: RwLock<BTreeMap<i64, Vec<Version<EpochData>>>>,
+    slot_history: RwLock<BTreeMap<i64, Vec<Version<SlotData>>>>,
+    changes: RwLock<ChangeLog>,
+    sink_cursors: RwLock<BTreeMap<String, i64>>,
+    /// Slots below this were pruned and stay counted in the rollups.
+    pruned_before_slot: RwLock<i64>,
+    network: Network,
+    source: ChangeSource,
+}
+
+/// A recorded row with its ingestion time in unix milliseconds.
+#[derive(Clone)]
+struct Version<T> {
+    recorded_at: i64,
+    source: ChangeSource,
+    data: T,
+}
+
+impl<T: Clone> Version<T> {
+    fn to_row_version(&self) -> RowVersion<T> {
+        RowVersion::new(
+            self.recorded_at,
+            self.source.as_str().to_string(),
+            self.data.clone(),
+        )
+    }
+}
+
+/// Outbox events by `seq`. `last_seq` keeps counting after pruning so numbers are never reused.
+#[derive(Default)]
+struct ChangeLog {
+    last_seq: i64,
+    events: BTreeMap<i64, Version<ChangeEvent>>,
+}
+
+/// The newest of `versions` recorded at or before `as_of`.
+fn version_as_of<T: Clone>(versions: Option<&Vec<Version<T>>>, as_of: i64) -> Option<T> {
+    versions?
+        .iter()
+        .rev()
+        .find(|version| version.recorded_at <= as_of)
+        .map(|version| version.data.clone())
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        MemoryStore::default()
+    }
+
+    pub fn with_network(mut self, network: Network) -> Self {
+        self.network = network;
+        self
+    }
+
+    /// Records writes through this store with `source` in the row history.
+    pub fn with_source(mut self, source: ChangeSource) -> Self {
+        self.source = source;
+        self
+    }
+
+    async fn record_epoch_version(&self, epoch_data: &EpochData) {
+        self.epoch_history
+            .write()
+            .await
+            .entry(epoch_data.epoch)
+            .or_default()
+            .push(Version {
+                recorded_at: now_millis(),
+                source: self.source,
+                data: epoch_data.clone(),
+            });
+    }
+
+    async fn record_slot_version(&self, slot: &SlotData) {
+        self.slot_history
+            .write()
+            .await
+            .entry(slot.slot)
+            .or_default()
+            .push(Version {
+                recorded_at: now_millis(),
+                source: self.source,
+                data: slot.clone(),
+            });
+    }
+
+    async fn record_change<T: Serialize>(
+        &self,
+        entity: ChangeEntity,
+        key: i64,
+        kind: ChangeKind,
+        row: &T,
+    ) -> AppResult<()> {
+        let payload = serde_json::to_string(row)?;
+        let recorded_at = now_millis();
+        let mut changes = self.changes.write().await;
+        changes.last_seq += 1;
+        let seq = changes.last_seq;
+        changes.events.insert(
+            seq,
+            Version {
+                recorded_at,
+                source: self.source,
+                data: ChangeEvent::new(
+                    seq,
+                    recorded_at,
+                    entity.as_str().to_string(),
+                    key,
+                    kind.as_str().to_string(),
+                    &payload,
+                ),
+            },
+        );
+        Ok(())
+    }
+
+    /// Newest first, at most `limit` slots matching `predicate`.
+    async fn find_slots<P>(&self, predicate: P, limit: i64) -> AppResult<Vec<SlotData>>
+    where
+        P: Fn(&SlotData) -> bool + Send,
+    {
+        Ok(self
+            .slots
+            .read()
+            .await
+            .values()
+            .rev()
+            .filter(|slot| predicate(slot))
+            .take(limit.max(0) as usize)
+            .cloned()
+            .collect())
+    }
+
+    fn rollups(&self, granularity: RollupGranularity) -> &RwLock<BTreeMap<i64, Rollup>> {
+        match granularity {
+            RollupGranularity::Hourly => &self.hourly_rollups,
+            RollupGranularity::Daily => &self.daily_rollups,
+        }
+    }
+
+    /// Applies `update` to the hourly and daily buckets holding the unix timestamp `ts`, the
+    /// same way the SQLite triggers maintain `rollup_hourly` and `rollup_daily`.
+    async fn update_rollups<F>(&self, ts: i64, update: F)
+    where
+        F: Fn(&mut Rollup) + Send,
+    {
+        for granularity in [RollupGranularity::Hourly, RollupGranularity::Daily] {
+            let bucket_start = granularity.bucket_start(ts);
+            let mut rollups = self.rollups(granularity).write().await;
+            update(
+                rollups
+                    .entry(bucket_start)
+                    .or_insert_with(|| Rollup::new(bucket_start)),
+            );
+        }
+    }
+
+    async fn store_blob_summary(&self, slot: &SlotData) {
+        let mut blob_summaries = self.blob_summaries.write().await;
+        match BlobSidecarSummary::from_slot(slot) {
+            Some(blob_summary) => blob_summaries.insert(blob_summary.slot, blob_summary),
+            None => blob_summaries.remove(&slot.slot),
+        };
+    }
+}
+
+impl NetworkScoped for MemoryStore {
+    fn network(&self) -> Network {
+        self.network
+    }
+}
+
+#[async_trait]
+impl EpochStore for MemoryStore {
+    async fn insert_epoch(&self, epoch_data: EpochData) -> AppResult<()> {
+        let mut epochs = self.epochs.write().await;
+        if epochs.contains_key(&epoch_data.epoch) {
+            return Ok(());
+        }
+        self.update_rollups(self.network.epoch_timestamp(epoch_data.epoch), |rollup| {
+            rollup.add_epoch(&epoch_data, 1)
+        })
+        .await;
+        self.record_epoch_version(&epoch_data).await;
+        self.record_change(
+            ChangeEntity::Epoch,
+            epoch_data.epoch,
+            ChangeKind::Inserted,
+            &epoch_data,
+        )
+        .await?;
+        epochs.insert(epoch_data.epoch, epoch_data);
+        Ok(())
+    }
+
+    async fn update_epoch(&self, epoch_number: i64, epoch_data: EpochData) -> AppResult<()> {
+        let mut epochs = self.epochs.write().await;
+        let kind = match epochs.get(&epoch_number) {
+            None => return Err(not_found("Epoch does not exist.")),
+            Some(existing) if *existing == epoch_data => return Ok(()),
+            Some(existing) => ChangeKind::of_epoch_update(existing, &epoch_data),
+        };
+        if let Some(old) = epochs.remove(&epoch_number) {
+            self.update_rollups(self.network.epoch_timestamp(old.epoch), |rollup| {
+                rollup.add_epoch(&old, -1)
+            })
+            .await;
+        }
+        self.update_rollups(self.network.epoch_timestamp(epoch_data.epoch), |rollup| {
+            rollup.add_epoch(&epoch_data, 1)
+        })
+        .await;
+        self.record_epoch_version(&epoch_data).await;
+        self.record_change(ChangeEntity::Epoch, epoch_data.epoch, kind, &epoch_data)
+            .await?;
+        epochs.insert(epoch_data.epoch, epoch_data);
+        Ok(())
+    }
+
+    async fn get_epoch(&self, epoch_number: i64) -> AppResult<Option<EpochData>> {
+        Ok(self.epochs.read().await.get(&epoch_number).cloned())
+    }
+
+    async fn get_latest_epoch(&self) -> AppResult<EpochData> {
+        self.epochs
+            .read()
+            .await
+            .values()
+            .next_back()
+            .cloned()
+            .ok_or_else(|| not_found("No epoch stored."))
+    }
+
+    async fn get_latest_epochs(&self, count: i64) -> AppResult<Vec<EpochData>> {
+        Ok(self
+            .epochs
+            .read()
+            .await
+            .values()
+            .rev()
+            .take(count.max(0) as usize)
+            .cloned()
+            .collect())
+    }
+
+    async fn get_earliest_epoch(&self) -> AppResult<Option<EpochData>> {
+        Ok(self.epochs.read().await.values().next().cloned())
+    }
+
+    async fn get_epochs_in_range(
+        &self,
+        from_epoch: i64,
+        to_epoch: i64,
+    ) -> AppResult<Vec<EpochData>> {
+        Ok(self
+            .epochs
+            .read()
+            .await
+            .range(from_epoch..=to_epoch)
+            .map(|(_, epoch_data)| epoch_data.clone())
+            .collect())
+    }
+
+    async fn get_latest_finalized_epoch(&self) -> AppResult<Option<EpochData>> {
+        Ok(self
+            .epochs
+            .read()
+            .await
+            .values()
+            .rev()
+            .find(|epoch_data| epoch_data.finalized == 1)
+            .cloned())
+    }
+}
+
+#[async_trait]
+impl SlotStore for MemoryStore {
+    async fn insert_slot(&self, slot: &SlotData) -> AppResult<()> {
+        let mut slots = self.slots.write().await;
+        if slots.contains_key(&slot.slot) {
+            return Ok(());
+        }
+        slots.insert(slot.slot, slot.clone());
+        self.record_change(ChangeEntity::Slot, slot.slot, ChangeKind::Inserted, slot)
+            .await?;
+        drop(slots);
+
+        if slot.slot >= *self.pruned_before_slot.read().await {
+            self.update_rollups(self.network.slot_timestamp(slot.slot), |rollup| {
+                rollup.add_slot(slot, 1)
+            })
+            .await;
+        }
+        self.record_slot_version(slot).await;
+        self.store_blob_summary(slot).await;
+        Ok(())
+    }
+
+    async fn update_slot(&self, slot: SlotData) -> AppResult<()> {
+        let mut slots = self.slots.write().await;
+        let Some(old) = slots.get(&slot.slot) else {
+            return Err(not_found("Slot does not exist."));
+        };
+        if *old == slot {
+            return Ok(());
+        }
+        let kind = ChangeKind::of_slot_update(old, &slot);
+        self.update_rollups(self.network.slot_timestamp(old.slot), |rollup| {
+            rollup.add_slot(old, -1)
+        })
+        .await;
+        self.update_rollups(self.network.slot_timestamp(slot.slot), |rollup| {
+            rollup.add_slot(&slot, 1)
+        })
+        .await;
+        self.record_slot_version(&slot).await;
+        self.record_change(ChangeEntity::Slot, slot.slot, kind, &slot)
+            .await?;
+        self.store_blob_summary(&slot).await;
+        slots.insert(slot.slot, slot);
+        Ok(())
+    }
+
+    async fn get_slot(&self, slot_number: i64) -> AppResult<Option<SlotData>> {
+        Ok(self.slots.read().await.get(&slot_number).cloned())
+    }
+
+    async fn get_epoch_slots(&self, epoch_number: i64) -> AppResult<Vec<SlotData>> {
+        Ok(self
+            .slots
+            .read()
+            .await
+            .values()
+            .filter(|slot| slot.epoch == epoch_number)
+            .cloned()
+            .collect())
+    }
+
+    async fn get_slots_in_range(&self, from_slot: i64, to_slot: i64) -> AppResult<Vec<SlotData>> {
+        Ok(self
+            .slots
+            .read()
+            .await
+            .range(from_slot..=to_slot)
+            .map(|(_, slot)| slot.clone())
+            .collect())
+    }
+
+    async fn get_slots_by_proposer(&self, proposer: i64, limit: i64) -> AppResult<Vec<SlotData>> {
+        self.find_slots(|slot| slot.proposer == proposer, limit)
+            .await
+    }
+
+    async fn get_slots_by_fee_recipient(
+        &self,
+        fee_recipient: &str,
+        limit: i64,
+    ) -> AppResult<Vec<SlotData>> {
+        self.find_slots(
+            |slot| {
+                slot.exec_fee_recipient
+                    .as_deref()
+                    .is_some_and(|exec_fee_recipient| hex_eq(exec_fee_recipient, fee_recipient))
+            },
+            limit,
+        )
+        .await
+    }
+
+    async fn query_slots(
+        &self,
+        query: &SlotQuery,
+        cursor: Option<i64>,
+        limit: i64,
+    ) -> AppResult<Vec<SlotData>> {
+        let Some((from_slot, to_slot)) = query.bounds(cursor) else {
+            return Ok(Vec::new());
+        };
+        let (from_slot, to_slot) = (from_slot.unwrap_or(i64::MIN), to_slot.unwrap_or(i64::MAX));
+        if from_slot > to_slot {
+            return Ok(Vec::new());
+        }
+
+        let slots = self.slots.read().await;
+        let range = slots.range(from_slot..=to_slot).map(|(_, slot)| slot);
+        let matching: Box<dyn Iterator<Item = &SlotData>> = match query.order {
+            SlotOrder::Ascending => Box::new(range),
+            SlotOrder::Descending => Box::new(range.rev()),
+        };
+
+        Ok(matching
+            .filter(|slot| query.matches(slot))
+            .take(limit.max(0) as usize)
+            .cloned()
+            .collect())
+    }
+
+    async fn get_slot_by_exec_block_number(
+        &self,
+        exec_block_number: i64,
+    ) -> AppResult<Option<SlotData>> {
+        Ok(self
+            .find_slots(|slot| slot.exec_block_number == Some(exec_block_number), 1)
+            .await?
+            .pop())
+    }
+
+    async fn get_slot_by_exec_block_hash(
+        &self,
+        exec_block_hash: &str,
+    ) -> AppResult<Option<SlotData>> {
+        Ok(self
+            .find_slots(
+                |slot| slot.exec_block_hash.as_deref() == Some(exec_block_hash),
+                1,
+            )
+            .await?
+            .pop())
+    }
+
+    async fn get_slot_by_blockroot(&self, blockroot: &str) -> AppResult<Option<SlotData>> {
+        Ok(self
+            .find_slots(|slot| slot.blockroot == blockroot, 1)
+            .await?
+            .pop())
+    }
+
+    async fn get_latest_unexecuted_slot(&self) -> AppResult<SlotData> {
+        self.slots
+            .read()
+            .await
+            .values()
+            .rev()
+            .find(|slot| matches!(slot.exec_timestamp, Some(timestamp) if timestamp != 0))
+            .cloned()
+            .ok_or_else(|| not_found("No executed slot stored."))
+    }
+
+    async fn search_graffiti(
+        &self,
+        query: &GraffitiQuery,
+        before_slot: Option<i64>,
+        limit: i64,
+    ) -> AppResult<Vec<GraffitiMatch>> {
+        Ok(self
+            .slots
+            .read()
+            .await
+            .range(..before_slot.unwrap_or(i64::MAX))
+            .rev()
+            .map(|(_, slot)| slot)
+            .filter(|slot| query.matches(&slot.graffiti_text))
+            .take(limit.max(0) as usize)
+            .map(|slot| {
+                GraffitiMatch::new(
+                    self.network,
+                    slot.slot,
+                    slot.epoch,
+                    slot.proposer,
+                    slot.graffiti_text.clone(),
+                )
+            })
+            .collect())
+    }
+
+    async fn get_blob_sidecar_summary(
+        &self,
+        slot_number: i64,
+    ) -> AppResult<Option<BlobSidecarSummary>> {
+        Ok(self.blob_summaries.read().await.get(&slot_number).cloned())
+    }
+
+    async fn prune_slots(&self, before_epoch: i64, limit: i64) -> AppResult<u64> {
+        let mut slots = self.slots.write().await;
+        let expired: Vec<i64> = slots
+            .values()
+            .filter(|slot| slot.epoch < before_epoch)
+            .take(limit.max(0) as usize)
+            .map(|slot| slot.slot)
+            .collect();
+
+        if let Some(last) = expired.last() {
+            let mut pruned_before_slot = self.pruned_before_slot.write().await;
+            *pruned_before_slot = (*pruned_before_slot).max(last + 1);
+        }
+        let mut blob_summaries = self.blob_summaries.write().await;
+        let mut slot_history = self.slot_history.write().await;
+        for slot_number in &expired {
+            slot_history.remove(slot_number);
+            blob_summaries.remove(slot_number);
+            slots.remove(slot_number);
+        }
+
+        Ok(expired.len() as u64)
+    }
+
+    async fn prune_blob_sidecar_summaries(&self, before_epoch: i64, limit: i64) -> AppResult<u64> {
+        let mut blob_summaries = self.blob_summaries.write().await;
+        let expired: Vec<i64> = blob_summaries
+            .values()
+            .filter(|blob_summary| blob_summary.epoch < before_epoch)
+            .take(limit.max(0) as usize)
+            .map(|blob_summary| blob_summary.slot)
+            .collect();
+
+        for slot_number in &expired {
+            blob_summaries.remove(slot_number);
+        }
+
+        Ok(expired.len() as u64)
+    }
+
+    async fn reclaim_space(&self, _max_pages: i64) -> AppResult<()> {
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl RollupStore for MemoryStore {
+    async fn get_rollups(
+        &self,
+        granularity: RollupGranularity,
+        from_ts: i64,
+        to_ts: i64,
+    ) -> AppResult<Vec<Rollup>> {
+        Ok(self
+            .rollups(granularity)
+            .read()
+            .await
+            .range(from_ts..=to_ts)
+            .map(|(_, rollup)| rollup.clone())
+            .collect())
+    }
+}
+
+#[async_trait]
+impl HistoryStore for MemoryStore {
+    async fn get_epoch_as_of(&self, epoch_number: i64, as_of: i64) -> AppResult<Option<EpochData>> {
+        Ok(version_as_of(
+            self.epoch_history.read().await.get(&epoch_number),
+            as_of,
+        ))
+    }
+
+    async fn get_slot_as_of(&self, slot_number: i64, as_of: i64) -> AppResult<Option<SlotData>> {
+        Ok(version_as_of(
+            self.slot_history.read().await.get(&slot_number),
+            as_of,
+        ))
+    }
+
+    async fn get_epoch_history(&self, epoch_number: i64) -> AppResult<Vec<RowVersion<EpochData>>> {
+        Ok(self
+            .epoch_history
+            .read()
+            .await
+            .get(&epoch_number)
+            .map(|versions| versions.iter().map(Version::to_row_version).collect())
+            .unwrap_or_default())
+    }
+
+    async fn get_slot_history(&self, slot_number: i64) -> AppResult<Vec<RowVersion<SlotData>>> {
+        Ok(self
+            .slot_history
+            .read()
+            .await
+            .get(&slot_number)
+            .map(|versions| versions.iter().map(Version::to_row_version).collect())
+            .unwrap_or_default())
+    }
+}
+
+#[async_trait]
+impl ChangeStore for MemoryStore {
+    async fn get_changes(&self, since: i64, limit: i64) -> AppResult<Vec<ChangeEvent>> {
+        Ok(self
+            .changes
+            .read()
+            .await
+            .events
+            .range(since.saturating_add(1)..)
+            .take(limit.max(0) as usize)
+            .map(|(_, version)| version.data.clone())
+            .collect())
+    }
+
+    async fn prune_changes(&self, before: i64, limit: i64) -> AppResult<u64> {
+        let delivered = self
+            .sink_cursors
+            .read()
+            .await
+            .values()
+            .min()
+            .copied()
+            .unwrap_or(i64::MAX);
+        let mut changes = self.changes.write().await;
+        let expired: Vec<i64> = changes
+            .events
+            .values()
+            .take_while(|version| version.recorded_at < before)
+            .map(|version| version.data.seq)
+            .take_while(|seq| *seq <= delivered)
+            .take(limit.max(0) as usize)
+            .collect();
+
+        for seq in &expired {
+            changes.events.remove(seq);
+        }
+
+        Ok(expired.len() as u64)
+    }
+
+    async fn get_sink_cursor(&self, sink: &str) -> AppResult<i64> {
+        Ok(self
+            .sink_cursors
+            .read()
+            .await
+            .get(sink)
+            .copied()
+            .unwrap_or(0))
+    }
+
+    async fn set_sink_cursor(&self, sink: &str, seq: i64) -> AppResult<()> {
+        self.sink_cursors
+            .write()
+            .await
+            .insert(sink.to_string(), seq);
+        Ok(())
+    }
+}
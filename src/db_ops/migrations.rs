@@ -0,0 +1,216 @@
+use std::{collections::HashMap, sync::Arc};
+
+use sqlx::{
+    migrate::{Migrate, MigrateError, Migration, Migrator},
+    Executor, Row, SqliteConnection,
+};
+
+use super::{db_ops::table_exists, pools::DbPools};
+use crate::AppResult;
+
+pub static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+/// First line of a migration that runs its own `BEGIN`/`COMMIT`, e.g. to turn `foreign_keys`
+/// off around a table rebuild, which SQLite ignores inside a transaction. `Migrator` wraps every
+/// script in a transaction, so these are applied and recorded by [`apply_pending`] itself.
+const NO_TRANSACTION: &str = "-- no-transaction";
+
+fn is_no_transaction(migration: &Migration) -> bool {
+    migration.sql.trim_start().starts_with(NO_TRANSACTION)
+}
+
+/// Highest migration version embedded in this binary.
+pub fn binary_schema_version() -> i64 {
+    schema_version_of(&MIGRATOR)
+}
+
+fn schema_version_of(migrator: &Migrator) -> i64 {
+    migrator
+        .iter()
+        .filter(|migration| !migration.migration_type.is_down_migration())
+        .map(|migration| migration.version)
+        .max()
+        .unwrap_or(0)
+}
+
+/// Versions successfully applied to the database, oldest first.
+pub async fn applied_versions(db_conn: Arc<DbPools>) -> AppResult<Vec<i64>> {
+    if !table_exists(Arc::clone(&db_conn), "_sqlx_migrations").await? {
+        return Ok(Vec::new());
+    }
+
+    let rows =
+        sqlx::query(r#"SELECT version FROM _sqlx_migrations WHERE success = 1 ORDER BY version"#)
+            .fetch_all(&db_conn.writer)
+            .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| row.get::<i64, _>("version"))
+        .collect())
+}
+
+/// Errors when the database carries migrations this binary does not know about, i.e. it was
+/// migrated by a newer rish and the current code could misread its tables.
+pub fn ensure_not_newer(applied: &[i64]) -> AppResult<()> {
+    ensure_not_newer_than(&MIGRATOR, applied)
+}
+
+pub fn ensure_not_newer_than(migrator: &Migrator, applied: &[i64]) -> AppResult<()> {
+    let known: Vec<i64> = migrator.iter().map(|migration| migration.version).collect();
+
+    match applied.iter().find(|version| !known.contains(version)) {
+        Some(unknown) => Err(format!(
+            "Database schema version {unknown} is newer than this binary (schema version {}), refusing to start",
+            schema_version_of(migrator)
+        )
+        .into()),
+        None => Ok(()),
+    }
+}
+
+/// Errors unless the database is at exactly the schema version of this binary, for callers that
+/// must not migrate it. Returns that version.
+pub async fn ensure_current(db_conn: Arc<DbPools>) -> AppResult<i64> {
+    let applied = applied_versions(db_conn).await?;
+    ensure_not_newer(&applied)?;
+
+    let current_version = applied.last().copied().unwrap_or(0);
+    let binary_version = binary_schema_version();
+    if current_version < binary_version {
+        return Err(format!(
+            "Database schema version {current_version} is older than this binary (schema version {binary_version}), run `rish --migrate-only` first"
+        )
+        .into());
+    }
+
+    Ok(current_version)
+}
+
+/// Applies every pending migration. Already applied ones are skipped, so this is safe to run
+/// on every start. Returns the resulting schema version.
+pub async fn run_migrations(db_conn: Arc<DbPools>) -> AppResult<i64> {
+    let applied = applied_versions(Arc::clone(&db_conn)).await?;
+    ensure_not_newer(&applied)?;
+
+    let current_version = applied.last().copied().unwrap_or(0);
+    let binary_version = binary_schema_version();
+
+    if current_version < binary_version {
+        println!("Migrating database schema from version {current_version} to {binary_version}");
+    }
+
+    apply_pending(&mut *db_conn.writer.acquire().await?).await?;
+
+    println!("Database schema version {binary_version}");
+
+    Ok(binary_version)
+}
+
+/// Reverts applied migrations newer than `target_version` using their down scripts.
+pub async fn undo_migrations(db_conn: Arc<DbPools>, target_version: i64) -> AppResult<i64> {
+    let applied = applied_versions(Arc::clone(&db_conn)).await?;
+    ensure_not_newer(&applied)?;
+
+    println!("Reverting database schema to version {target_version}");
+
+    revert_newer(&mut *db_conn.writer.acquire().await?, target_version).await?;
+
+    let version = applied_versions(db_conn)
+        .await?
+        .last()
+        .copied()
+        .unwrap_or(0);
+
+    println!("Database schema version {version}");
+
+    Ok(version)
+}
+
+/// `Migrator::run` for [`MIGRATOR`], running `-- no-transaction` scripts as they are.
+async fn apply_pending(conn: &mut SqliteConnection) -> AppResult<()> {
+    let applied = checked_applied_migrations(conn).await?;
+
+    for migration in MIGRATOR
+        .iter()
+        .filter(|migration| !migration.migration_type.is_down_migration())
+    {
+        match applied.get(&migration.version) {
+            Some(checksum) if *checksum != *migration.checksum => {
+                return Err(MigrateError::VersionMismatch(migration.version).into())
+            }
+            Some(_) => {}
+            None if is_no_transaction(migration) => {
+                execute_script(conn, migration).await?;
+                sqlx::query(
+                    r#"
+                        INSERT INTO _sqlx_migrations (version, description, success, checksum, execution_time)
+                        VALUES (?, ?, TRUE, ?, -1)
+                    "#,
+                )
+                .bind(migration.version)
+                .bind(&*migration.description)
+                .bind(&*migration.checksum)
+                .execute(&mut *conn)
+                .await?;
+            }
+            None => {
+                conn.apply(migration).await?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// `Migrator::undo` for [`MIGRATOR`], running `-- no-transaction` scripts as they are.
+async fn revert_newer(conn: &mut SqliteConnection, target_version: i64) -> AppResult<()> {
+    let applied = checked_applied_migrations(conn).await?;
+
+    for migration in MIGRATOR.iter().rev().filter(|migration| {
+        migration.migration_type.is_down_migration()
+            && migration.version > target_version
+            && applied.contains_key(&migration.version)
+    }) {
+        if is_no_transaction(migration) {
+            execute_script(conn, migration).await?;
+            sqlx::query("DELETE FROM _sqlx_migrations WHERE version = ?")
+                .bind(migration.version)
+                .execute(&mut *conn)
+                .await?;
+        } else {
+            conn.revert(migration).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Checksums of the applied migrations by version, refusing a database a migration failed on.
+async fn checked_applied_migrations(
+    conn: &mut SqliteConnection,
+) -> AppResult<HashMap<i64, Vec<u8>>> {
+    conn.ensure_migrations_table().await?;
+    if let Some(version) = conn.dirty_version().await? {
+        return Err(MigrateError::Dirty(version).into());
+    }
+
+    Ok(conn
+        .list_applied_migrations()
+        .await?
+        .into_iter()
+        .map(|migration| (migration.version, migration.checksum.into_owned()))
+        .collect())
+}
+
+/// Runs a `-- no-transaction` script. One failing halfway is rolled back by its own transaction
+/// being abandoned here, and `foreign_keys` is switched back on in case it turned it off.
+async fn execute_script(conn: &mut SqliteConnection, migration: &Migration) -> AppResult<()> {
+    if let Err(e) = conn.execute(&*migration.sql).await {
+        let _ = conn.execute("ROLLBACK").await;
+        let _ = conn.execute("PRAGMA foreign_keys = ON").await;
+        return Err(e.into());
+    }
+
+    Ok(())
+}
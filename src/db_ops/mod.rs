@@ -1,2 +1,19 @@
+pub mod backup;
+pub mod cached_store;
 pub mod db_ops;
+pub mod memory_store;
+pub mod migrations;
+#[cfg(feature = "postgres")]
+pub mod pg_store;
+pub mod pools;
+#[cfg(test)]
+mod query_plans;
+pub mod store;
+pub use backup::*;
+pub use cached_store::*;
 pub use db_ops::*;
+pub use memory_store::*;
+#[cfg(feature = "postgres")]
+pub use pg_store::*;
+pub use pools::*;
+pub use store::*;
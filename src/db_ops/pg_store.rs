@@ -0,0 +1,1091 @@
+use async_trait::async_trait;
+use chrono::{DateTime, SecondsFormat, Utc};
+use sqlx::{
+    migrate::Migrator,
+    postgres::{PgConnection, PgPool, PgRow, Postgres},
+    QueryBuilder, Row,
+};
+
+use super::{
+    migrations::ensure_not_newer_than,
+    store::{
+        not_found, ChangeStore, EpochStore, HistoryStore, NetworkScoped, RollupStore, SlotStore,
+    },
+};
+use crate::{
+    models::{
+        bytes_to_hex, hex_to_bytes, now_millis, optional_bytes_to_hex, optional_hex_to_bytes,
+        BlobSidecarSummary, ChangeEvent, ChangeSource, EpochData, GraffitiMatch, GraffitiQuery,
+        Rollup, RollupGranularity, RowVersion, SlotData, SlotQuery,
+    },
+    utils::Network,
+    AppResult,
+};
+
+pub static PG_MIGRATOR: Migrator = sqlx::migrate!("./pg_migrations");
+
+const EPOCH_COLUMNS: &str = r#"
+    attestationscount,
+    attesterslashingscount,
+    averagevalidatorbalance,
+    blockscount,
+    depositscount,
+    eligibleether,
+    epoch,
+    finalized,
+    globalparticipationrate,
+    missedblocks,
+    orphanedblocks,
+    proposedblocks,
+    proposerslashingscount,
+    rewards_exported,
+    scheduledblocks,
+    totalvalidatorbalance,
+    ts,
+    validatorscount,
+    voluntaryexitscount,
+    votedether,
+    withdrawalcount
+"#;
+
+const SLOT_COLUMNS: &str = r#"
+    attestationscount,
+    attesterslashingscount,
+    blob_kzg_commitments_count,
+    blockroot,
+    depositscount,
+    epoch,
+    eth1data_blockhash,
+    eth1data_depositcount,
+    eth1data_depositroot,
+    exec_base_fee_per_gas,
+    exec_blob_gas_used,
+    exec_block_hash,
+    exec_block_number,
+    exec_excess_blob_gas,
+    exec_extra_data,
+    exec_fee_recipient,
+    exec_gas_limit,
+    exec_gas_used,
+    exec_logs_bloom,
+    exec_parent_hash,
+    exec_random,
+    exec_receipts_root,
+    exec_state_root,
+    exec_timestamp,
+    exec_transactions_count,
+    graffiti,
+    graffiti_text,
+    parent_beacon_block_root,
+    parentroot,
+    proposer,
+    proposerslashingscount,
+    randaoreveal,
+    signature,
+    slot,
+    stateroot,
+    status,
+    syncaggregate_bits,
+    syncaggregate_participation,
+    syncaggregate_signature,
+    voluntaryexitscount,
+    withdrawalcount
+"#;
+
+/// `$1, $2, ..., $n` for an insert of `count` columns.
+fn placeholders(count: usize) -> String {
+    (1..=count)
+        .map(|index| format!("${index}"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// `column = EXCLUDED.column` for every column, used to turn an insert into an update.
+fn excluded_assignments(columns: &str) -> String {
+    columns
+        .split(',')
+        .map(str::trim)
+        .map(|column| format!("{column} = EXCLUDED.{column}"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn column_count(columns: &str) -> usize {
+    columns.split(',').count()
+}
+
+/// Serializes writers of `change_outbox` until their transaction ends. `seq` comes from a
+/// sequence, which hands out numbers in call order rather than commit order; without the lock
+/// a consumer could read `seq` 11 before 10 commits and skip it.
+async fn lock_change_outbox(conn: &mut PgConnection) -> AppResult<()> {
+    sqlx::query("LOCK TABLE change_outbox IN SHARE ROW EXCLUSIVE MODE")
+        .execute(conn)
+        .await?;
+
+    Ok(())
+}
+
+fn change_from_row(row: &PgRow) -> AppResult<ChangeEvent> {
+    let payload: String = row.try_get("payload")?;
+
+    Ok(ChangeEvent::new(
+        row.try_get("seq")?,
+        row.try_get("recorded_at")?,
+        row.try_get("entity")?,
+        row.try_get("key")?,
+        row.try_get("kind")?,
+        &payload,
+    ))
+}
+
+fn rollup_from_row(row: &PgRow) -> AppResult<Rollup> {
+    Ok(Rollup {
+        bucket_start: row.try_get("bucket_start")?,
+        epochs: row.try_get("epochs")?,
+        participation_sum: row.try_get("participation_sum")?,
+        slots: row.try_get("slots")?,
+        blocks_proposed: row.try_get("blocks_proposed")?,
+        blocks_missed: row.try_get("blocks_missed")?,
+        blocks_orphaned: row.try_get("blocks_orphaned")?,
+        gas_used: row.try_get("gas_used")?,
+        base_fee_sum: row.try_get("base_fee_sum")?,
+        base_fee_count: row.try_get("base_fee_count")?,
+        withdrawals: row.try_get("withdrawals")?,
+    })
+}
+
+fn epoch_from_row(row: &PgRow) -> AppResult<EpochData> {
+    let ts: DateTime<Utc> = row.try_get("ts")?;
+
+    Ok(EpochData {
+        attestationscount: row.try_get("attestationscount")?,
+        attesterslashingscount: row.try_get("attesterslashingscount")?,
+        averagevalidatorbalance: row.try_get("averagevalidatorbalance")?,
+        blockscount: row.try_get("blockscount")?,
+        depositscount: row.try_get("depositscount")?,
+        eligibleether: row.try_get("eligibleether")?,
+        epoch: row.try_get("epoch")?,
+        finalized: i64::from(row.try_get::<bool, _>("finalized")?),
+        globalparticipationrate: row.try_get("globalparticipationrate")?,
+        missedblocks: row.try_get("missedblocks")?,
+        orphanedblocks: row.try_get("orphanedblocks")?,
+        proposedblocks: row.try_get("proposedblocks")?,
+        proposerslashingscount: row.try_get("proposerslashingscount")?,
+        rewards_exported: i64::from(row.try_get::<bool, _>("rewards_exported")?),
+        scheduledblocks: row.try_get("scheduledblocks")?,
+        totalvalidatorbalance: row.try_get("totalvalidatorbalance")?,
+        ts: ts.to_rfc3339_opts(SecondsFormat::Secs, true),
+        validatorscount: row.try_get("validatorscount")?,
+        voluntaryexitscount: row.try_get("voluntaryexitscount")?,
+        votedether: row.try_get("votedether")?,
+        withdrawalcount: row.try_get("withdrawalcount")?,
+    })
+}
+
+fn slot_from_row(row: &PgRow) -> AppResult<SlotData> {
+    let bytes = |column: &str| -> AppResult<String> {
+        Ok(bytes_to_hex(&row.try_get::<Vec<u8>, _>(column)?))
+    };
+    let optional_bytes = |column: &str| -> AppResult<Option<String>> {
+        Ok(optional_bytes_to_hex(row.try_get(column)?))
+    };
+
+    Ok(SlotData {
+        attestationscount: row.try_get("attestationscount")?,
+        attesterslashingscount: row.try_get("attesterslashingscount")?,
+        blob_kzg_commitments_count: row.try_get("blob_kzg_commitments_count")?,
+        blockroot: bytes("blockroot")?,
+        depositscount: row.try_get("depositscount")?,
+        epoch: row.try_get("epoch")?,
+        eth1data_blockhash: bytes("eth1data_blockhash")?,
+        eth1data_depositcount: row.try_get("eth1data_depositcount")?,
+        eth1data_depositroot: bytes("eth1data_depositroot")?,
+        exec_base_fee_per_gas: row.try_get("exec_base_fee_per_gas")?,
+        exec_blob_gas_used: row.try_get("exec_blob_gas_used")?,
+        exec_block_hash: optional_bytes("exec_block_hash")?,
+        exec_block_number: row.try_get("exec_block_number")?,
+        exec_excess_blob_gas: row.try_get("exec_excess_blob_gas")?,
+        exec_extra_data: optional_bytes("exec_extra_data")?,
+        exec_fee_recipient: optional_bytes("exec_fee_recipient")?,
+        exec_gas_limit: row.try_get("exec_gas_limit")?,
+        exec_gas_used: row.try_get("exec_gas_used")?,
+        exec_logs_bloom: optional_bytes("exec_logs_bloom")?,
+        exec_parent_hash: optional_bytes("exec_parent_hash")?,
+        exec_random: optional_bytes("exec_random")?,
+        exec_receipts_root: optional_bytes("exec_receipts_root")?,
+        exec_state_root: optional_bytes("exec_state_root")?,
+        exec_timestamp: row.try_get("exec_timestamp")?,
+        exec_transactions_count: row.try_get("exec_transactions_count")?,
+        graffiti: bytes("graffiti")?,
+        graffiti_text: row.try_get("graffiti_text")?,
+        parent_beacon_block_root: optional_bytes("parent_beacon_block_root")?,
+        parentroot: bytes("parentroot")?,
+        proposer: row.try_get("proposer")?,
+        proposerslashingscount: row.try_get("proposerslashingscount")?,
+        randaoreveal: bytes("randaoreveal")?,
+        signature: bytes("signature")?,
+        slot: row.try_get("slot")?,
+        stateroot: bytes("stateroot")?,
+        status: row.try_get("status")?,
+        syncaggregate_bits: optional_bytes("syncaggregate_bits")?,
+        syncaggregate_participation: row.try_get("syncaggregate_participation")?,
+        syncaggregate_signature: optional_bytes("syncaggregate_signature")?,
+        voluntaryexitscount: row.try_get("voluntaryexitscount")?,
+        withdrawalcount: row.try_get("withdrawalcount")?,
+    })
+}
+
+/// PostgreSQL backed store, enabled by the `postgres` cargo feature and selected when the
+/// database URL uses the `postgres://` scheme.
+#[derive(Clone)]
+pub struct PgStore {
+    pool: PgPool,
+    network: Network,
+    source: ChangeSource,
+}
+
+impl PgStore {
+    pub async fn connect(db_url: &str, network: Network) -> AppResult<Self> {
+        let pool = PgPool::connect(db_url).await?;
+        let store = PgStore {
+            pool,
+            network,
+            source: ChangeSource::default(),
+        };
+
+        ensure_not_newer_than(&PG_MIGRATOR, &store.applied_versions().await?)?;
+        PG_MIGRATOR.run(&store.pool).await?;
+        store.ensure_chain_config().await?;
+
+        Ok(store)
+    }
+
+    /// Records which network the database follows, refusing one that holds another network.
+    async fn ensure_chain_config(&self) -> AppResult<()> {
+        let stored = sqlx::query("SELECT network FROM chain_config WHERE id = 1")
+            .fetch_optional(&self.pool)
+            .await?;
+
+        if let Some(row) = stored {
+            let stored: String = row.try_get("network")?;
+            if stored != self.network.name {
+                return Err(
+                    format!("The database holds {stored}, not {}", self.network.name).into(),
+                );
+            }
+            return Ok(());
+        }
+
+        sqlx::query(
+            "INSERT INTO chain_config (id, network, genesis_timestamp, seconds_per_slot, slots_per_epoch) VALUES (1, $1, $2, $3, $4)",
+        )
+        .bind(self.network.name)
+        .bind(self.network.genesis_timestamp)
+        .bind(self.network.seconds_per_slot)
+        .bind(self.network.slots_per_epoch)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Records writes through this store with `source` in the row history.
+    pub fn with_source(mut self, source: ChangeSource) -> Self {
+        self.source = source;
+        self
+    }
+
+    async fn applied_versions(&self) -> AppResult<Vec<i64>> {
+        let table = sqlx::query("SELECT to_regclass('_sqlx_migrations') IS NOT NULL AS present")
+            .fetch_one(&self.pool)
+            .await?;
+        if !table.try_get::<bool, _>("present")? {
+            return Ok(Vec::new());
+        }
+
+        let rows = sqlx::query(
+            "SELECT version FROM _sqlx_migrations WHERE success = TRUE ORDER BY version",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter()
+            .map(|row| -> AppResult<i64> { Ok(row.try_get("version")?) })
+            .collect()
+    }
+
+    /// Reverts applied migrations newer than `target_version` using their down scripts.
+    pub async fn undo_migrations(db_url: &str, target_version: i64) -> AppResult<()> {
+        let store = PgStore {
+            pool: PgPool::connect(db_url).await?,
+            network: Network::default(),
+            source: ChangeSource::default(),
+        };
+
+        ensure_not_newer_than(&PG_MIGRATOR, &store.applied_versions().await?)?;
+        PG_MIGRATOR.undo(&store.pool, target_version).await?;
+
+        Ok(())
+    }
+
+    pub fn pool(&self) -> &PgPool {
+        &self.pool
+    }
+
+    async fn write_epoch(&self, epoch_data: &EpochData, overwrite: bool) -> AppResult<u64> {
+        let conflict = if overwrite {
+            format!(
+                "DO UPDATE SET {} WHERE (epoch_data.*) IS DISTINCT FROM (EXCLUDED.*)",
+                excluded_assignments(EPOCH_COLUMNS)
+            )
+        } else {
+            "DO NOTHING".to_string()
+        };
+        // Only rows that were actually inserted or changed come back from `RETURNING` and
+        // get recorded as a new version and a change event. `previous` still sees the row as
+        // it was before this statement; the event kind mirrors `ChangeKind::of_epoch_update`.
+        let count = column_count(EPOCH_COLUMNS);
+        let query = format!(
+            "WITH previous AS (\
+                SELECT finalized FROM epoch_data WHERE epoch = ${key}\
+            ), written AS (\
+                INSERT INTO epoch_data ({EPOCH_COLUMNS}) VALUES ({}) ON CONFLICT (epoch) {conflict} \
+                RETURNING {EPOCH_COLUMNS}\
+            ), versions AS (\
+                INSERT INTO epoch_data_history (recorded_at, source, {EPOCH_COLUMNS}) \
+                SELECT ${recorded_at}, ${source}, {EPOCH_COLUMNS} FROM written\
+            ) \
+            INSERT INTO change_outbox (recorded_at, entity, key, kind, payload) \
+            SELECT ${recorded_at}, 'epoch', written.epoch, \
+                CASE \
+                    WHEN previous.finalized IS NULL THEN 'inserted' \
+                    WHEN written.finalized AND NOT previous.finalized THEN 'finalized' \
+                    ELSE 'updated' \
+                END, \
+                ${payload}::jsonb \
+            FROM written LEFT JOIN previous ON TRUE",
+            placeholders(count),
+            recorded_at = count + 1,
+            source = count + 2,
+            payload = count + 3,
+            key = count + 4,
+        );
+        let ts = DateTime::parse_from_rfc3339(&epoch_data.ts)?.with_timezone(&Utc);
+
+        let mut tx = self.pool.begin().await?;
+        lock_change_outbox(&mut tx).await?;
+        let result = sqlx::query(&query)
+            .bind(epoch_data.attestationscount)
+            .bind(epoch_data.attesterslashingscount)
+            .bind(epoch_data.averagevalidatorbalance)
+            .bind(epoch_data.blockscount)
+            .bind(epoch_data.depositscount)
+            .bind(epoch_data.eligibleether)
+            .bind(epoch_data.epoch)
+            .bind(epoch_data.finalized == 1)
+            .bind(epoch_data.globalparticipationrate)
+            .bind(epoch_data.missedblocks)
+            .bind(epoch_data.orphanedblocks)
+            .bind(epoch_data.proposedblocks)
+            .bind(epoch_data.proposerslashingscount)
+            .bind(epoch_data.rewards_exported == 1)
+            .bind(epoch_data.scheduledblocks)
+            .bind(epoch_data.totalvalidatorbalance)
+            .bind(ts)
+            .bind(epoch_data.validatorscount)
+            .bind(epoch_data.voluntaryexitscount)
+            .bind(epoch_data.votedether)
+            .bind(epoch_data.withdrawalcount)
+            .bind(now_millis())
+            .bind(self.source.as_str())
+            .bind(serde_json::to_string(epoch_data)?)
+            .bind(epoch_data.epoch)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn write_slot(&self, slot: &SlotData, overwrite: bool) -> AppResult<u64> {
+        let conflict = if overwrite {
+            format!(
+                "DO UPDATE SET {} WHERE (slot_data.*) IS DISTINCT FROM (EXCLUDED.*)",
+                excluded_assignments(SLOT_COLUMNS)
+            )
+        } else {
+            "DO NOTHING".to_string()
+        };
+        // Only rows that were actually inserted or changed come back from `RETURNING` and
+        // get recorded as a new version and a change event. `previous` still sees the row as
+        // it was before this statement; the event kind mirrors `ChangeKind::of_slot_update`.
+        let count = column_count(SLOT_COLUMNS);
+        let query = format!(
+            "WITH previous AS (\
+                SELECT status, blockroot FROM slot_data WHERE slot = ${key}\
+            ), written AS (\
+                INSERT INTO slot_data ({SLOT_COLUMNS}) VALUES ({}) ON CONFLICT (slot) {conflict} \
+                RETURNING {SLOT_COLUMNS}\
+            ), versions AS (\
+                INSERT INTO slot_data_history (recorded_at, source, {SLOT_COLUMNS}) \
+                SELECT ${recorded_at}, ${source}, {SLOT_COLUMNS} FROM written\
+            ) \
+            INSERT INTO change_outbox (recorded_at, entity, key, kind, payload) \
+            SELECT ${recorded_at}, 'slot', written.slot, \
+                CASE \
+                    WHEN previous.status IS NULL THEN 'inserted' \
+                    WHEN written.status = '3' AND previous.status <> '3' THEN 'reorged' \
+                    WHEN written.blockroot <> previous.blockroot THEN 'reorged' \
+                    ELSE 'updated' \
+                END, \
+                ${payload}::jsonb \
+            FROM written LEFT JOIN previous ON TRUE",
+            placeholders(count),
+            recorded_at = count + 1,
+            source = count + 2,
+            payload = count + 3,
+            key = count + 4,
+        );
+
+        let mut tx = self.pool.begin().await?;
+        lock_change_outbox(&mut tx).await?;
+        let result = sqlx::query(&query)
+            .bind(slot.attestationscount)
+            .bind(slot.attesterslashingscount)
+            .bind(slot.blob_kzg_commitments_count)
+            .bind(hex_to_bytes(&slot.blockroot)?)
+            .bind(slot.depositscount)
+            .bind(slot.epoch)
+            .bind(hex_to_bytes(&slot.eth1data_blockhash)?)
+            .bind(slot.eth1data_depositcount)
+            .bind(hex_to_bytes(&slot.eth1data_depositroot)?)
+            .bind(slot.exec_base_fee_per_gas)
+            .bind(slot.exec_blob_gas_used)
+            .bind(optional_hex_to_bytes(&slot.exec_block_hash)?)
+            .bind(slot.exec_block_number)
+            .bind(slot.exec_excess_blob_gas)
+            .bind(optional_hex_to_bytes(&slot.exec_extra_data)?)
+            .bind(optional_hex_to_bytes(&slot.exec_fee_recipient)?)
+            .bind(slot.exec_gas_limit)
+            .bind(slot.exec_gas_used)
+            .bind(optional_hex_to_bytes(&slot.exec_logs_bloom)?)
+            .bind(optional_hex_to_bytes(&slot.exec_parent_hash)?)
+            .bind(optional_hex_to_bytes(&slot.exec_random)?)
+            .bind(optional_hex_to_bytes(&slot.exec_receipts_root)?)
+            .bind(optional_hex_to_bytes(&slot.exec_state_root)?)
+            .bind(slot.exec_timestamp)
+            .bind(slot.exec_transactions_count)
+            .bind(hex_to_bytes(&slot.graffiti)?)
+            .bind(&slot.graffiti_text)
+            .bind(optional_hex_to_bytes(&slot.parent_beacon_block_root)?)
+            .bind(hex_to_bytes(&slot.parentroot)?)
+            .bind(slot.proposer)
+            .bind(slot.proposerslashingscount)
+            .bind(hex_to_bytes(&slot.randaoreveal)?)
+            .bind(hex_to_bytes(&slot.signature)?)
+            .bind(slot.slot)
+            .bind(hex_to_bytes(&slot.stateroot)?)
+            .bind(&slot.status)
+            .bind(optional_hex_to_bytes(&slot.syncaggregate_bits)?)
+            .bind(slot.syncaggregate_participation)
+            .bind(optional_hex_to_bytes(&slot.syncaggregate_signature)?)
+            .bind(slot.voluntaryexitscount)
+            .bind(slot.withdrawalcount)
+            .bind(now_millis())
+            .bind(self.source.as_str())
+            .bind(serde_json::to_string(slot)?)
+            .bind(slot.slot)
+            .execute(&mut *tx)
+            .await?;
+
+        // A slot that was not written keeps its summary; a written slot without blob data drops
+        // the one left over from its previous version.
+        if result.rows_affected() > 0 {
+            match BlobSidecarSummary::from_slot(slot) {
+                Some(blob_summary) => {
+                    sqlx::query(
+                        r#"
+                            INSERT INTO blob_sidecar_summary (
+                                slot,
+                                epoch,
+                                blob_count,
+                                blob_gas_used,
+                                excess_blob_gas,
+                                total_blob_size
+                            )
+                            VALUES ($1, $2, $3, $4, $5, $6)
+                            ON CONFLICT (slot) DO UPDATE SET
+                                epoch = EXCLUDED.epoch,
+                                blob_count = EXCLUDED.blob_count,
+                                blob_gas_used = EXCLUDED.blob_gas_used,
+                                excess_blob_gas = EXCLUDED.excess_blob_gas,
+                                total_blob_size = EXCLUDED.total_blob_size
+                        "#,
+                    )
+                    .bind(blob_summary.slot)
+                    .bind(blob_summary.epoch)
+                    .bind(blob_summary.blob_count)
+                    .bind(blob_summary.blob_gas_used)
+                    .bind(blob_summary.excess_blob_gas)
+                    .bind(blob_summary.total_blob_size)
+                    .execute(&mut *tx)
+                    .await?;
+                }
+                None => {
+                    sqlx::query("DELETE FROM blob_sidecar_summary WHERE slot = $1")
+                        .bind(slot.slot)
+                        .execute(&mut *tx)
+                        .await?;
+                }
+            }
+        }
+        tx.commit().await?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+impl NetworkScoped for PgStore {
+    fn network(&self) -> Network {
+        self.network
+    }
+}
+
+#[async_trait]
+impl EpochStore for PgStore {
+    async fn insert_epoch(&self, epoch_data: EpochData) -> AppResult<()> {
+        self.write_epoch(&epoch_data, false).await?;
+        Ok(())
+    }
+
+    async fn update_epoch(&self, epoch_number: i64, epoch_data: EpochData) -> AppResult<()> {
+        if self.get_epoch(epoch_number).await?.is_none() {
+            return Err(not_found("Epoch does not exist."));
+        }
+        self.write_epoch(&epoch_data, true).await?;
+        Ok(())
+    }
+
+    async fn get_epoch(&self, epoch_number: i64) -> AppResult<Option<EpochData>> {
+        sqlx::query("SELECT * FROM epoch_data WHERE epoch = $1")
+            .bind(epoch_number)
+            .fetch_optional(&self.pool)
+            .await?
+            .as_ref()
+            .map(epoch_from_row)
+            .transpose()
+    }
+
+    async fn get_latest_epoch(&self) -> AppResult<EpochData> {
+        let row = sqlx::query("SELECT * FROM epoch_data ORDER BY epoch DESC LIMIT 1")
+            .fetch_one(&self.pool)
+            .await?;
+        epoch_from_row(&row)
+    }
+
+    async fn get_latest_epochs(&self, count: i64) -> AppResult<Vec<EpochData>> {
+        sqlx::query("SELECT * FROM epoch_data ORDER BY epoch DESC LIMIT $1")
+            .bind(count)
+            .fetch_all(&self.pool)
+            .await?
+            .iter()
+            .map(epoch_from_row)
+            .collect()
+    }
+
+    async fn get_earliest_epoch(&self) -> AppResult<Option<EpochData>> {
+        sqlx::query("SELECT * FROM epoch_data ORDER BY epoch ASC LIMIT 1")
+            .fetch_optional(&self.pool)
+            .await?
+            .as_ref()
+            .map(epoch_from_row)
+            .transpose()
+    }
+
+    async fn get_epochs_in_range(
+        &self,
+        from_epoch: i64,
+        to_epoch: i64,
+    ) -> AppResult<Vec<EpochData>> {
+        sqlx::query("SELECT * FROM epoch_data WHERE epoch BETWEEN $1 AND $2 ORDER BY epoch ASC")
+            .bind(from_epoch)
+            .bind(to_epoch)
+            .fetch_all(&self.pool)
+            .await?
+            .iter()
+            .map(epoch_from_row)
+            .collect()
+    }
+
+    async fn get_latest_finalized_epoch(&self) -> AppResult<Option<EpochData>> {
+        sqlx::query("SELECT * FROM epoch_data WHERE finalized ORDER BY epoch DESC LIMIT 1")
+            .fetch_optional(&self.pool)
+            .await?
+            .as_ref()
+            .map(epoch_from_row)
+            .transpose()
+    }
+}
+
+#[async_trait]
+impl SlotStore for PgStore {
+    async fn insert_slot(&self, slot: &SlotData) -> AppResult<()> {
+        self.write_slot(slot, false).await?;
+        Ok(())
+    }
+
+    async fn update_slot(&self, slot: SlotData) -> AppResult<()> {
+        if self.get_slot(slot.slot).await?.is_none() {
+            return Err(not_found("Slot does not exist."));
+        }
+        self.write_slot(&slot, true).await?;
+        Ok(())
+    }
+
+    async fn get_slot(&self, slot_number: i64) -> AppResult<Option<SlotData>> {
+        sqlx::query("SELECT * FROM slot_data WHERE slot = $1")
+            .bind(slot_number)
+            .fetch_optional(&self.pool)
+            .await?
+            .as_ref()
+            .map(slot_from_row)
+            .transpose()
+    }
+
+    async fn get_epoch_slots(&self, epoch_number: i64) -> AppResult<Vec<SlotData>> {
+        sqlx::query("SELECT * FROM slot_data WHERE epoch = $1 ORDER BY slot ASC")
+            .bind(epoch_number)
+            .fetch_all(&self.pool)
+            .await?
+            .iter()
+            .map(slot_from_row)
+            .collect()
+    }
+
+    async fn get_slots_in_range(&self, from_slot: i64, to_slot: i64) -> AppResult<Vec<SlotData>> {
+        sqlx::query("SELECT * FROM slot_data WHERE slot BETWEEN $1 AND $2 ORDER BY slot ASC")
+            .bind(from_slot)
+            .bind(to_slot)
+            .fetch_all(&self.pool)
+            .await?
+            .iter()
+            .map(slot_from_row)
+            .collect()
+    }
+
+    async fn get_slots_by_proposer(&self, proposer: i64, limit: i64) -> AppResult<Vec<SlotData>> {
+        sqlx::query("SELECT * FROM slot_data WHERE proposer = $1 ORDER BY slot DESC LIMIT $2")
+            .bind(proposer)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await?
+            .iter()
+            .map(slot_from_row)
+            .collect()
+    }
+
+    async fn get_slots_by_fee_recipient(
+        &self,
+        fee_recipient: &str,
+        limit: i64,
+    ) -> AppResult<Vec<SlotData>> {
+        sqlx::query(
+            "SELECT * FROM slot_data WHERE exec_fee_recipient = $1 ORDER BY slot DESC LIMIT $2",
+        )
+        .bind(hex_to_bytes(fee_recipient)?)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?
+        .iter()
+        .map(slot_from_row)
+        .collect()
+    }
+
+    async fn query_slots(
+        &self,
+        query: &SlotQuery,
+        cursor: Option<i64>,
+        limit: i64,
+    ) -> AppResult<Vec<SlotData>> {
+        let Some((from_slot, to_slot)) = query.bounds(cursor) else {
+            return Ok(Vec::new());
+        };
+        let mut builder = QueryBuilder::<Postgres>::new("SELECT * FROM slot_data WHERE TRUE");
+        if let Some(from_slot) = from_slot {
+            builder.push(" AND slot >= ").push_bind(from_slot);
+        }
+        if let Some(to_slot) = to_slot {
+            builder.push(" AND slot <= ").push_bind(to_slot);
+        }
+        if let Some(epoch) = query.epoch {
+            builder.push(" AND epoch = ").push_bind(epoch);
+        }
+        if let Some(proposer) = query.proposer {
+            builder.push(" AND proposer = ").push_bind(proposer);
+        }
+        if let Some(status) = &query.status {
+            builder.push(" AND status = ").push_bind(status.clone());
+        }
+        if let Some(fee_recipient) = &query.fee_recipient {
+            builder
+                .push(" AND exec_fee_recipient = ")
+                .push_bind(hex_to_bytes(fee_recipient)?);
+        }
+        builder
+            .push(" ORDER BY slot ")
+            .push(query.order.as_sql())
+            .push(" LIMIT ")
+            .push_bind(limit);
+
+        builder
+            .build()
+            .fetch_all(&self.pool)
+            .await?
+            .iter()
+            .map(slot_from_row)
+            .collect()
+    }
+
+    async fn get_slot_by_exec_block_number(
+        &self,
+        exec_block_number: i64,
+    ) -> AppResult<Option<SlotData>> {
+        sqlx::query("SELECT * FROM slot_data WHERE exec_block_number = $1")
+            .bind(exec_block_number)
+            .fetch_optional(&self.pool)
+            .await?
+            .as_ref()
+            .map(slot_from_row)
+            .transpose()
+    }
+
+    async fn get_slot_by_exec_block_hash(
+        &self,
+        exec_block_hash: &str,
+    ) -> AppResult<Option<SlotData>> {
+        sqlx::query("SELECT * FROM slot_data WHERE exec_block_hash = $1")
+            .bind(hex_to_bytes(exec_block_hash)?)
+            .fetch_optional(&self.pool)
+            .await?
+            .as_ref()
+            .map(slot_from_row)
+            .transpose()
+    }
+
+    async fn get_slot_by_blockroot(&self, blockroot: &str) -> AppResult<Option<SlotData>> {
+        sqlx::query("SELECT * FROM slot_data WHERE blockroot = $1")
+            .bind(hex_to_bytes(blockroot)?)
+            .fetch_optional(&self.pool)
+            .await?
+            .as_ref()
+            .map(slot_from_row)
+            .transpose()
+    }
+
+    async fn get_latest_unexecuted_slot(&self) -> AppResult<SlotData> {
+        let row = sqlx::query(
+            "SELECT * FROM slot_data WHERE exec_timestamp <> 0 ORDER BY slot DESC LIMIT 1",
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        slot_from_row(&row)
+    }
+
+    async fn search_graffiti(
+        &self,
+        query: &GraffitiQuery,
+        before_slot: Option<i64>,
+        limit: i64,
+    ) -> AppResult<Vec<GraffitiMatch>> {
+        sqlx::query(
+            r#"
+                SELECT slot, epoch, proposer, graffiti_text
+                FROM slot_data
+                WHERE to_tsvector('simple', graffiti_text) @@ to_tsquery('simple', $1)
+                    AND slot < $2
+                ORDER BY slot DESC
+                LIMIT $3
+            "#,
+        )
+        .bind(query.to_tsquery())
+        .bind(before_slot.unwrap_or(i64::MAX))
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?
+        .iter()
+        .map(|row| -> AppResult<GraffitiMatch> {
+            Ok(GraffitiMatch::new(
+                self.network,
+                row.try_get("slot")?,
+                row.try_get("epoch")?,
+                row.try_get("proposer")?,
+                row.try_get("graffiti_text")?,
+            ))
+        })
+        .collect()
+    }
+
+    async fn get_blob_sidecar_summary(
+        &self,
+        slot_number: i64,
+    ) -> AppResult<Option<BlobSidecarSummary>> {
+        let row = sqlx::query("SELECT * FROM blob_sidecar_summary WHERE slot = $1")
+            .bind(slot_number)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        row.map(|row| -> AppResult<BlobSidecarSummary> {
+            Ok(BlobSidecarSummary {
+                slot: row.try_get("slot")?,
+                epoch: row.try_get("epoch")?,
+                blob_count: row.try_get("blob_count")?,
+                blob_gas_used: row.try_get("blob_gas_used")?,
+                excess_blob_gas: row.try_get("excess_blob_gas")?,
+                total_blob_size: row.try_get("total_blob_size")?,
+            })
+        })
+        .transpose()
+    }
+
+    async fn prune_slots(&self, before_epoch: i64, limit: i64) -> AppResult<u64> {
+        let mut tx = self.pool.begin().await?;
+        let expired: Vec<i64> = sqlx::query_scalar(
+            "SELECT slot FROM slot_data WHERE epoch < $1 ORDER BY slot ASC LIMIT $2",
+        )
+        .bind(before_epoch)
+        .bind(limit)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        // The rollups keep counting the pruned slots, the insert trigger skips them if they
+        // are stored again.
+        if let Some(last) = expired.last() {
+            sqlx::query(
+                "UPDATE chain_config SET pruned_before_slot = GREATEST(pruned_before_slot, $1)",
+            )
+            .bind(last + 1)
+            .execute(&mut *tx)
+            .await?;
+        }
+        sqlx::query("DELETE FROM slot_data_history WHERE slot = ANY($1)")
+            .bind(&expired)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM blob_sidecar_summary WHERE slot = ANY($1)")
+            .bind(&expired)
+            .execute(&mut *tx)
+            .await?;
+        let result = sqlx::query("DELETE FROM slot_data WHERE slot = ANY($1)")
+            .bind(&expired)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn prune_blob_sidecar_summaries(&self, before_epoch: i64, limit: i64) -> AppResult<u64> {
+        let result = sqlx::query(
+            r#"
+                DELETE FROM blob_sidecar_summary
+                WHERE slot IN (
+                    SELECT slot
+                    FROM blob_sidecar_summary
+                    WHERE epoch < $1
+                    ORDER BY slot ASC
+                    LIMIT $2
+                )
+            "#,
+        )
+        .bind(before_epoch)
+        .bind(limit)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Autovacuum already recycles dead tuples, space is reused without a manual `VACUUM`.
+    async fn reclaim_space(&self, _max_pages: i64) -> AppResult<()> {
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl RollupStore for PgStore {
+    async fn get_rollups(
+        &self,
+        granularity: RollupGranularity,
+        from_ts: i64,
+        to_ts: i64,
+    ) -> AppResult<Vec<Rollup>> {
+        let table = match granularity {
+            RollupGranularity::Hourly => "rollup_hourly",
+            RollupGranularity::Daily => "rollup_daily",
+        };
+
+        sqlx::query(&format!(
+            "SELECT * FROM {table} WHERE bucket_start BETWEEN $1 AND $2 ORDER BY bucket_start ASC"
+        ))
+        .bind(from_ts)
+        .bind(to_ts)
+        .fetch_all(&self.pool)
+        .await?
+        .iter()
+        .map(rollup_from_row)
+        .collect()
+    }
+}
+
+/// `(recorded_at, source)` of a history row, converted alongside the row itself.
+fn version_from_row<T>(
+    row: &PgRow,
+    data_from_row: fn(&PgRow) -> AppResult<T>,
+) -> AppResult<RowVersion<T>> {
+    Ok(RowVersion::new(
+        row.try_get("recorded_at")?,
+        row.try_get("source")?,
+        data_from_row(row)?,
+    ))
+}
+
+#[async_trait]
+impl HistoryStore for PgStore {
+    async fn get_epoch_as_of(&self, epoch_number: i64, as_of: i64) -> AppResult<Option<EpochData>> {
+        sqlx::query(
+            r#"
+                SELECT * FROM epoch_data_history
+                WHERE epoch = $1 AND recorded_at <= $2
+                ORDER BY recorded_at DESC, history_id DESC
+                LIMIT 1
+            "#,
+        )
+        .bind(epoch_number)
+        .bind(as_of)
+        .fetch_optional(&self.pool)
+        .await?
+        .as_ref()
+        .map(epoch_from_row)
+        .transpose()
+    }
+
+    async fn get_slot_as_of(&self, slot_number: i64, as_of: i64) -> AppResult<Option<SlotData>> {
+        sqlx::query(
+            r#"
+                SELECT * FROM slot_data_history
+                WHERE slot = $1 AND recorded_at <= $2
+                ORDER BY recorded_at DESC, history_id DESC
+                LIMIT 1
+            "#,
+        )
+        .bind(slot_number)
+        .bind(as_of)
+        .fetch_optional(&self.pool)
+        .await?
+        .as_ref()
+        .map(slot_from_row)
+        .transpose()
+    }
+
+    async fn get_epoch_history(&self, epoch_number: i64) -> AppResult<Vec<RowVersion<EpochData>>> {
+        sqlx::query(
+            "SELECT * FROM epoch_data_history WHERE epoch = $1 ORDER BY recorded_at, history_id",
+        )
+        .bind(epoch_number)
+        .fetch_all(&self.pool)
+        .await?
+        .iter()
+        .map(|row| version_from_row(row, epoch_from_row))
+        .collect()
+    }
+
+    async fn get_slot_history(&self, slot_number: i64) -> AppResult<Vec<RowVersion<SlotData>>> {
+        sqlx::query(
+            "SELECT * FROM slot_data_history WHERE slot = $1 ORDER BY recorded_at, history_id",
+        )
+        .bind(slot_number)
+        .fetch_all(&self.pool)
+        .await?
+        .iter()
+        .map(|row| version_from_row(row, slot_from_row))
+        .collect()
+    }
+}
+
+#[async_trait]
+impl ChangeStore for PgStore {
+    async fn get_changes(&self, since: i64, limit: i64) -> AppResult<Vec<ChangeEvent>> {
+        let rows = sqlx::query(
+            r#"
+                SELECT seq, recorded_at, entity, key, kind, payload::text AS payload
+                FROM change_outbox
+                WHERE seq > $1
+                ORDER BY seq
+                LIMIT $2
+            "#,
+        )
+        .bind(since)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(change_from_row).collect()
+    }
+
+    async fn get_sink_cursor(&self, sink: &str) -> AppResult<i64> {
+        let cursor = sqlx::query("SELECT seq FROM sink_cursors WHERE sink = $1")
+            .bind(sink)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        match cursor {
+            Some(row) => Ok(row.try_get("seq")?),
+            None => Ok(0),
+        }
+    }
+
+    async fn set_sink_cursor(&self, sink: &str, seq: i64) -> AppResult<()> {
+        sqlx::query(
+            r#"
+                INSERT INTO sink_cursors (sink, seq, updated_at)
+                VALUES ($1, $2, $3)
+                ON CONFLICT (sink) DO UPDATE SET
+                    seq = EXCLUDED.seq,
+                    updated_at = EXCLUDED.updated_at
+            "#,
+        )
+        .bind(sink)
+        .bind(seq)
+        .bind(now_millis())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn prune_changes(&self, before: i64, limit: i64) -> AppResult<u64> {
+        let result = sqlx::query(
+            r#"
+                DELETE FROM change_outbox
+                WHERE seq IN (
+                    SELECT seq
+                    FROM change_outbox
+                    WHERE recorded_at < $1
+                        AND (
+                            NOT EXISTS (SELECT 1 FROM sink_cursors)
+                            OR seq <= (SELECT MIN(seq) FROM sink_cursors)
+                        )
+                    ORDER BY seq ASC
+                    LIMIT $2
+                )
+            "#,
+        )
+        .bind(before)
+        .bind(limit)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,72 @@
+use std::{str::FromStr, time::Duration};
+
+use sqlx::{
+    sqlite::{
+        SqliteAutoVacuum, SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions,
+        SqliteSynchronous,
+    },
+    SqlitePool,
+};
+
+use crate::AppResult;
+
+/// How long a connection waits on a locked database before giving up with `SQLITE_BUSY`.
+pub const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+/// Readers never block each other under WAL, so handlers and schedulers share several.
+pub const READ_POOL_SIZE: u32 = 8;
+
+/// SQLite only allows one writer at a time: every write goes through the single connection
+/// of `writer`, while reads are spread over `reader` and never wait on a write under WAL.
+#[derive(Debug, Clone)]
+pub struct DbPools {
+    pub reader: SqlitePool,
+    pub writer: SqlitePool,
+}
+
+impl DbPools {
+    pub async fn connect(db_url: &str) -> AppResult<Self> {
+        let options = SqliteConnectOptions::from_str(db_url)?
+            .journal_mode(SqliteJournalMode::Wal)
+            .synchronous(SqliteSynchronous::Normal)
+            .busy_timeout(BUSY_TIMEOUT);
+
+        // The writer connects first so the database is switched to WAL before any reader opens it.
+        // Incremental auto-vacuum only takes effect on new files, older ones are converted by the
+        // first `reclaim_space` run.
+        let writer = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(
+                options
+                    .clone()
+                    .create_if_missing(true)
+                    .auto_vacuum(SqliteAutoVacuum::Incremental),
+            )
+            .await?;
+        let reader = SqlitePoolOptions::new()
+            .max_connections(READ_POOL_SIZE)
+            .connect_with(options.read_only(true))
+            .await?;
+
+        Ok(DbPools { reader, writer })
+    }
+
+    /// Opens an existing database without changing it: the file is not created when missing,
+    /// no pragma switches its journal or auto-vacuum mode, and both pools are read-only so a
+    /// stray write fails instead of modifying it.
+    pub async fn connect_read_only(db_url: &str) -> AppResult<Self> {
+        let options = SqliteConnectOptions::from_str(db_url)?
+            .read_only(true)
+            .busy_timeout(BUSY_TIMEOUT);
+
+        let writer = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options.clone())
+            .await?;
+        let reader = SqlitePoolOptions::new()
+            .max_connections(READ_POOL_SIZE)
+            .connect_with(options)
+            .await?;
+
+        Ok(DbPools { reader, writer })
+    }
+}
@@ -0,0 +1,189 @@
+//! Lookups issued by `db_ops` that must be answered through an index. The SQL is the one the
+//! functions run: the `query_file_as!` files and the [`push_slot_query`] builder, checked with
+//! `EXPLAIN QUERY PLAN` against a freshly migrated database.
+
+use std::sync::Arc;
+
+use sqlx::{QueryBuilder, Row, Sqlite};
+
+use super::{db_ops::push_slot_query, pools::DbPools, setup_db};
+use crate::{
+    models::{SlotOrder, SlotQuery},
+    utils::MAINNET,
+    AppResult,
+};
+
+const INDEXED_QUERIES: &[(&str, &str)] = &[
+    (
+        "get_epoch_slots",
+        include_str!("../../queries/get_epoch_slots.sql"),
+    ),
+    (
+        "get_slots_by_proposer",
+        include_str!("../../queries/get_slots_by_proposer.sql"),
+    ),
+    (
+        "get_slots_by_fee_recipient",
+        include_str!("../../queries/get_slots_by_fee_recipient.sql"),
+    ),
+    (
+        "get_slot_by_exec_block_number",
+        include_str!("../../queries/get_slot_by_exec_block_number.sql"),
+    ),
+    (
+        "get_slot_by_exec_block_hash",
+        include_str!("../../queries/get_slot_by_exec_block_hash.sql"),
+    ),
+    (
+        "get_slot_by_blockroot",
+        include_str!("../../queries/get_slot_by_blockroot.sql"),
+    ),
+    ("get_slot", include_str!("../../queries/get_slot.sql")),
+    (
+        "get_epoch_data",
+        include_str!("../../queries/get_epoch_data.sql"),
+    ),
+    (
+        "get_epochs_in_range",
+        include_str!("../../queries/get_epochs_in_range.sql"),
+    ),
+    (
+        "get_slots_in_range",
+        include_str!("../../queries/get_slots_in_range.sql"),
+    ),
+    (
+        "get_latest_finalized_epoch_data",
+        include_str!("../../queries/get_latest_finalized_epoch_data.sql"),
+    ),
+];
+
+/// `query_slots` filters that each have an index of their own.
+fn indexed_slot_queries() -> Vec<(&'static str, SlotQuery, Option<i64>)> {
+    vec![
+        (
+            "query_slots by range",
+            SlotQuery {
+                from_slot: Some(100),
+                to_slot: Some(200),
+                ..SlotQuery::default()
+            },
+            None,
+        ),
+        (
+            "query_slots by cursor",
+            SlotQuery {
+                order: SlotOrder::Descending,
+                ..SlotQuery::default()
+            },
+            Some(200),
+        ),
+        (
+            "query_slots by epoch",
+            SlotQuery {
+                epoch: Some(3),
+                ..SlotQuery::default()
+            },
+            None,
+        ),
+        (
+            "query_slots by proposer",
+            SlotQuery {
+                proposer: Some(7),
+                ..SlotQuery::default()
+            },
+            Some(200),
+        ),
+        (
+            "query_slots by fee recipient",
+            SlotQuery {
+                fee_recipient: Some(format!("0x{}", "11".repeat(20))),
+                order: SlotOrder::Descending,
+                ..SlotQuery::default()
+            },
+            None,
+        ),
+    ]
+}
+
+/// Migrated database file removed with its WAL files on drop.
+struct TempDb(String);
+
+impl TempDb {
+    fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("rish-{name}-{}.sqlite", std::process::id()));
+        let db = TempDb(path.display().to_string());
+        db.remove();
+        db
+    }
+
+    fn remove(&self) {
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{suffix}", self.0));
+        }
+    }
+}
+
+impl Drop for TempDb {
+    fn drop(&mut self) {
+        self.remove();
+    }
+}
+
+/// Returns the `EXPLAIN QUERY PLAN` detail lines of `query`, binding `NULL` to every parameter.
+async fn query_plan(db_conn: Arc<DbPools>, query: &str) -> AppResult<Vec<String>> {
+    let explain = format!("EXPLAIN QUERY PLAN {query}");
+    let mut statement = sqlx::query(&explain);
+    for _ in 0..query.matches('?').count() {
+        statement = statement.bind(Option::<i64>::None);
+    }
+
+    let rows = statement.fetch_all(&db_conn.reader).await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| row.get::<String, _>("detail"))
+        .collect())
+}
+
+/// A plain `SCAN <table>` step without `USING ... INDEX` reads every row of the table.
+fn is_full_scan(detail: &str) -> bool {
+    detail.starts_with("SCAN ") && !detail.contains("INDEX")
+}
+
+#[tokio::test]
+async fn lookups_use_an_index() {
+    let db = TempDb::new("query-plans");
+    let db_conn = setup_db(&format!("sqlite://{}", db.0), MAINNET)
+        .await
+        .unwrap();
+    let mut full_scans = Vec::new();
+
+    for (name, query) in INDEXED_QUERIES {
+        let plan = query_plan(Arc::clone(&db_conn), query).await.unwrap();
+        if plan.iter().any(|detail| is_full_scan(detail)) {
+            full_scans.push(format!("{name}: {}", plan.join("; ")));
+        }
+    }
+
+    for (name, query, cursor) in indexed_slot_queries() {
+        let mut builder = QueryBuilder::<Sqlite>::new("EXPLAIN QUERY PLAN ");
+        push_slot_query(&mut builder, &query, query.bounds(cursor).unwrap(), 100).unwrap();
+        let plan: Vec<String> = builder
+            .build()
+            .fetch_all(&db_conn.reader)
+            .await
+            .unwrap()
+            .iter()
+            .map(|row| row.get::<String, _>("detail"))
+            .collect();
+        if plan.iter().any(|detail| is_full_scan(detail)) {
+            full_scans.push(format!("{name}: {}", plan.join("; ")));
+        }
+    }
+
+    assert!(
+        full_scans.is_empty(),
+        "Queries not using an index:\n{}",
+        full_scans.join("\n")
+    );
+}
@@ -0,0 +1,642 @@
+use std::sync::Arc;
+
+use super::{db_ops, migrations, pools::DbPools};
+use crate::{
+    dtos::{EpochWithSlots, SlotDataDto},
+    models::{
+        BlobSidecarSummary, ChangeEvent, ChangeSource, EpochData, GraffitiMatch, GraffitiQuery,
+        Rollup, RollupGranularity, RowVersion, SlotData, SlotQuery,
+    },
+    utils::{self, Network, PersistMode},
+    AppResult,
+};
+use async_trait::async_trait;
+use sqlx::{migrate::MigrateDatabase, Sqlite};
+
+#[async_trait]
+pub trait EpochStore: Send + Sync {
+    /// Inserts the epoch unless a row for it already exists.
+    async fn insert_epoch(&self, epoch_data: EpochData) -> AppResult<()>;
+    /// Overwrites an existing epoch, erroring with `NotFound` when it was never inserted.
+    async fn update_epoch(&self, epoch_number: i64, epoch_data: EpochData) -> AppResult<()>;
+    async fn get_epoch(&self, epoch_number: i64) -> AppResult<Option<EpochData>>;
+    async fn get_latest_epoch(&self) -> AppResult<EpochData>;
+    /// The `count` newest stored epochs, newest first, skipping over any gaps.
+    async fn get_latest_epochs(&self, count: i64) -> AppResult<Vec<EpochData>>;
+    async fn get_earliest_epoch(&self) -> AppResult<Option<EpochData>>;
+    /// Stored epochs within `from_epoch..=to_epoch`, oldest first.
+    async fn get_epochs_in_range(
+        &self,
+        from_epoch: i64,
+        to_epoch: i64,
+    ) -> AppResult<Vec<EpochData>>;
+    /// Newest epoch marked as finalized, if any.
+    async fn get_latest_finalized_epoch(&self) -> AppResult<Option<EpochData>>;
+}
+
+#[async_trait]
+pub trait SlotStore: Send + Sync {
+    /// Inserts the slot unless a row for it already exists.
+    async fn insert_slot(&self, slot: &SlotData) -> AppResult<()>;
+    /// Overwrites an existing slot, erroring with `NotFound` when it was never inserted.
+    async fn update_slot(&self, slot: SlotData) -> AppResult<()>;
+    async fn get_slot(&self, slot_number: i64) -> AppResult<Option<SlotData>>;
+    async fn get_epoch_slots(&self, epoch_number: i64) -> AppResult<Vec<SlotData>>;
+    /// Stored slots within `from_slot..=to_slot`, oldest first.
+    async fn get_slots_in_range(&self, from_slot: i64, to_slot: i64) -> AppResult<Vec<SlotData>>;
+    /// Most recent `limit` slots proposed by `proposer`, newest first.
+    async fn get_slots_by_proposer(&self, proposer: i64, limit: i64) -> AppResult<Vec<SlotData>>;
+    /// Most recent `limit` slots paying `fee_recipient`, newest first.
+    async fn get_slots_by_fee_recipient(
+        &self,
+        fee_recipient: &str,
+        limit: i64,
+    ) -> AppResult<Vec<SlotData>>;
+    /// At most `limit` slots matching `query` in its order, continuing after the slot
+    /// `cursor` when given.
+    async fn query_slots(
+        &self,
+        query: &SlotQuery,
+        cursor: Option<i64>,
+        limit: i64,
+    ) -> AppResult<Vec<SlotData>>;
+    async fn get_slot_by_exec_block_number(
+        &self,
+        exec_block_number: i64,
+    ) -> AppResult<Option<SlotData>>;
+    async fn get_slot_by_exec_block_hash(
+        &self,
+        exec_block_hash: &str,
+    ) -> AppResult<Option<SlotData>>;
+    async fn get_slot_by_blockroot(&self, blockroot: &str) -> AppResult<Option<SlotData>>;
+    async fn get_latest_unexecuted_slot(&self) -> AppResult<SlotData>;
+    /// Slots whose graffiti matches `query`, newest first, at most `limit` of them and only
+    /// slots before `before_slot` when given.
+    async fn search_graffiti(
+        &self,
+        query: &GraffitiQuery,
+        before_slot: Option<i64>,
+        limit: i64,
+    ) -> AppResult<Vec<GraffitiMatch>>;
+    async fn get_blob_sidecar_summary(
+        &self,
+        slot_number: i64,
+    ) -> AppResult<Option<BlobSidecarSummary>>;
+    /// Deletes at most `limit` of the oldest slots before `before_epoch` along with their blob
+    /// sidecar summaries, returning how many slots were deleted. The rollups keep counting them,
+    /// a pruned slot inserted again is not added to them a second time.
+    async fn prune_slots(&self, before_epoch: i64, limit: i64) -> AppResult<u64>;
+    /// Deletes at most `limit` of the oldest blob sidecar summaries before `before_epoch`.
+    async fn prune_blob_sidecar_summaries(&self, before_epoch: i64, limit: i64) -> AppResult<u64>;
+    /// Gives space freed by pruning back to the filesystem, `max_pages` at a time where the
+    /// backend supports it.
+    async fn reclaim_space(&self, max_pages: i64) -> AppResult<()>;
+}
+
+#[async_trait]
+pub trait RollupStore: Send + Sync {
+    /// Hourly or daily aggregates whose bucket starts within `from_ts..=to_ts` (unix seconds),
+    /// oldest first. Buckets are maintained as epochs and slots are inserted and updated.
+    async fn get_rollups(
+        &self,
+        granularity: RollupGranularity,
+        from_ts: i64,
+        to_ts: i64,
+    ) -> AppResult<Vec<Rollup>>;
+}
+
+/// Every insert or update that changes an epoch or slot is also recorded as a version, stamped
+/// with the ingestion time and the store's [`ChangeSource`]. `as_of` is in unix milliseconds.
+#[async_trait]
+pub trait HistoryStore: Send + Sync {
+    /// The epoch as rish had it stored at `as_of`.
+    async fn get_epoch_as_of(&self, epoch_number: i64, as_of: i64) -> AppResult<Option<EpochData>>;
+    /// The slot as rish had it stored at `as_of`.
+    async fn get_slot_as_of(&self, slot_number: i64, as_of: i64) -> AppResult<Option<SlotData>>;
+    /// Every recorded version of the epoch, oldest first.
+    async fn get_epoch_history(&self, epoch_number: i64) -> AppResult<Vec<RowVersion<EpochData>>>;
+    /// Every recorded version of the slot, oldest first.
+    async fn get_slot_history(&self, slot_number: i64) -> AppResult<Vec<RowVersion<SlotData>>>;
+}
+
+/// Change-data-capture outbox: every insert or update that changes an epoch or slot also
+/// appends a [`ChangeEvent`] in the same transaction, numbered by an increasing `seq`.
+#[async_trait]
+pub trait ChangeStore: Send + Sync {
+    /// At most `limit` events with `seq` above `since`, oldest first.
+    async fn get_changes(&self, since: i64, limit: i64) -> AppResult<Vec<ChangeEvent>>;
+    /// Last `seq` delivered to the named output sink, 0 before its first delivery.
+    async fn get_sink_cursor(&self, sink: &str) -> AppResult<i64>;
+    async fn set_sink_cursor(&self, sink: &str, seq: i64) -> AppResult<()>;
+    /// Deletes at most `limit` of the oldest events recorded before `before` (unix
+    /// milliseconds). Events not yet delivered to every sink with a cursor are kept, events
+    /// recorded at or after `before` are always kept.
+    async fn prune_changes(&self, before: i64, limit: i64) -> AppResult<u64>;
+}
+
+/// Every store holds the chain of a single network, which decides slot timing and where missing
+/// rows are fetched from.
+pub trait NetworkScoped: Send + Sync {
+    fn network(&self) -> Network;
+}
+
+pub trait Store:
+    EpochStore + SlotStore + RollupStore + HistoryStore + ChangeStore + NetworkScoped
+{
+}
+
+impl<T> Store for T where
+    T: EpochStore + SlotStore + RollupStore + HistoryStore + ChangeStore + NetworkScoped
+{
+}
+
+pub type SharedStore = Arc<dyn Store>;
+
+/// The runtime `DATABASE_URL` wins over the one baked in at compile time for the `query!` macros.
+/// Builds checked against the committed `.sqlx` data have none and fall back to `rish.sqlite`.
+pub fn database_url() -> String {
+    std::env::var("DATABASE_URL").unwrap_or_else(|_| {
+        option_env!("DATABASE_URL")
+            .unwrap_or("sqlite://rish.sqlite")
+            .to_string()
+    })
+}
+
+pub fn is_postgres_url(db_url: &str) -> bool {
+    db_url.starts_with("postgres://") || db_url.starts_with("postgresql://")
+}
+
+/// Applies pending migrations without starting anything else.
+pub async fn migrate_only(db_url: &str, network: Network) -> AppResult<()> {
+    if is_postgres_url(db_url) {
+        open_postgres_store(db_url, network, ChangeSource::default()).await?;
+    } else {
+        super::open_db(db_url).await?;
+    }
+
+    Ok(())
+}
+
+/// Reverts the schema down to `target_version`.
+pub async fn migrate_down(db_url: &str, target_version: i64) -> AppResult<()> {
+    if is_postgres_url(db_url) {
+        #[cfg(feature = "postgres")]
+        return super::PgStore::undo_migrations(db_url, target_version).await;
+        #[cfg(not(feature = "postgres"))]
+        return Err(
+            format!("Cannot open {db_url}: rish was built without the `postgres` feature").into(),
+        );
+    }
+
+    let db_conn = Arc::new(DbPools::connect(db_url).await?);
+    migrations::undo_migrations(db_conn, target_version).await?;
+
+    Ok(())
+}
+
+/// Opens the database behind `db_url` for `network`, PostgreSQL or SQLite by URL scheme,
+/// applying pending migrations first. Rows written through the store are attributed to `source`.
+pub async fn open_store(
+    db_url: &str,
+    network: Network,
+    source: ChangeSource,
+) -> AppResult<SharedStore> {
+    if is_postgres_url(db_url) {
+        return open_postgres_store(db_url, network, source).await;
+    }
+
+    let db_pool = super::setup_db(db_url, network).await?;
+    Ok(Arc::new(
+        SqliteStore::new(db_pool, network).with_source(source),
+    ))
+}
+
+/// Opens an existing SQLite database read-only, without migrating it or writing the default
+/// rows, for callers that must not change it. It must exist and be at the schema version of
+/// this binary. PostgreSQL databases are opened like [`open_store`] does.
+pub async fn open_store_read_only(db_url: &str, network: Network) -> AppResult<SharedStore> {
+    if is_postgres_url(db_url) {
+        return open_postgres_store(db_url, network, ChangeSource::default()).await;
+    }
+
+    if !Sqlite::database_exists(db_url).await.unwrap_or(false) {
+        return Err(not_found(&format!("Database {db_url} does not exist")));
+    }
+    let db_pool = Arc::new(DbPools::connect_read_only(db_url).await?);
+    migrations::ensure_current(Arc::clone(&db_pool)).await?;
+
+    Ok(Arc::new(SqliteStore::new(db_pool, network)))
+}
+
+#[cfg(feature = "postgres")]
+pub async fn open_postgres_store(
+    db_url: &str,
+    network: Network,
+    source: ChangeSource,
+) -> AppResult<SharedStore> {
+    Ok(Arc::new(
+        super::PgStore::connect(db_url, network)
+            .await?
+            .with_source(source),
+    ))
+}
+
+#[cfg(not(feature = "postgres"))]
+pub async fn open_postgres_store(
+    db_url: &str,
+    _network: Network,
+    _source: ChangeSource,
+) -> AppResult<SharedStore> {
+    Err(format!("Cannot open {db_url}: rish was built without the `postgres` feature").into())
+}
+
+pub(crate) fn not_found(message: &str) -> Box<dyn std::error::Error + Send + Sync> {
+    Box::new(std::io::Error::new(
+        std::io::ErrorKind::NotFound,
+        message.to_string(),
+    ))
+}
+
+/// Looks the slot up in the store, fetching and storing it from the beacon chain on a miss.
+/// Slots that have not started yet are `NotFound` without asking the beacon chain.
+pub async fn get_or_fetch_slot(store: &dyn Store, slot_number: i64) -> AppResult<SlotData> {
+    if let Some(slot) = store.get_slot(slot_number).await? {
+        return Ok(slot);
+    }
+
+    let network = store.network();
+    if network.slot_timestamp(slot_number) > chrono::Utc::now().timestamp() {
+        return Err(not_found(&format!(
+            "Slot {slot_number} has not started yet"
+        )));
+    }
+
+    println!("Slot {slot_number} not found in DB, fetching slot from beacon chain...");
+    let slot: SlotData = utils::external_api::get_specific_slot(network, slot_number)
+        .await?
+        .into();
+    let report = utils::pipeline::ingest_decoded(
+        store,
+        PersistMode::Insert,
+        network.epoch_of_slot(slot_number),
+        None,
+        vec![slot],
+    )
+    .await?;
+    if report.slots_persisted == 0 {
+        return Err(format!("Slot {slot_number} from the beacon chain could not be stored").into());
+    }
+    println!("Slot {slot_number} inserted into db successfully");
+
+    store
+        .get_slot(slot_number)
+        .await?
+        .ok_or_else(|| not_found(&format!("Slot {slot_number} not found")))
+}
+
+/// Looks the epoch up in the store, fetching and storing it from the beacon chain on a miss.
+/// Epochs that have not started yet are `NotFound` without asking the beacon chain.
+pub async fn get_or_fetch_epoch(store: &dyn Store, epoch_number: i64) -> AppResult<EpochData> {
+    if let Some(epoch_data) = store.get_epoch(epoch_number).await? {
+        return Ok(epoch_data);
+    }
+
+    let network = store.network();
+    if network.epoch_timestamp(epoch_number) > chrono::Utc::now().timestamp() {
+        return Err(not_found(&format!(
+            "Epoch {epoch_number} has not started yet"
+        )));
+    }
+
+    println!("Epoch {epoch_number} not found in DB, fetching epoch from beacon chain...");
+    let epoch_data: EpochData =
+        utils::external_api::get_specific_epoch_data(network, &epoch_number.to_string())
+            .await?
+            .into();
+    utils::pipeline::ingest_decoded(
+        store,
+        PersistMode::Insert,
+        epoch_number,
+        Some(epoch_data),
+        Vec::new(),
+    )
+    .await?;
+    println!("Epoch {epoch_number} inserted into db successfully");
+
+    store
+        .get_epoch(epoch_number)
+        .await?
+        .ok_or_else(|| not_found(&format!("Epoch {epoch_number} not found")))
+}
+
+/// The newest finalized epoch. A stored one is only trusted while it is at most
+/// [`FINALITY_DELAY_EPOCHS`](utils::FINALITY_DELAY_EPOCHS) behind the running epoch, the
+/// `finalized` flags of older rows are not kept up to date, so the beacon chain is asked
+/// otherwise.
+pub async fn get_or_fetch_finalized_epoch(store: &dyn Store) -> AppResult<EpochData> {
+    let network = store.network();
+    let assumed_finalized = network.finalized_epoch_at(chrono::Utc::now().timestamp());
+    if let Some(epoch_data) = store.get_latest_finalized_epoch().await? {
+        if epoch_data.epoch >= assumed_finalized {
+            return Ok(epoch_data);
+        }
+    }
+
+    println!("No recent finalized epoch in DB, fetching it from beacon chain...");
+    let epoch_data: EpochData = utils::external_api::get_specific_epoch_data(network, "finalized")
+        .await?
+        .into();
+    let epoch_number = epoch_data.epoch;
+    // The epoch is usually stored already, from before it was finalized.
+    utils::pipeline::ingest_decoded(
+        store,
+        PersistMode::Replace,
+        epoch_number,
+        Some(epoch_data),
+        Vec::new(),
+    )
+    .await?;
+    println!("Finalized epoch {epoch_number} stored in db successfully");
+
+    store
+        .get_epoch(epoch_number)
+        .await?
+        .ok_or_else(|| not_found(&format!("Epoch {epoch_number} not found")))
+}
+
+/// The epoch's slots in the store, fetching and storing them from the beacon chain when none
+/// are stored. Epochs that have not started yet are `NotFound` without asking the beacon chain.
+pub async fn get_or_fetch_epoch_slots(
+    store: &dyn Store,
+    epoch_number: i64,
+) -> AppResult<Vec<SlotData>> {
+    let slots = store.get_epoch_slots(epoch_number).await?;
+    if !slots.is_empty() {
+        return Ok(slots);
+    }
+
+    let network = store.network();
+    if network.epoch_timestamp(epoch_number) > chrono::Utc::now().timestamp() {
+        return Err(not_found(&format!(
+            "Epoch {epoch_number} has not started yet"
+        )));
+    }
+
+    println!("Epoch {epoch_number} slots not found in DB, fetching them from beacon chain...");
+    let slots: Vec<SlotData> = utils::external_api::get_specific_epoch_slots(network, epoch_number)
+        .await?
+        .into_iter()
+        .map(SlotData::from)
+        .collect();
+    let report =
+        utils::pipeline::ingest_decoded(store, PersistMode::Insert, epoch_number, None, slots)
+            .await?;
+    println!(
+        "Epoch {epoch_number}: {} slots inserted into db",
+        report.slots_persisted
+    );
+
+    store.get_epoch_slots(epoch_number).await
+}
+
+/// The `count` newest stored epochs with their slots, newest first, read from the store only.
+/// Missing epochs are skipped rather than counted, so up to `count` are always returned.
+pub async fn get_recent_epochs(store: &dyn Store, count: i64) -> AppResult<Vec<EpochWithSlots>> {
+    let epochs = store.get_latest_epochs(count).await?;
+
+    let mut recent = Vec::with_capacity(epochs.len());
+    for epoch_data in epochs {
+        let slots = store.get_epoch_slots(epoch_data.epoch).await?;
+        recent.push(EpochWithSlots {
+            epoch: epoch_data.into(),
+            slots: slots.into_iter().map(SlotDataDto::from).collect(),
+        });
+    }
+
+    Ok(recent)
+}
+
+#[derive(Clone)]
+pub struct SqliteStore {
+    db_conn: Arc<DbPools>,
+    network: Network,
+    source: ChangeSource,
+}
+
+impl SqliteStore {
+    pub fn new(db_conn: Arc<DbPools>, network: Network) -> Self {
+        SqliteStore {
+            db_conn,
+            network,
+            source: ChangeSource::default(),
+        }
+    }
+
+    /// Records writes through this store with `source` in the row history.
+    pub fn with_source(mut self, source: ChangeSource) -> Self {
+        self.source = source;
+        self
+    }
+}
+
+impl NetworkScoped for SqliteStore {
+    fn network(&self) -> Network {
+        self.network
+    }
+}
+
+#[async_trait]
+impl EpochStore for SqliteStore {
+    async fn insert_epoch(&self, epoch_data: EpochData) -> AppResult<()> {
+        db_ops::insert_epoch(Arc::clone(&self.db_conn), epoch_data, self.source).await
+    }
+
+    async fn update_epoch(&self, epoch_number: i64, epoch_data: EpochData) -> AppResult<()> {
+        db_ops::update_epoch(
+            Arc::clone(&self.db_conn),
+            epoch_number,
+            epoch_data,
+            self.source,
+        )
+        .await
+    }
+
+    async fn get_epoch(&self, epoch_number: i64) -> AppResult<Option<EpochData>> {
+        db_ops::get_epoch_data(Arc::clone(&self.db_conn), epoch_number).await
+    }
+
+    async fn get_latest_epoch(&self) -> AppResult<EpochData> {
+        db_ops::get_latest_epoch_data(Arc::clone(&self.db_conn)).await
+    }
+
+    async fn get_latest_epochs(&self, count: i64) -> AppResult<Vec<EpochData>> {
+        db_ops::get_latest_epochs(Arc::clone(&self.db_conn), count).await
+    }
+
+    async fn get_earliest_epoch(&self) -> AppResult<Option<EpochData>> {
+        db_ops::get_earliest_epoch_data(Arc::clone(&self.db_conn)).await
+    }
+
+    async fn get_epochs_in_range(
+        &self,
+        from_epoch: i64,
+        to_epoch: i64,
+    ) -> AppResult<Vec<EpochData>> {
+        db_ops::get_epochs_in_range(Arc::clone(&self.db_conn), from_epoch, to_epoch).await
+    }
+
+    async fn get_latest_finalized_epoch(&self) -> AppResult<Option<EpochData>> {
+        db_ops::get_latest_finalized_epoch_data(Arc::clone(&self.db_conn)).await
+    }
+}
+
+#[async_trait]
+impl SlotStore for SqliteStore {
+    async fn insert_slot(&self, slot: &SlotData) -> AppResult<()> {
+        db_ops::insert_slot(Arc::clone(&self.db_conn), slot.slot, slot, self.source).await
+    }
+
+    async fn update_slot(&self, slot: SlotData) -> AppResult<()> {
+        db_ops::update_slot(Arc::clone(&self.db_conn), slot.slot, slot, self.source).await
+    }
+
+    async fn get_slot(&self, slot_number: i64) -> AppResult<Option<SlotData>> {
+        db_ops::get_slot(Arc::clone(&self.db_conn), slot_number).await
+    }
+
+    async fn get_epoch_slots(&self, epoch_number: i64) -> AppResult<Vec<SlotData>> {
+        db_ops::get_epoch_slots(Arc::clone(&self.db_conn), epoch_number).await
+    }
+
+    async fn get_slots_in_range(&self, from_slot: i64, to_slot: i64) -> AppResult<Vec<SlotData>> {
+        db_ops::get_slots_in_range(Arc::clone(&self.db_conn), from_slot, to_slot).await
+    }
+
+    async fn get_slots_by_proposer(&self, proposer: i64, limit: i64) -> AppResult<Vec<SlotData>> {
+        db_ops::get_slots_by_proposer(Arc::clone(&self.db_conn), proposer, limit).await
+    }
+
+    async fn get_slots_by_fee_recipient(
+        &self,
+        fee_recipient: &str,
+        limit: i64,
+    ) -> AppResult<Vec<SlotData>> {
+        db_ops::get_slots_by_fee_recipient(Arc::clone(&self.db_conn), fee_recipient, limit).await
+    }
+
+    async fn query_slots(
+        &self,
+        query: &SlotQuery,
+        cursor: Option<i64>,
+        limit: i64,
+    ) -> AppResult<Vec<SlotData>> {
+        db_ops::query_slots(Arc::clone(&self.db_conn), query, cursor, limit).await
+    }
+
+    async fn get_slot_by_exec_block_number(
+        &self,
+        exec_block_number: i64,
+    ) -> AppResult<Option<SlotData>> {
+        db_ops::get_slot_by_exec_block_number(Arc::clone(&self.db_conn), exec_block_number).await
+    }
+
+    async fn get_slot_by_exec_block_hash(
+        &self,
+        exec_block_hash: &str,
+    ) -> AppResult<Option<SlotData>> {
+        db_ops::get_slot_by_exec_block_hash(Arc::clone(&self.db_conn), exec_block_hash).await
+    }
+
+    async fn get_slot_by_blockroot(&self, blockroot: &str) -> AppResult<Option<SlotData>> {
+        db_ops::get_slot_by_blockroot(Arc::clone(&self.db_conn), blockroot).await
+    }
+
+    async fn get_latest_unexecuted_slot(&self) -> AppResult<SlotData> {
+        db_ops::get_latest_unexecuted_slot(Arc::clone(&self.db_conn)).await
+    }
+
+    async fn search_graffiti(
+        &self,
+        query: &GraffitiQuery,
+        before_slot: Option<i64>,
+        limit: i64,
+    ) -> AppResult<Vec<GraffitiMatch>> {
+        db_ops::search_graffiti(
+            Arc::clone(&self.db_conn),
+            self.network,
+            query,
+            before_slot,
+            limit,
+        )
+        .await
+    }
+
+    async fn get_blob_sidecar_summary(
+        &self,
+        slot_number: i64,
+    ) -> AppResult<Option<BlobSidecarSummary>> {
+        db_ops::get_blob_sidecar_summary(Arc::clone(&self.db_conn), slot_number).await
+    }
+
+    async fn prune_slots(&self, before_epoch: i64, limit: i64) -> AppResult<u64> {
+        db_ops::prune_slots(Arc::clone(&self.db_conn), before_epoch, limit).await
+    }
+
+    async fn prune_blob_sidecar_summaries(&self, before_epoch: i64, limit: i64) -> AppResult<u64> {
+        db_ops::prune_blob_sidecar_summaries(Arc::clone(&self.db_conn), before_epoch, limit).await
+    }
+
+    async fn reclaim_space(&self, max_pages: i64) -> AppResult<()> {
+        db_ops::reclaim_space(Arc::clone(&self.db_conn), max_pages).await
+    }
+}
+
+#[async_trait]
+impl RollupStore for SqliteStore {
+    async fn get_rollups(
+        &self,
+        granularity: RollupGranularity,
+        from_ts: i64,
+        to_ts: i64,
+    ) -> AppResult<Vec<Rollup>> {
+        db_ops::get_rollups(Arc::clone(&self.db_conn), granularity, from_ts, to_ts).await
+    }
+}
+
+#[async_trait]
+impl HistoryStore for SqliteStore {
+    async fn get_epoch_as_of(&self, epoch_number: i64, as_of: i64) -> AppResult<Option<EpochData>> {
+        db_ops::get_epoch_data_as_of(Arc::clone(&self.db_conn), epoch_number, as_of).await
+    }
+
+    async fn get_slot_as_of(&self, slot_number: i64, as_of: i64) -> AppResult<Option<SlotData>> {
+        db_ops::get_slot_as_of(Arc::clone(&self.db_conn), slot_number, as_of).await
+    }
+
+    async fn get_epoch_history(&self, epoch_number: i64) -> AppResult<Vec<RowVersion<EpochData>>> {
+        db_ops::get_epoch_data_history(Arc::clone(&self.db_conn), epoch_number).await
+    }
+
+    async fn get_slot_history(&self, slot_number: i64) -> AppResult<Vec<RowVersion<SlotData>>> {
+        db_ops::get_slot_history(Arc::clone(&self.db_conn), slot_number).await
+    }
+}
+
+#[async_trait]
+impl ChangeStore for SqliteStore {
+    async fn get_changes(&self, since: i64, limit: i64) -> AppResult<Vec<ChangeEvent>> {
+        db_ops::get_changes(Arc::clone(&self.db_conn), since, limit).await
+    }
+
+    async fn get_sink_cursor(&self, sink: &str) -> AppResult<i64> {
+        db_ops::get_sink_cursor(Arc::clone(&self.db_conn), sink).await
+    }
+
+    async fn set_sink_cursor(&self, sink: &str, seq: i64) -> AppResult<()> {
+        db_ops::set_sink_cursor(Arc::clone(&self.db_conn), sink, seq).await
+    }
+
+    async fn prune_changes(&self, before: i64, limit: i64) -> AppResult<u64> {
+        db_ops::prune_changes(Arc::clone(&self.db_conn), before, limit).await
+    }
+}
@@ -50,17 +50,13 @@ impl From<EpochData> for EpochDataDto {
             depositscount: value.depositscount,
             eligibleether: value.eligibleether,
             epoch: value.epoch,
-            finalized: if value.finalized == 1 { true } else { false },
+            finalized: value.finalized != 0,
             globalparticipationrate: value.globalparticipationrate,
             missedblocks: value.missedblocks,
             orphanedblocks: value.orphanedblocks,
             proposedblocks: value.proposedblocks,
             proposerslashingscount: value.proposerslashingscount,
-            rewards_exported: if value.rewards_exported == 1 {
-                true
-            } else {
-                false
-            },
+            rewards_exported: value.rewards_exported != 0,
             scheduledblocks: value.scheduledblocks,
             totalvalidatorbalance: value.totalvalidatorbalance,
             ts: value.ts,
@@ -76,6 +72,7 @@ impl From<EpochData> for EpochDataDto {
 pub struct SlotDataDto {
     pub attestationscount: i64,
     pub attesterslashingscount: i64,
+    pub blob_kzg_commitments_count: Option<i64>,
     pub blockroot: String,
     pub depositscount: i64,
     pub epoch: i64,
@@ -83,8 +80,10 @@ pub struct SlotDataDto {
     pub eth1data_depositcount: i64,
     pub eth1data_depositroot: Option<String>,
     pub exec_base_fee_per_gas: Option<i64>,
+    pub exec_blob_gas_used: Option<i64>,
     pub exec_block_hash: Option<String>,
     pub exec_block_number: Option<i64>,
+    pub exec_excess_blob_gas: Option<i64>,
     pub exec_extra_data: Option<String>,
     pub exec_fee_recipient: Option<String>,
     pub exec_gas_limit: Option<i64>,
@@ -98,6 +97,7 @@ pub struct SlotDataDto {
     pub exec_transactions_count: i64,
     pub graffiti: Option<String>,
     pub graffiti_text: String,
+    pub parent_beacon_block_root: Option<String>,
     pub parentroot: Option<String>,
     pub proposer: i64,
     pub proposerslashingscount: i64,
@@ -118,6 +118,7 @@ impl From<SlotData> for SlotDataDto {
         SlotDataDto {
             attestationscount: value.attestationscount,
             attesterslashingscount: value.attesterslashingscount,
+            blob_kzg_commitments_count: value.blob_kzg_commitments_count,
             blockroot: value.blockroot,
             depositscount: value.depositscount,
             epoch: value.epoch,
@@ -125,8 +126,10 @@ impl From<SlotData> for SlotDataDto {
             eth1data_depositcount: value.eth1data_depositcount,
             eth1data_depositroot: Some(value.eth1data_depositroot),
             exec_base_fee_per_gas: value.exec_base_fee_per_gas,
+            exec_blob_gas_used: value.exec_blob_gas_used,
             exec_block_hash: value.exec_block_hash,
             exec_block_number: value.exec_block_number,
+            exec_excess_blob_gas: value.exec_excess_blob_gas,
             exec_extra_data: value.exec_extra_data,
             exec_fee_recipient: value.exec_fee_recipient,
             exec_gas_limit: value.exec_gas_limit,
@@ -140,6 +143,7 @@ impl From<SlotData> for SlotDataDto {
             exec_transactions_count: value.exec_transactions_count,
             graffiti: Some(value.graffiti),
             graffiti_text: value.graffiti_text,
+            parent_beacon_block_root: value.parent_beacon_block_root,
             parentroot: Some(value.parentroot),
             proposer: value.proposer,
             proposerslashingscount: value.proposerslashingscount,
@@ -156,3 +160,10 @@ impl From<SlotData> for SlotDataDto {
         }
     }
 }
+
+/// An epoch with its stored slots, oldest slot first.
+#[derive(Debug, Serialize)]
+pub struct EpochWithSlots {
+    pub epoch: EpochDataDto,
+    pub slots: Vec<SlotDataDto>,
+}
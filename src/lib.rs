@@ -0,0 +1,35 @@
+//! Beacon chain epoch and slot data as collected by the `rish` service, for services that want
+//! to read a rish database or run ingestion themselves instead of going through the HTTP API.
+//!
+//! - [`open_store`] opens a SQLite or PostgreSQL database and returns a [`SharedStore`], queried
+//!   through the [`EpochStore`], [`SlotStore`], [`RollupStore`], [`HistoryStore`] and
+//!   [`ChangeStore`] traits. [`open_store_read_only`] leaves the database untouched.
+//! - [`IngestPipeline::spawn`] fetches, validates and persists epochs into a store, the
+//!   [`utils::scheduler`] functions keep it following the chain.
+//! - [`utils::external_api`] is the beacon chain data source client, it reads `API_KEY` from
+//!   the environment.
+//! - [`models`] are the stored rows, [`dtos`] the data source's payloads.
+//! - [`cli`] parses the `rish` command line and holds the options of the diff, export and
+//!   backup functions.
+//!
+//! Every fallible function returns an [`AppResult`]. A row that does not exist is reported as
+//! a [`std::io::Error`] of kind [`NotFound`](std::io::ErrorKind::NotFound).
+//!
+//! The SQL queries are checked at compile time. Without a migrated database in `DATABASE_URL`,
+//! build with `SQLX_OFFLINE=true` to check them against the committed `.sqlx` query data; run
+//! `cargo sqlx prepare` after changing a query.
+
+pub mod cli;
+pub mod db_ops;
+pub mod dtos;
+pub mod models;
+pub mod sinks;
+pub mod utils;
+
+pub use db_ops::{
+    open_store, open_store_read_only, ChangeStore, EpochStore, HistoryStore, NetworkScoped,
+    RollupStore, SharedStore, SlotStore, Store,
+};
+pub use utils::{IngestPipeline, Network};
+
+pub type AppResult<T> = Result<T, Box<dyn std::error::Error + Send + Sync>>;
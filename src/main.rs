@@ -1,14 +1,9 @@
-mod db_ops;
-mod dtos;
-mod models;
-mod utils;
-
 // use dtos::SlotDataDto;
 // use once_cell::sync::OnceCell;
+use rish::{cli, db_ops, dtos, models, sinks, utils, AppResult};
 use salvo::prelude::*;
-use sqlx::{sqlite::SqliteArgumentValue, SqlitePool};
 use std::sync::Arc;
-use tokio::sync::{oneshot, Mutex};
+use tokio::sync::oneshot;
 use utils::scheduler;
 
 // static SQLITE: OnceCell<SqlitePool> = OnceCell::new();
@@ -18,68 +13,547 @@ use utils::scheduler;
 //     unsafe { SQLITE.get_unchecked() }
 // }
 
-pub type AppResult<T> = Result<T, Box<dyn std::error::Error + Send + Sync>>;
+/// Hands the shared store to every handler through the `Depot`, so requests reuse the
+/// application pools instead of connecting on their own.
+struct InjectStore(db_ops::SharedStore);
+
+#[handler]
+impl InjectStore {
+    async fn handle(&self, depot: &mut Depot) {
+        depot.inject(Arc::clone(&self.0));
+    }
+}
+
+fn obtain_store(depot: &Depot) -> &db_ops::SharedStore {
+    depot
+        .obtain::<db_ops::SharedStore>()
+        .expect("store is injected by the router hoop")
+}
+
+fn is_not_found(e: &(dyn std::error::Error + Send + Sync)) -> bool {
+    e.downcast_ref::<std::io::Error>()
+        .is_some_and(|e| e.kind() == std::io::ErrorKind::NotFound)
+}
+
+/// Whether `e` came from requesting or parsing the beacon chain API rather than the store.
+fn is_upstream_error(e: &(dyn std::error::Error + Send + Sync)) -> bool {
+    e.downcast_ref::<reqwest::Error>().is_some() || e.downcast_ref::<serde_json::Error>().is_some()
+}
+
+/// `GET /cache/stats`, hit and miss counters of the network's read-through cache.
+struct CacheStats(Arc<db_ops::CachedStore>);
 
 #[handler]
-async fn get_specific_slot(req: &mut Request, res: &mut Response) {
+impl CacheStats {
+    async fn handle(&self, res: &mut Response) {
+        res.render(Json(self.0.metrics().snapshot()));
+    }
+}
+
+/// Reads the optional `as_of` query parameter, rendering a 400 when it does not parse.
+fn as_of_param(req: &Request, res: &mut Response) -> Result<Option<i64>, ()> {
+    match req.query::<String>("as_of") {
+        None => Ok(None),
+        Some(as_of) => match models::parse_as_of(&as_of) {
+            Ok(as_of) => Ok(Some(as_of)),
+            Err(e) => {
+                res.set_status_code(StatusCode::BAD_REQUEST);
+                res.render(format!("Invalid as_of {as_of}: {e}"));
+                Err(())
+            }
+        },
+    }
+}
+
+#[handler]
+async fn get_specific_slot(req: &mut Request, depot: &mut Depot, res: &mut Response) {
     let slot_number = req.param::<i64>("slot").unwrap();
+    let Ok(as_of) = as_of_param(req, res) else {
+        return;
+    };
+    let store = obtain_store(depot);
+    if let Some(as_of) = as_of {
+        println!("API CALLED: Fetching Slot: {slot_number} as of {as_of} from history.");
+        match store.get_slot_as_of(slot_number, as_of).await.unwrap() {
+            Some(slot) => res.render(Json(slot)),
+            None => res.set_status_code(StatusCode::NOT_FOUND),
+        }
+        return;
+    }
     println!("API CALLED: Fetching Slot: {slot_number} from records.");
-    let slot_dto = db_ops::api_get_specific_slot(
-        Arc::new(Mutex::new(
-            SqlitePool::connect(env!("DATABASE_URL")).await.unwrap(),
-        )),
-        slot_number,
+    render_found(
+        res,
+        db_ops::get_or_fetch_slot(store.as_ref(), slot_number).await,
+    )
+}
+
+/// Renders `result`, or the error with a 404 when it failed with `NotFound`, a 502 when the
+/// beacon chain failed and a 500 otherwise.
+fn render_found<T: serde::Serialize + Send>(res: &mut Response, result: AppResult<T>) {
+    match result {
+        Ok(value) => res.render(Json(value)),
+        Err(e) => {
+            let status = if is_not_found(e.as_ref()) {
+                StatusCode::NOT_FOUND
+            } else if is_upstream_error(e.as_ref()) {
+                StatusCode::BAD_GATEWAY
+            } else {
+                StatusCode::INTERNAL_SERVER_ERROR
+            };
+            println!("API ERROR: {e}");
+            res.set_status_code(status);
+            res.render(e.to_string());
+        }
+    }
+}
+
+#[handler]
+async fn get_specific_epoch(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let epoch_number = req.param::<i64>("epoch").unwrap();
+    println!("API CALLED: Fetching Epoch: {epoch_number} from records.");
+    let store = obtain_store(depot);
+    let epoch_data = db_ops::get_or_fetch_epoch(store.as_ref(), epoch_number).await;
+    render_found(res, epoch_data.map(dtos::EpochDataDto::from))
+}
+
+#[handler]
+async fn get_latest_epoch(depot: &mut Depot, res: &mut Response) {
+    println!("API CALLED: Fetching latest Epoch from records.");
+    let store = obtain_store(depot);
+    let epoch_data = store.get_latest_epoch().await;
+    render_found(res, epoch_data.map(dtos::EpochDataDto::from))
+}
+
+#[handler]
+async fn get_finalized_epoch(depot: &mut Depot, res: &mut Response) {
+    println!("API CALLED: Fetching latest finalized Epoch from records.");
+    let store = obtain_store(depot);
+    let epoch_data = db_ops::get_or_fetch_finalized_epoch(store.as_ref()).await;
+    render_found(res, epoch_data.map(dtos::EpochDataDto::from))
+}
+
+#[handler]
+async fn get_epoch_slots(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let epoch_number = req.param::<i64>("epoch").unwrap();
+    println!("API CALLED: Fetching slots of Epoch: {epoch_number} from records.");
+    let store = obtain_store(depot);
+    render_found(
+        res,
+        db_ops::get_or_fetch_epoch_slots(store.as_ref(), epoch_number).await,
     )
-    .await
-    .unwrap();
-    res.render(Json(slot_dto))
 }
 
 #[handler]
-async fn get_recent_epoch_slots(_req: &mut Request, res: &mut Response) {
-    let epoch_number = db_ops::get_latest_epoch_data(Arc::new(Mutex::new(
-        SqlitePool::connect(env!("DATABASE_URL")).await.unwrap(),
-    )))
-    .await
-    .unwrap()
-    .epoch;
-    let slots = utils::external_api::get_specific_epoch_slots(epoch_number)
+async fn get_slot_history(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let slot_number = req.param::<i64>("slot").unwrap();
+    println!("API CALLED: Fetching history of Slot: {slot_number}.");
+    let store = obtain_store(depot);
+    let versions = store.get_slot_history(slot_number).await.unwrap();
+    res.render(Json(versions))
+}
+
+#[handler]
+async fn get_epoch_history(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let epoch_number = req.param::<i64>("epoch").unwrap();
+    println!("API CALLED: Fetching history of Epoch: {epoch_number}.");
+    let store = obtain_store(depot);
+    let versions = store.get_epoch_history(epoch_number).await.unwrap();
+    res.render(Json(versions))
+}
+
+#[handler]
+async fn get_slot_blob_summary(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let slot_number = req.param::<i64>("slot").unwrap();
+    println!("API CALLED: Fetching blob summary of Slot: {slot_number} from records.");
+    let store = obtain_store(depot);
+    let blob_summary = store.get_blob_sidecar_summary(slot_number).await.unwrap();
+    match blob_summary {
+        Some(blob_summary) => res.render(Json(blob_summary)),
+        None => res.set_status_code(StatusCode::NOT_FOUND),
+    }
+}
+
+const RECENT_DEFAULT_EPOCHS: i64 = 5;
+const RECENT_MAX_EPOCHS: i64 = 100;
+
+/// `GET /recent?epochs=<n>`, the `n` newest stored epochs with their slots, newest first.
+/// `recent_five` is the same with the default count.
+#[handler]
+async fn get_recent_epoch_slots(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let count = req
+        .query::<i64>("epochs")
+        .unwrap_or(RECENT_DEFAULT_EPOCHS)
+        .clamp(1, RECENT_MAX_EPOCHS);
+    println!("API CALLED: Fetching the {count} most recent epochs from records.");
+    let store = obtain_store(depot);
+    let recent = db_ops::get_recent_epochs(store.as_ref(), count)
+        .await
+        .unwrap();
+    res.render(Json(recent))
+}
+
+/// `GET /rollups/<hourly|daily>?from=<unix>&to=<unix>`, defaulting to the last day of hourly
+/// or the last 30 days of daily buckets.
+#[handler]
+async fn get_rollups(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let granularity = match req
+        .param::<String>("granularity")
+        .unwrap()
+        .parse::<models::RollupGranularity>()
+    {
+        Ok(granularity) => granularity,
+        Err(message) => {
+            res.set_status_code(StatusCode::BAD_REQUEST);
+            res.render(message);
+            return;
+        }
+    };
+    let to_ts = req
+        .query::<i64>("to")
+        .unwrap_or_else(|| chrono::Utc::now().timestamp());
+    let from_ts = req
+        .query::<i64>("from")
+        .unwrap_or_else(|| match granularity {
+            models::RollupGranularity::Hourly => to_ts - utils::SECONDS_PER_DAY,
+            models::RollupGranularity::Daily => to_ts - 30 * utils::SECONDS_PER_DAY,
+        });
+    println!("API CALLED: Fetching {granularity:?} rollups from {from_ts} to {to_ts}.");
+    let store = obtain_store(depot);
+    let rollups = store
+        .get_rollups(granularity, from_ts, to_ts)
         .await
         .unwrap();
-    res.render(Json(slots))
+    let summaries: Vec<models::RollupSummary> =
+        rollups.iter().map(models::RollupSummary::from).collect();
+    res.render(Json(summaries))
+}
+
+const GRAFFITI_SEARCH_DEFAULT_LIMIT: i64 = 50;
+const GRAFFITI_SEARCH_MAX_LIMIT: i64 = 500;
+
+/// `GET /graffiti/search?q=<query>&limit=<n>&before=<slot>`, see `models::GraffitiQuery` for
+/// the query syntax.
+#[handler]
+async fn search_graffiti(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let query = match models::GraffitiQuery::parse(&req.query::<String>("q").unwrap_or_default()) {
+        Ok(query) => query,
+        Err(e) => {
+            res.set_status_code(StatusCode::BAD_REQUEST);
+            res.render(e.to_string());
+            return;
+        }
+    };
+    let limit = req
+        .query::<i64>("limit")
+        .unwrap_or(GRAFFITI_SEARCH_DEFAULT_LIMIT)
+        .clamp(1, GRAFFITI_SEARCH_MAX_LIMIT);
+    let before = req.query::<i64>("before");
+    println!("API CALLED: Searching graffiti for {query:?} before {before:?}.");
+    let store = obtain_store(depot);
+    let results = store.search_graffiti(&query, before, limit).await.unwrap();
+    let next_before = match results.last() {
+        Some(last) if results.len() as i64 == limit => Some(last.slot),
+        _ => None,
+    };
+    res.render(Json(models::GraffitiSearchPage {
+        results,
+        next_before,
+    }))
+}
+
+const SLOTS_DEFAULT_LIMIT: i64 = 100;
+const SLOTS_MAX_LIMIT: i64 = 1000;
+
+/// Reads the filters and the cursor of `GET /slots`, `None` for absent ones.
+fn slot_query_params(req: &Request) -> Result<(models::SlotQuery, Option<i64>), String> {
+    let number = |name: &str| -> Result<Option<i64>, String> {
+        req.query::<String>(name)
+            .map(|value| {
+                value
+                    .parse::<i64>()
+                    .map_err(|e| format!("Invalid {name} {value}: {e}"))
+            })
+            .transpose()
+    };
+    let order = match req.query::<String>("sort") {
+        Some(sort) => sort.parse::<models::SlotOrder>()?,
+        None => models::SlotOrder::default(),
+    };
+    let fee_recipient = match req.query::<String>("fee_recipient") {
+        Some(fee_recipient) => {
+            let bytes = models::hex_to_bytes(&fee_recipient)
+                .map_err(|e| format!("Invalid fee_recipient {fee_recipient}: {e}"))?;
+            Some(models::bytes_to_hex(&bytes))
+        }
+        None => None,
+    };
+
+    let query = models::SlotQuery {
+        from_slot: number("from")?,
+        to_slot: number("to")?,
+        epoch: number("epoch")?,
+        proposer: number("proposer")?,
+        status: req.query::<String>("status"),
+        fee_recipient,
+        order,
+    };
+
+    Ok((query, number("cursor")?))
+}
+
+/// `GET /slots?from=&to=&epoch=&proposer=&status=&fee_recipient=&sort=<asc|desc>&limit=&cursor=`,
+/// stored slots matching every given filter, oldest first unless `sort=desc`. Pass
+/// `next_cursor` as `cursor` for the next page.
+#[handler]
+async fn get_slots(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let (query, cursor) = match slot_query_params(req) {
+        Ok(params) => params,
+        Err(message) => {
+            res.set_status_code(StatusCode::BAD_REQUEST);
+            res.render(message);
+            return;
+        }
+    };
+    let limit = req
+        .query::<i64>("limit")
+        .unwrap_or(SLOTS_DEFAULT_LIMIT)
+        .clamp(1, SLOTS_MAX_LIMIT);
+    println!("API CALLED: Fetching slots matching {query:?} after {cursor:?}.");
+    let store = obtain_store(depot);
+    let slots = store.query_slots(&query, cursor, limit).await.unwrap();
+    let next_cursor = match slots.last() {
+        Some(last) if slots.len() as i64 == limit => Some(last.slot),
+        _ => None,
+    };
+    res.render(Json(models::SlotPage { slots, next_cursor }))
+}
+
+const CHANGES_DEFAULT_LIMIT: i64 = 100;
+const CHANGES_MAX_LIMIT: i64 = 1000;
+
+/// `GET /changes?since=<seq>&limit=<n>`, the change outbox after the `since` cursor. Consumers
+/// store `next_since` and pass it back to resume without missing an event.
+#[handler]
+async fn get_changes(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let since = req.query::<i64>("since").unwrap_or(0);
+    let limit = req
+        .query::<i64>("limit")
+        .unwrap_or(CHANGES_DEFAULT_LIMIT)
+        .clamp(1, CHANGES_MAX_LIMIT);
+    println!("API CALLED: Fetching changes since {since}.");
+    let store = obtain_store(depot);
+    let changes = store.get_changes(since, limit).await.unwrap();
+    let next_since = changes.last().map_or(since, |change| change.seq);
+    res.render(Json(models::ChangePage {
+        changes,
+        next_since,
+    }))
+}
+
+/// Routes of one network's API, served through `store`.
+fn api_routes(store: db_ops::SharedStore, cache: Option<Arc<db_ops::CachedStore>>) -> Router {
+    let router = Router::new()
+        .hoop(InjectStore(store))
+        .push(Router::with_path("slots").get(get_slots))
+        .push(Router::with_path("slot/<slot>").get(get_specific_slot))
+        .push(Router::with_path("slot/<slot>/blobs").get(get_slot_blob_summary))
+        .push(Router::with_path("slot/<slot>/history").get(get_slot_history))
+        // The named epochs go first, `<epoch>` would match them as well.
+        .push(Router::with_path("epoch/latest").get(get_latest_epoch))
+        .push(Router::with_path("epoch/finalized").get(get_finalized_epoch))
+        .push(Router::with_path("epoch/<epoch>").get(get_specific_epoch))
+        .push(Router::with_path("epoch/<epoch>/slots").get(get_epoch_slots))
+        .push(Router::with_path("epoch/<epoch>/history").get(get_epoch_history))
+        .push(Router::with_path("recent").get(get_recent_epoch_slots))
+        .push(Router::with_path("recent_five").get(get_recent_epoch_slots))
+        .push(Router::with_path("rollups/<granularity>").get(get_rollups))
+        .push(Router::with_path("graffiti/search").get(search_graffiti))
+        .push(Router::with_path("changes").get(get_changes));
+
+    match cache {
+        Some(cache) => router.push(Router::with_path("cache/stats").get(CacheStats(cache))),
+        None => router,
+    }
+}
+
+async fn open_store(
+    network: utils::Network,
+    db_url: &str,
+    command: &cli::Command,
+    change_source: models::ChangeSource,
+) -> AppResult<db_ops::SharedStore> {
+    if db_ops::is_postgres_url(db_url) {
+        println!("Using the PostgreSQL database for {network}");
+    }
+
+    // Diff and export must not write anything, not even migrations or the default rows.
+    if matches!(command, cli::Command::Diff(..) | cli::Command::Export(..)) {
+        db_ops::open_store_read_only(db_url, network).await
+    } else {
+        db_ops::open_store(db_url, network, change_source).await
+    }
 }
 
 #[tokio::main]
 async fn main() -> AppResult<()> {
     tracing_subscriber::fmt().init();
-    let db_url = env!("DATABASE_URL");
-    let pool = SqlitePool::connect(db_url).await?;
-    let db_pool = Arc::new(Mutex::new(pool));
-    let db_pool_thread_1 = db_pool.clone();
-    let db_pool_thread_2 = db_pool.clone();
-    // let db_pool_thread_3 = db_pool.clone();
+    let networks = utils::Network::from_env()?;
+    let primary = networks[0];
+    let db_url = primary.database_url(true);
+    let command = cli::parse_args(std::env::args())?;
+
+    match &command {
+        cli::Command::MigrateOnly => return db_ops::migrate_only(&db_url, primary).await,
+        cli::Command::MigrateDown(version) => return db_ops::migrate_down(&db_url, *version).await,
+        cli::Command::Backup(path, compression) => {
+            let manifest = db_ops::backup_database(&db_url, path, *compression).await?;
+            println!("{}", serde_json::to_string_pretty(&manifest)?);
+            return Ok(());
+        }
+        cli::Command::Restore(path) => {
+            db_ops::restore_database(&db_url, path).await?;
+            return Ok(());
+        }
+        _ => (),
+    }
+
+    // Recorded with every row version written by this process.
+    let change_source = match &command {
+        cli::Command::Import(_) => models::ChangeSource::Import,
+        cli::Command::Verify(_) => models::ChangeSource::Repair,
+        _ => models::ChangeSource::BeaconChain,
+    };
+
+    let store = open_store(primary, &db_url, &command, change_source).await?;
+
+    if let cli::Command::Diff(range, output) = &command {
+        let report = utils::diff::diff_range(Arc::clone(&store), *range).await?;
+        let report = serde_json::to_string_pretty(&report)?;
+        match output {
+            Some(path) => tokio::fs::write(path, report).await?,
+            None => println!("{report}"),
+        }
+        return Ok(());
+    }
 
-    db_ops::setup_db(db_url, Arc::clone(&db_pool.to_owned())).await?;
+    if let cli::Command::Verify(args) = &command {
+        let (from, to) = args.epochs;
+        let mut report = utils::integrity::verify_range(Arc::clone(&store), from, to).await?;
+        if args.repair {
+            let pipeline = utils::IngestPipeline::spawn(Arc::clone(&store));
+            utils::integrity::reingest_bad_epochs(&pipeline, &mut report).await?;
+        }
+        let report = serde_json::to_string_pretty(&report)?;
+        match &args.output {
+            Some(path) => tokio::fs::write(path, report).await?,
+            None => println!("{report}"),
+        }
+        return Ok(());
+    }
+
+    if let cli::Command::Export(output_dir, mode) = &command {
+        let report =
+            utils::parquet_export::export_parquet(Arc::clone(&store), output_dir, *mode).await?;
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
 
-    utils::fetch_recent_epoch_slots(Arc::clone(&db_pool), 1).await?;
+    if let cli::Command::Import(paths) = &command {
+        let report = utils::importer::import_paths(Arc::clone(&store), paths).await?;
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
 
-    println!("Starting scheduler for fetching new epoch data");
-    let task1 =
-        tokio::spawn(async move { scheduler::fetch_latest_epoch(db_pool_thread_1) }).await?;
+    let retention_policy = utils::RetentionPolicy::from_env()?;
+    let sink_settings = sinks::SinkSettings::from_env()?;
 
-    println!("Starting scheduler for updating the current epoch");
-    let task2 =
-        tokio::spawn(async move { scheduler::update_current_epoch_and_slots(db_pool_thread_2) })
-            .await?;
+    let mut stores = vec![(primary, store)];
+    for network in &networks[1..] {
+        let db_url = network.database_url(false);
+        stores.push((
+            *network,
+            open_store(*network, &db_url, &command, change_source).await?,
+        ));
+    }
+
+    // The schedulers write through the cache as well, so their writes invalidate it.
+    let mut caches = Vec::new();
+    for (network, store) in &mut stores {
+        let cache_settings = db_ops::CacheSettings::from_env(*network)?;
+        if cache_settings.capacity == 0 {
+            caches.push(None);
+            continue;
+        }
+        let cache = Arc::new(db_ops::CachedStore::new(Arc::clone(store), cache_settings));
+        *store = Arc::clone(&cache) as db_ops::SharedStore;
+        caches.push(Some(cache));
+    }
 
-    // println!("Starting the scheduler for updating the unexecuted slot");
-    // let task3 =
-    //     tokio::spawn(async move { scheduler::update_unexecuted_slot(db_pool_thread_3) }).await?;
+    let mut tasks = tokio::task::JoinSet::new();
+    for (network, store) in &stores {
+        let network = *network;
+        let pipeline = utils::IngestPipeline::spawn(Arc::clone(store));
 
+        utils::fetch_recent_epoch_slots(&pipeline, 1).await?;
+
+        println!("Starting scheduler for fetching new {network} epoch data");
+        let pipeline_thread_1 = pipeline.clone();
+        tasks.spawn(async move {
+            (
+                network.name,
+                1,
+                scheduler::fetch_latest_epoch(pipeline_thread_1).await,
+            )
+        });
+
+        println!("Starting scheduler for updating the current {network} epoch");
+        let store_thread_2 = Arc::clone(store);
+        tasks.spawn(async move {
+            (
+                network.name,
+                2,
+                scheduler::update_current_epoch_and_slots(store_thread_2, pipeline).await,
+            )
+        });
+
+        println!("Starting scheduler for pruning {network} data past its retention");
+        let store_thread_3 = Arc::clone(store);
+        let retention_policy = retention_policy.clone();
+        tasks.spawn(async move {
+            (
+                network.name,
+                3,
+                scheduler::prune_expired_data(store_thread_3, retention_policy).await,
+            )
+        });
+
+        for sink in sinks::sinks_from_env(network).await? {
+            println!(
+                "Starting relay of {network} changes to the {} sink",
+                sink.name()
+            );
+            let store_thread_4 = Arc::clone(store);
+            let sink_settings = sink_settings.clone();
+            tasks.spawn(async move {
+                (
+                    network.name,
+                    4,
+                    sinks::relay_changes(store_thread_4, sink, sink_settings).await,
+                )
+            });
+        }
+    }
+
+    // The primary network keeps the unprefixed routes, every network is also served under
+    // `/<network>/...`.
     let (tx, rx) = oneshot::channel();
-    let router = Router::new()
-        .push(Router::with_path("slot/<slot>").get(get_specific_slot))
-        .push(Router::with_path("recent_five").get(get_recent_epoch_slots));
+    let mut router = Router::new();
+    for ((network, store), cache) in stores.iter().zip(&caches) {
+        router = router.push(
+            Router::with_path(network.name).push(api_routes(Arc::clone(store), cache.clone())),
+        );
+    }
+    let router = router.push(api_routes(Arc::clone(&stores[0].1), caches[0].clone()));
     let acceptor = TcpListener::new("127.0.0.1:5800").bind().await;
 
     let server = Server::new(acceptor).serve_with_graceful_shutdown(
@@ -92,23 +566,18 @@ async fn main() -> AppResult<()> {
 
     tokio::spawn(server);
 
-    let (result1, result2) = tokio::join!(task1, task2);
-
-    match result1 {
-        Ok(_) => println!("Thread 1 executed successfully"),
-        Err(e) => eprintln!("Error in Thread 1 : {e}"),
+    while let Some(result) = tasks.join_next().await {
+        match result {
+            Ok((network, thread, Ok(_))) => {
+                println!("Thread {thread} of {network} executed successfully")
+            }
+            Ok((network, thread, Err(e))) => {
+                eprintln!("Error in Thread {thread} of {network} : {e}")
+            }
+            Err(e) => eprintln!("Error in a scheduler thread : {e}"),
+        }
     }
 
-    match result2 {
-        Ok(_) => println!("Thread 2 executed successfully"),
-        Err(e) => eprintln!("Error in Thread 2 : {e}"),
-    }
-
-    // match result3 {
-    //     Ok(_) => println!("Thread 3 executed successfully"),
-    //     Err(e) => eprintln!("Error in Thread 3 : {e}"),
-    // }
-
     let _ = tx.send(());
 
     Ok(())
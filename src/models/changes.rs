@@ -0,0 +1,103 @@
+use serde::Serialize;
+use serde_json::Value;
+
+use super::{millis_to_rfc3339, EpochData, SlotData};
+
+/// Slot `status` of a block that was proposed but is no longer canonical.
+const STATUS_ORPHANED: &str = "3";
+
+/// Table a change happened in, stored in the `entity` column of `change_outbox`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeEntity {
+    Epoch,
+    Slot,
+}
+
+impl ChangeEntity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChangeEntity::Epoch => "epoch",
+            ChangeEntity::Slot => "slot",
+        }
+    }
+}
+
+/// What happened to the row, stored in the `kind` column of `change_outbox`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Inserted,
+    Updated,
+    /// The slot's block was replaced or orphaned.
+    Reorged,
+    /// The epoch became finalized.
+    Finalized,
+}
+
+impl ChangeKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChangeKind::Inserted => "inserted",
+            ChangeKind::Updated => "updated",
+            ChangeKind::Reorged => "reorged",
+            ChangeKind::Finalized => "finalized",
+        }
+    }
+
+    pub fn of_epoch_update(old: &EpochData, new: &EpochData) -> Self {
+        if old.finalized == 0 && new.finalized != 0 {
+            ChangeKind::Finalized
+        } else {
+            ChangeKind::Updated
+        }
+    }
+
+    pub fn of_slot_update(old: &SlotData, new: &SlotData) -> Self {
+        let orphaned = old.status != STATUS_ORPHANED && new.status == STATUS_ORPHANED;
+        if orphaned || old.blockroot != new.blockroot {
+            ChangeKind::Reorged
+        } else {
+            ChangeKind::Updated
+        }
+    }
+}
+
+/// One row of `change_outbox`. Events are written in the same transaction as the change they
+/// describe and `seq` grows in commit order, so reading `seq > cursor` never skips an event.
+#[derive(Debug, Clone, Serialize)]
+pub struct ChangeEvent {
+    pub seq: i64,
+    pub recorded_at: String,
+    pub entity: String,
+    /// Epoch or slot number.
+    pub key: i64,
+    pub kind: String,
+    /// The row as written, serialized like the API serves it.
+    pub payload: Value,
+}
+
+impl ChangeEvent {
+    pub fn new(
+        seq: i64,
+        recorded_at_millis: i64,
+        entity: String,
+        key: i64,
+        kind: String,
+        payload: &str,
+    ) -> Self {
+        ChangeEvent {
+            seq,
+            recorded_at: millis_to_rfc3339(recorded_at_millis),
+            entity,
+            key,
+            kind,
+            payload: serde_json::from_str(payload).unwrap_or(Value::Null),
+        }
+    }
+}
+
+/// A page of events in `seq` order. Pass `next_since` as `since` to continue.
+#[derive(Debug, Serialize)]
+pub struct ChangePage {
+    pub changes: Vec<ChangeEvent>,
+    pub next_since: i64,
+}
@@ -0,0 +1,25 @@
+use crate::AppResult;
+
+/// Decodes a `0x` prefixed hex string into raw bytes.
+pub fn hex_to_bytes(value: &str) -> AppResult<Vec<u8>> {
+    Ok(hex::decode(value.trim_start_matches("0x"))?)
+}
+
+/// Whether two hex strings encode the same bytes, regardless of case and `0x` prefix.
+pub fn hex_eq(a: &str, b: &str) -> bool {
+    a.trim_start_matches("0x")
+        .eq_ignore_ascii_case(b.trim_start_matches("0x"))
+}
+
+/// Encodes raw bytes as a `0x` prefixed lowercase hex string.
+pub fn bytes_to_hex(bytes: &[u8]) -> String {
+    format!("0x{}", hex::encode(bytes))
+}
+
+pub fn optional_hex_to_bytes(value: &Option<String>) -> AppResult<Option<Vec<u8>>> {
+    value.as_deref().map(hex_to_bytes).transpose()
+}
+
+pub fn optional_bytes_to_hex(bytes: Option<Vec<u8>>) -> Option<String> {
+    bytes.as_deref().map(bytes_to_hex)
+}
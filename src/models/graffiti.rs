@@ -0,0 +1,173 @@
+use chrono::{NaiveDateTime, SecondsFormat, TimeZone, Utc};
+use serde::Serialize;
+
+use crate::{utils::Network, AppResult};
+
+/// One search term. Every term is a phrase of one or more words, as the FTS5 `unicode61`
+/// tokenizer splits `Lighthouse/v4.3.0` into `lighthouse v4 3 0`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraffitiTerm {
+    pub words: Vec<String>,
+    /// The last word only has to be a prefix, written as a trailing `*`.
+    pub prefix: bool,
+}
+
+/// A graffiti search parsed from the `q` parameter: whitespace separated words or
+/// `"quoted phrases"`, each optionally ending in `*` for a prefix match. All terms must match.
+///
+/// Parsing it ourselves instead of passing `q` through keeps FTS5 operators out of user input
+/// and lets every store backend answer the same query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraffitiQuery {
+    pub terms: Vec<GraffitiTerm>,
+}
+
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+impl GraffitiQuery {
+    pub fn parse(query: &str) -> AppResult<Self> {
+        let mut terms = Vec::new();
+        let mut rest = query.trim_start();
+
+        while !rest.is_empty() {
+            let (text, after) = match rest.strip_prefix('"') {
+                Some(quoted) => {
+                    let end = quoted
+                        .find('"')
+                        .ok_or_else(|| format!("Unterminated phrase in graffiti query {query}"))?;
+                    (&quoted[..end], &quoted[end + 1..])
+                }
+                None => {
+                    let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+                    (&rest[..end], &rest[end..])
+                }
+            };
+            let (text, prefix, after) = match (text.strip_suffix('*'), after.strip_prefix('*')) {
+                (Some(text), _) => (text, true, after),
+                (None, Some(after)) => (text, true, after),
+                (None, None) => (text, false, after),
+            };
+
+            let words = words(text);
+            if !words.is_empty() {
+                terms.push(GraffitiTerm { words, prefix });
+            }
+            rest = after.trim_start();
+        }
+
+        if terms.is_empty() {
+            return Err(format!("Graffiti query {query:?} has no words to search for").into());
+        }
+
+        Ok(GraffitiQuery { terms })
+    }
+
+    /// FTS5 `MATCH` expression, every term a quoted phrase.
+    pub fn to_fts5(&self) -> String {
+        self.terms
+            .iter()
+            .map(|term| {
+                let phrase = format!("\"{}\"", term.words.join(" "));
+                if term.prefix {
+                    format!("{phrase} *")
+                } else {
+                    phrase
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// PostgreSQL `to_tsquery('simple', ...)` expression.
+    pub fn to_tsquery(&self) -> String {
+        self.terms
+            .iter()
+            .map(|term| {
+                let last = term.words.len() - 1;
+                let words: Vec<String> = term
+                    .words
+                    .iter()
+                    .enumerate()
+                    .map(|(index, word)| {
+                        if term.prefix && index == last {
+                            format!("'{word}':*")
+                        } else {
+                            format!("'{word}'")
+                        }
+                    })
+                    .collect();
+                format!("({})", words.join(" <-> "))
+            })
+            .collect::<Vec<_>>()
+            .join(" & ")
+    }
+
+    /// Whether `graffiti_text` matches, tokenized the way FTS5 does. Used by the memory store.
+    pub fn matches(&self, graffiti_text: &str) -> bool {
+        let text = words(graffiti_text);
+
+        self.terms.iter().all(|term| {
+            text.windows(term.words.len()).any(|window| {
+                window
+                    .iter()
+                    .zip(&term.words)
+                    .enumerate()
+                    .all(|(index, (word, wanted))| {
+                        if term.prefix && index == term.words.len() - 1 {
+                            word.starts_with(wanted.as_str())
+                        } else {
+                            word == wanted
+                        }
+                    })
+            })
+        })
+    }
+}
+
+/// A slot whose graffiti matched a search.
+#[derive(Debug, Clone, Serialize)]
+pub struct GraffitiMatch {
+    pub slot: i64,
+    pub epoch: i64,
+    pub proposer: i64,
+    pub graffiti_text: String,
+    /// Start of the slot, RFC 3339.
+    pub timestamp: String,
+}
+
+impl GraffitiMatch {
+    pub fn new(
+        network: Network,
+        slot: i64,
+        epoch: i64,
+        proposer: i64,
+        graffiti_text: String,
+    ) -> Self {
+        let timestamp = NaiveDateTime::from_timestamp_opt(network.slot_timestamp(slot), 0)
+            .map(|timestamp| {
+                Utc.from_utc_datetime(&timestamp)
+                    .to_rfc3339_opts(SecondsFormat::Secs, true)
+            })
+            .unwrap_or_default();
+
+        GraffitiMatch {
+            slot,
+            epoch,
+            proposer,
+            graffiti_text,
+            timestamp,
+        }
+    }
+}
+
+/// A page of search results, newest slot first. Pass `next_before` as `before` to continue.
+#[derive(Debug, Serialize)]
+pub struct GraffitiSearchPage {
+    pub results: Vec<GraffitiMatch>,
+    pub next_before: Option<i64>,
+}
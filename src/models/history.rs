@@ -0,0 +1,74 @@
+use chrono::{DateTime, NaiveDateTime, SecondsFormat, TimeZone, Utc};
+use serde::Serialize;
+
+use crate::AppResult;
+
+/// Where a recorded version of an epoch or slot came from, stored in the `source` column of
+/// `epoch_data_history` and `slot_data_history`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ChangeSource {
+    /// Fetched from the beacon chain API by the schedulers or on an API cache miss.
+    #[default]
+    BeaconChain,
+    /// Loaded by `rish import`.
+    Import,
+    /// Re-ingested by `rish verify --repair`.
+    Repair,
+    /// Placeholder rows written by `setup_db` on an empty database.
+    Seed,
+}
+
+impl ChangeSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChangeSource::BeaconChain => "beaconchain",
+            ChangeSource::Import => "import",
+            ChangeSource::Repair => "repair",
+            ChangeSource::Seed => "seed",
+        }
+    }
+}
+
+/// One stored version of a row, `recorded_at` being when rish ingested it.
+#[derive(Debug, Clone, Serialize)]
+pub struct RowVersion<T> {
+    pub recorded_at: String,
+    pub source: String,
+    pub data: T,
+}
+
+impl<T> RowVersion<T> {
+    pub fn new(recorded_at_millis: i64, source: String, data: T) -> Self {
+        RowVersion {
+            recorded_at: millis_to_rfc3339(recorded_at_millis),
+            source,
+            data,
+        }
+    }
+}
+
+/// Current time in unix milliseconds, the resolution of `recorded_at`.
+pub fn now_millis() -> i64 {
+    Utc::now().timestamp_millis()
+}
+
+pub fn millis_to_rfc3339(millis: i64) -> String {
+    NaiveDateTime::from_timestamp_millis(millis)
+        .map(|recorded_at| {
+            Utc.from_utc_datetime(&recorded_at)
+                .to_rfc3339_opts(SecondsFormat::Millis, true)
+        })
+        .unwrap_or_default()
+}
+
+/// Parses an `as_of` query parameter, either an RFC 3339 timestamp or unix seconds, into unix
+/// milliseconds.
+pub fn parse_as_of(value: &str) -> AppResult<i64> {
+    if let Ok(seconds) = value.parse::<i64>() {
+        return Ok(seconds
+            .checked_mul(1000)
+            .ok_or_else(|| format!("{seconds} seconds is out of range"))?);
+    }
+
+    Ok(DateTime::parse_from_rfc3339(value)?.timestamp_millis())
+}
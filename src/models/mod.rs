@@ -1,3 +1,15 @@
+pub mod changes;
+pub mod encoding;
+pub mod graffiti;
+pub mod history;
 pub mod models;
+pub mod rollups;
+pub mod slot_query;
 
+pub use changes::*;
+pub use encoding::*;
+pub use graffiti::*;
+pub use history::*;
 pub use models::*;
+pub use rollups::*;
+pub use slot_query::*;
@@ -1,8 +1,10 @@
 use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
 
+use super::encoding::{bytes_to_hex, hex_to_bytes, optional_bytes_to_hex, optional_hex_to_bytes};
 use crate::dtos::{EpochDataDto, SlotDataDto};
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 pub struct EpochData {
     pub attestationscount: i64,
     pub attesterslashingscount: i64,
@@ -55,10 +57,11 @@ impl From<EpochDataDto> for EpochData {
     }
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 pub struct SlotData {
     pub attestationscount: i64,
     pub attesterslashingscount: i64,
+    pub blob_kzg_commitments_count: Option<i64>,
     pub blockroot: String,
     pub depositscount: i64,
     pub epoch: i64,
@@ -66,8 +69,10 @@ pub struct SlotData {
     pub eth1data_depositcount: i64,
     pub eth1data_depositroot: String,
     pub exec_base_fee_per_gas: Option<i64>,
+    pub exec_blob_gas_used: Option<i64>,
     pub exec_block_hash: Option<String>,
     pub exec_block_number: Option<i64>,
+    pub exec_excess_blob_gas: Option<i64>,
     pub exec_extra_data: Option<String>,
     pub exec_fee_recipient: Option<String>,
     pub exec_gas_limit: Option<i64>,
@@ -81,6 +86,7 @@ pub struct SlotData {
     pub exec_transactions_count: i64,
     pub graffiti: String,
     pub graffiti_text: String,
+    pub parent_beacon_block_root: Option<String>,
     pub parentroot: String,
     pub proposer: i64,
     pub proposerslashingscount: i64,
@@ -101,6 +107,7 @@ impl From<SlotDataDto> for SlotData {
         SlotData {
             attestationscount: value.attestationscount,
             attesterslashingscount: value.attesterslashingscount,
+            blob_kzg_commitments_count: value.blob_kzg_commitments_count,
             blockroot: value.blockroot,
             depositscount: value.depositscount,
             epoch: value.epoch,
@@ -108,8 +115,10 @@ impl From<SlotDataDto> for SlotData {
             eth1data_depositcount: value.eth1data_depositcount,
             eth1data_depositroot: value.eth1data_depositroot.unwrap_or_default(),
             exec_base_fee_per_gas: value.exec_base_fee_per_gas,
+            exec_blob_gas_used: value.exec_blob_gas_used,
             exec_block_hash: value.exec_block_hash,
             exec_block_number: value.exec_block_number,
+            exec_excess_blob_gas: value.exec_excess_blob_gas,
             exec_extra_data: value.exec_extra_data,
             exec_fee_recipient: value.exec_fee_recipient,
             exec_gas_limit: value.exec_gas_limit,
@@ -123,6 +132,7 @@ impl From<SlotDataDto> for SlotData {
             exec_transactions_count: value.exec_transactions_count,
             graffiti: value.graffiti.unwrap_or_default(),
             graffiti_text: value.graffiti_text,
+            parent_beacon_block_root: value.parent_beacon_block_root,
             parentroot: value.parentroot.unwrap_or_default(),
             proposer: value.proposer,
             proposerslashingscount: value.proposerslashingscount,
@@ -139,3 +149,182 @@ impl From<SlotDataDto> for SlotData {
         }
     }
 }
+
+/// `slot_data` as stored: roots, hashes, signatures and other hex fields are kept as raw bytes,
+/// half the size of their hex text. [`SlotData`] carries them `0x` prefixed for the API.
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct SlotDataRow {
+    pub attestationscount: i64,
+    pub attesterslashingscount: i64,
+    pub blob_kzg_commitments_count: Option<i64>,
+    pub blockroot: Vec<u8>,
+    pub depositscount: i64,
+    pub epoch: i64,
+    pub eth1data_blockhash: Vec<u8>,
+    pub eth1data_depositcount: i64,
+    pub eth1data_depositroot: Vec<u8>,
+    pub exec_base_fee_per_gas: Option<i64>,
+    pub exec_blob_gas_used: Option<i64>,
+    pub exec_block_hash: Option<Vec<u8>>,
+    pub exec_block_number: Option<i64>,
+    pub exec_excess_blob_gas: Option<i64>,
+    pub exec_extra_data: Option<Vec<u8>>,
+    pub exec_fee_recipient: Option<Vec<u8>>,
+    pub exec_gas_limit: Option<i64>,
+    pub exec_gas_used: Option<i64>,
+    pub exec_logs_bloom: Option<Vec<u8>>,
+    pub exec_parent_hash: Option<Vec<u8>>,
+    pub exec_random: Option<Vec<u8>>,
+    pub exec_receipts_root: Option<Vec<u8>>,
+    pub exec_state_root: Option<Vec<u8>>,
+    pub exec_timestamp: Option<i64>,
+    pub exec_transactions_count: i64,
+    pub graffiti: Vec<u8>,
+    pub graffiti_text: String,
+    pub parent_beacon_block_root: Option<Vec<u8>>,
+    pub parentroot: Vec<u8>,
+    pub proposer: i64,
+    pub proposerslashingscount: i64,
+    pub randaoreveal: Vec<u8>,
+    pub signature: Vec<u8>,
+    pub slot: i64,
+    pub stateroot: Vec<u8>,
+    pub status: String,
+    pub syncaggregate_bits: Option<Vec<u8>>,
+    pub syncaggregate_participation: f64,
+    pub syncaggregate_signature: Option<Vec<u8>>,
+    pub voluntaryexitscount: i64,
+    pub withdrawalcount: i64,
+}
+
+impl From<SlotDataRow> for SlotData {
+    fn from(value: SlotDataRow) -> Self {
+        SlotData {
+            attestationscount: value.attestationscount,
+            attesterslashingscount: value.attesterslashingscount,
+            blob_kzg_commitments_count: value.blob_kzg_commitments_count,
+            blockroot: bytes_to_hex(&value.blockroot),
+            depositscount: value.depositscount,
+            epoch: value.epoch,
+            eth1data_blockhash: bytes_to_hex(&value.eth1data_blockhash),
+            eth1data_depositcount: value.eth1data_depositcount,
+            eth1data_depositroot: bytes_to_hex(&value.eth1data_depositroot),
+            exec_base_fee_per_gas: value.exec_base_fee_per_gas,
+            exec_blob_gas_used: value.exec_blob_gas_used,
+            exec_block_hash: optional_bytes_to_hex(value.exec_block_hash),
+            exec_block_number: value.exec_block_number,
+            exec_excess_blob_gas: value.exec_excess_blob_gas,
+            exec_extra_data: optional_bytes_to_hex(value.exec_extra_data),
+            exec_fee_recipient: optional_bytes_to_hex(value.exec_fee_recipient),
+            exec_gas_limit: value.exec_gas_limit,
+            exec_gas_used: value.exec_gas_used,
+            exec_logs_bloom: optional_bytes_to_hex(value.exec_logs_bloom),
+            exec_parent_hash: optional_bytes_to_hex(value.exec_parent_hash),
+            exec_random: optional_bytes_to_hex(value.exec_random),
+            exec_receipts_root: optional_bytes_to_hex(value.exec_receipts_root),
+            exec_state_root: optional_bytes_to_hex(value.exec_state_root),
+            exec_timestamp: value.exec_timestamp,
+            exec_transactions_count: value.exec_transactions_count,
+            graffiti: bytes_to_hex(&value.graffiti),
+            graffiti_text: value.graffiti_text,
+            parent_beacon_block_root: optional_bytes_to_hex(value.parent_beacon_block_root),
+            parentroot: bytes_to_hex(&value.parentroot),
+            proposer: value.proposer,
+            proposerslashingscount: value.proposerslashingscount,
+            randaoreveal: bytes_to_hex(&value.randaoreveal),
+            signature: bytes_to_hex(&value.signature),
+            slot: value.slot,
+            stateroot: bytes_to_hex(&value.stateroot),
+            status: value.status,
+            syncaggregate_bits: optional_bytes_to_hex(value.syncaggregate_bits),
+            syncaggregate_participation: value.syncaggregate_participation,
+            syncaggregate_signature: optional_bytes_to_hex(value.syncaggregate_signature),
+            voluntaryexitscount: value.voluntaryexitscount,
+            withdrawalcount: value.withdrawalcount,
+        }
+    }
+}
+
+impl TryFrom<&SlotData> for SlotDataRow {
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    fn try_from(value: &SlotData) -> Result<Self, Self::Error> {
+        Ok(SlotDataRow {
+            attestationscount: value.attestationscount,
+            attesterslashingscount: value.attesterslashingscount,
+            blob_kzg_commitments_count: value.blob_kzg_commitments_count,
+            blockroot: hex_to_bytes(&value.blockroot)?,
+            depositscount: value.depositscount,
+            epoch: value.epoch,
+            eth1data_blockhash: hex_to_bytes(&value.eth1data_blockhash)?,
+            eth1data_depositcount: value.eth1data_depositcount,
+            eth1data_depositroot: hex_to_bytes(&value.eth1data_depositroot)?,
+            exec_base_fee_per_gas: value.exec_base_fee_per_gas,
+            exec_blob_gas_used: value.exec_blob_gas_used,
+            exec_block_hash: optional_hex_to_bytes(&value.exec_block_hash)?,
+            exec_block_number: value.exec_block_number,
+            exec_excess_blob_gas: value.exec_excess_blob_gas,
+            exec_extra_data: optional_hex_to_bytes(&value.exec_extra_data)?,
+            exec_fee_recipient: optional_hex_to_bytes(&value.exec_fee_recipient)?,
+            exec_gas_limit: value.exec_gas_limit,
+            exec_gas_used: value.exec_gas_used,
+            exec_logs_bloom: optional_hex_to_bytes(&value.exec_logs_bloom)?,
+            exec_parent_hash: optional_hex_to_bytes(&value.exec_parent_hash)?,
+            exec_random: optional_hex_to_bytes(&value.exec_random)?,
+            exec_receipts_root: optional_hex_to_bytes(&value.exec_receipts_root)?,
+            exec_state_root: optional_hex_to_bytes(&value.exec_state_root)?,
+            exec_timestamp: value.exec_timestamp,
+            exec_transactions_count: value.exec_transactions_count,
+            graffiti: hex_to_bytes(&value.graffiti)?,
+            graffiti_text: value.graffiti_text.clone(),
+            parent_beacon_block_root: optional_hex_to_bytes(&value.parent_beacon_block_root)?,
+            parentroot: hex_to_bytes(&value.parentroot)?,
+            proposer: value.proposer,
+            proposerslashingscount: value.proposerslashingscount,
+            randaoreveal: hex_to_bytes(&value.randaoreveal)?,
+            signature: hex_to_bytes(&value.signature)?,
+            slot: value.slot,
+            stateroot: hex_to_bytes(&value.stateroot)?,
+            status: value.status.clone(),
+            syncaggregate_bits: optional_hex_to_bytes(&value.syncaggregate_bits)?,
+            syncaggregate_participation: value.syncaggregate_participation,
+            syncaggregate_signature: optional_hex_to_bytes(&value.syncaggregate_signature)?,
+            voluntaryexitscount: value.voluntaryexitscount,
+            withdrawalcount: value.withdrawalcount,
+        })
+    }
+}
+
+/// Size of a single blob sidecar payload (4096 field elements * 32 bytes).
+pub const BYTES_PER_BLOB: i64 = 131_072;
+/// Blob gas charged per blob by EIP-4844.
+pub const GAS_PER_BLOB: i64 = 131_072;
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct BlobSidecarSummary {
+    pub slot: i64,
+    pub epoch: i64,
+    pub blob_count: i64,
+    pub blob_gas_used: i64,
+    pub excess_blob_gas: i64,
+    pub total_blob_size: i64,
+}
+
+impl BlobSidecarSummary {
+    /// Builds the per slot blob summary, pre-Deneb slots have no blob data and yield `None`.
+    pub fn from_slot(slot: &SlotData) -> Option<Self> {
+        let blob_count = match slot.blob_kzg_commitments_count {
+            Some(count) => count,
+            None => slot.exec_blob_gas_used? / GAS_PER_BLOB,
+        };
+
+        Some(BlobSidecarSummary {
+            slot: slot.slot,
+            epoch: slot.epoch,
+            blob_count,
+            blob_gas_used: slot.exec_blob_gas_used.unwrap_or(blob_count * GAS_PER_BLOB),
+            excess_blob_gas: slot.exec_excess_blob_gas.unwrap_or_default(),
+            total_blob_size: blob_count * BYTES_PER_BLOB,
+        })
+    }
+}
@@ -0,0 +1,124 @@
+use std::str::FromStr;
+
+use chrono::{NaiveDateTime, SecondsFormat, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::models::{EpochData, SlotData};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RollupGranularity {
+    Hourly,
+    Daily,
+}
+
+impl RollupGranularity {
+    pub fn bucket_seconds(&self) -> i64 {
+        match self {
+            RollupGranularity::Hourly => 60 * 60,
+            RollupGranularity::Daily => 24 * 60 * 60,
+        }
+    }
+
+    /// Start of the bucket holding the unix timestamp `ts`.
+    pub fn bucket_start(&self, ts: i64) -> i64 {
+        ts - ts.rem_euclid(self.bucket_seconds())
+    }
+}
+
+impl FromStr for RollupGranularity {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "hourly" => Ok(RollupGranularity::Hourly),
+            "daily" => Ok(RollupGranularity::Daily),
+            _ => Err(format!(
+                "Unknown rollup granularity {value}, expected hourly or daily"
+            )),
+        }
+    }
+}
+
+/// Running sums of one `rollup_hourly`/`rollup_daily` bucket. Averages are derived in
+/// [`RollupSummary`] so that rows can be added and subtracted again on updates.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct Rollup {
+    pub bucket_start: i64,
+    pub epochs: i64,
+    pub participation_sum: f64,
+    pub slots: i64,
+    pub blocks_proposed: i64,
+    pub blocks_missed: i64,
+    pub blocks_orphaned: i64,
+    pub gas_used: i64,
+    pub base_fee_sum: i64,
+    pub base_fee_count: i64,
+    pub withdrawals: i64,
+}
+
+impl Rollup {
+    pub fn new(bucket_start: i64) -> Self {
+        Rollup {
+            bucket_start,
+            ..Rollup::default()
+        }
+    }
+
+    /// Adds the epoch, or takes it out again with `sign = -1`.
+    pub fn add_epoch(&mut self, epoch_data: &EpochData, sign: i64) {
+        self.epochs += sign;
+        self.participation_sum += sign as f64 * epoch_data.globalparticipationrate;
+    }
+
+    /// Adds the slot, or takes it out again with `sign = -1`.
+    pub fn add_slot(&mut self, slot: &SlotData, sign: i64) {
+        let is = |status: &str| i64::from(slot.status == status);
+
+        self.slots += sign;
+        self.blocks_proposed += sign * is("1");
+        self.blocks_missed += sign * is("2");
+        self.blocks_orphaned += sign * is("3");
+        self.gas_used += sign * slot.exec_gas_used.unwrap_or_default();
+        self.base_fee_sum += sign * slot.exec_base_fee_per_gas.unwrap_or_default();
+        self.base_fee_count += sign * i64::from(slot.exec_base_fee_per_gas.is_some());
+        self.withdrawals += sign * slot.withdrawalcount;
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct RollupSummary {
+    pub bucket_start: String,
+    pub epochs: i64,
+    pub average_participation: Option<f64>,
+    pub slots: i64,
+    pub blocks_proposed: i64,
+    pub blocks_missed: i64,
+    pub blocks_orphaned: i64,
+    pub gas_used: i64,
+    pub average_base_fee_per_gas: Option<f64>,
+    pub withdrawals: i64,
+}
+
+impl From<&Rollup> for RollupSummary {
+    fn from(value: &Rollup) -> Self {
+        let average = |sum: f64, count: i64| (count > 0).then(|| sum / count as f64);
+
+        RollupSummary {
+            bucket_start: NaiveDateTime::from_timestamp_opt(value.bucket_start, 0)
+                .map(|bucket_start| {
+                    Utc.from_utc_datetime(&bucket_start)
+                        .to_rfc3339_opts(SecondsFormat::Secs, true)
+                })
+                .unwrap_or_default(),
+            epochs: value.epochs,
+            average_participation: average(value.participation_sum, value.epochs),
+            slots: value.slots,
+            blocks_proposed: value.blocks_proposed,
+            blocks_missed: value.blocks_missed,
+            blocks_orphaned: value.blocks_orphaned,
+            gas_used: value.gas_used,
+            average_base_fee_per_gas: average(value.base_fee_sum as f64, value.base_fee_count),
+            withdrawals: value.withdrawals,
+        }
+    }
+}
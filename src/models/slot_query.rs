@@ -0,0 +1,91 @@
+use std::str::FromStr;
+
+use serde::Serialize;
+
+use super::{hex_eq, SlotData};
+
+/// Order of `GET /slots` pages, by slot number.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SlotOrder {
+    #[default]
+    Ascending,
+    Descending,
+}
+
+impl SlotOrder {
+    pub fn as_sql(&self) -> &'static str {
+        match self {
+            SlotOrder::Ascending => "ASC",
+            SlotOrder::Descending => "DESC",
+        }
+    }
+}
+
+impl FromStr for SlotOrder {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "asc" => Ok(SlotOrder::Ascending),
+            "desc" => Ok(SlotOrder::Descending),
+            _ => Err(format!("Unknown sort {value}, expected asc or desc")),
+        }
+    }
+}
+
+/// Filters of a slot listing, all of them optional and all given ones must match. Pages are
+/// continued with the slot number they ended at as cursor, which stays valid while slots are
+/// inserted or pruned as slot numbers never change.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SlotQuery {
+    /// First slot, inclusive.
+    pub from_slot: Option<i64>,
+    /// Last slot, inclusive.
+    pub to_slot: Option<i64>,
+    pub epoch: Option<i64>,
+    pub proposer: Option<i64>,
+    pub status: Option<String>,
+    /// Hex address, compared regardless of case and `0x` prefix.
+    pub fee_recipient: Option<String>,
+    pub order: SlotOrder,
+}
+
+impl SlotQuery {
+    /// Inclusive slot bounds of the page after `cursor`, `None` when no slot number can follow
+    /// the cursor and the page is empty.
+    pub fn bounds(&self, cursor: Option<i64>) -> Option<(Option<i64>, Option<i64>)> {
+        let (mut from_slot, mut to_slot) = (self.from_slot, self.to_slot);
+        match (cursor, self.order) {
+            (Some(cursor), SlotOrder::Ascending) => {
+                let next = cursor.checked_add(1)?;
+                from_slot = Some(from_slot.map_or(next, |from| from.max(next)))
+            }
+            (Some(cursor), SlotOrder::Descending) => {
+                let next = cursor.checked_sub(1)?;
+                to_slot = Some(to_slot.map_or(next, |to| to.min(next)))
+            }
+            (None, _) => (),
+        }
+        Some((from_slot, to_slot))
+    }
+
+    /// Whether `slot` passes every filter except the slot bounds.
+    pub fn matches(&self, slot: &SlotData) -> bool {
+        (self.epoch.is_none() || self.epoch == Some(slot.epoch))
+            && (self.proposer.is_none() || self.proposer == Some(slot.proposer))
+            && (self.status.is_none() || self.status.as_ref() == Some(&slot.status))
+            && match (&self.fee_recipient, &slot.exec_fee_recipient) {
+                (None, _) => true,
+                (Some(wanted), Some(paid)) => hex_eq(wanted, paid),
+                (Some(_), None) => false,
+            }
+    }
+}
+
+/// A page of slots in the requested order. Pass `next_cursor` as `cursor` to continue, it is
+/// `None` on the last page.
+#[derive(Debug, Serialize)]
+pub struct SlotPage {
+    pub slots: Vec<SlotData>,
+    pub next_cursor: Option<i64>,
+}
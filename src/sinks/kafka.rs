@@ -0,0 +1,80 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use rdkafka::{
+    producer::{FutureProducer, FutureRecord},
+    ClientConfig,
+};
+
+use super::sink::{env_string, PublishedChange, Sink};
+use crate::AppResult;
+
+pub const DEFAULT_KAFKA_TOPIC: &str = "rish.changes";
+/// How long librdkafka keeps retrying a message before reporting it as failed.
+const KAFKA_MESSAGE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Produces every change to a Kafka topic, keyed by [`PublishedChange::key`] so the changes
+/// of one epoch or slot land on one partition in order. The producer is idempotent and waits
+/// for all in-sync replicas.
+pub struct KafkaSink {
+    producer: FutureProducer,
+    topic: String,
+}
+
+impl KafkaSink {
+    pub fn new(brokers: &str, topic: String) -> AppResult<Self> {
+        let producer = ClientConfig::new()
+            .set("bootstrap.servers", brokers)
+            .set("enable.idempotence", "true")
+            .set("acks", "all")
+            .set(
+                "message.timeout.ms",
+                KAFKA_MESSAGE_TIMEOUT.as_millis().to_string(),
+            )
+            .create()?;
+
+        Ok(KafkaSink { producer, topic })
+    }
+
+    /// Reads `RISH_SINK_KAFKA_BROKERS` and `RISH_SINK_KAFKA_TOPIC`, `None` when no brokers
+    /// are configured.
+    pub fn from_env() -> AppResult<Option<Self>> {
+        let Ok(brokers) = std::env::var("RISH_SINK_KAFKA_BROKERS") else {
+            return Ok(None);
+        };
+        let topic = env_string("RISH_SINK_KAFKA_TOPIC", DEFAULT_KAFKA_TOPIC);
+
+        Ok(Some(KafkaSink::new(&brokers, topic)?))
+    }
+}
+
+#[async_trait]
+impl Sink for KafkaSink {
+    fn name(&self) -> String {
+        format!("kafka:{}", self.topic)
+    }
+
+    async fn publish(&mut self, changes: &[PublishedChange]) -> AppResult<()> {
+        // Queue the whole batch first so librdkafka can pipeline it, then wait for every ack.
+        let mut deliveries = Vec::with_capacity(changes.len());
+        for change in changes {
+            let key = change.key();
+            let payload = change.to_json()?;
+            let record = FutureRecord::to(&self.topic).key(&key).payload(&payload);
+            let delivery = self
+                .producer
+                .send_result(record)
+                .map_err(|(err, _)| format!("Kafka rejected change {}: {err}", change.id()))?;
+            deliveries.push((change.id(), delivery));
+        }
+
+        for (id, delivery) in deliveries {
+            delivery
+                .await
+                .map_err(|_| format!("Kafka producer dropped change {id}"))?
+                .map_err(|(err, _)| format!("Kafka did not acknowledge change {id}: {err}"))?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,18 @@
+#[cfg(feature = "kafka")]
+pub mod kafka;
+#[cfg(feature = "nats")]
+pub mod nats;
+pub mod ndjson;
+#[cfg(feature = "redis")]
+pub mod redis_stream;
+pub mod relay;
+pub mod sink;
+#[cfg(feature = "kafka")]
+pub use kafka::*;
+#[cfg(feature = "nats")]
+pub use nats::*;
+pub use ndjson::*;
+#[cfg(feature = "redis")]
+pub use redis_stream::*;
+pub use relay::*;
+pub use sink::*;
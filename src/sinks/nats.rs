@@ -0,0 +1,67 @@
+use async_nats::{jetstream, HeaderMap};
+use async_trait::async_trait;
+
+use super::sink::{env_string, PublishedChange, Sink};
+use crate::{utils::Network, AppResult};
+
+pub const DEFAULT_NATS_SUBJECT: &str = "rish.changes";
+
+/// Publishes every change to NATS JetStream as `<subject>.<network>.<entity>` and waits for
+/// the stream to acknowledge it. A stream capturing `<subject>.>` must exist. Messages carry
+/// [`PublishedChange::id`] as `Nats-Msg-Id`, so JetStream drops retried duplicates within its
+/// deduplication window.
+pub struct NatsSink {
+    jetstream: jetstream::Context,
+    subject: String,
+    network: Network,
+}
+
+impl NatsSink {
+    pub async fn connect(url: &str, subject: String, network: Network) -> AppResult<Self> {
+        let client = async_nats::connect(url).await?;
+
+        Ok(NatsSink {
+            jetstream: jetstream::new(client),
+            subject,
+            network,
+        })
+    }
+
+    /// Reads `RISH_SINK_NATS_URL` and `RISH_SINK_NATS_SUBJECT`, `None` when no server is
+    /// configured.
+    pub async fn from_env(network: Network) -> AppResult<Option<Self>> {
+        let Ok(url) = std::env::var("RISH_SINK_NATS_URL") else {
+            return Ok(None);
+        };
+        let subject = env_string("RISH_SINK_NATS_SUBJECT", DEFAULT_NATS_SUBJECT);
+
+        Ok(Some(NatsSink::connect(&url, subject, network).await?))
+    }
+}
+
+#[async_trait]
+impl Sink for NatsSink {
+    fn name(&self) -> String {
+        format!("nats:{}", self.subject)
+    }
+
+    async fn publish(&mut self, changes: &[PublishedChange]) -> AppResult<()> {
+        let mut acks = Vec::with_capacity(changes.len());
+        for change in changes {
+            let subject = format!("{}.{}.{}", self.subject, self.network, change.change.entity);
+            let mut headers = HeaderMap::new();
+            headers.insert("Nats-Msg-Id", change.id().as_str());
+            let ack = self
+                .jetstream
+                .publish_with_headers(subject, headers, change.to_json()?.into())
+                .await?;
+            acks.push(ack);
+        }
+
+        for ack in acks {
+            ack.await?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,103 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{BufWriter, Write},
+    path::PathBuf,
+};
+
+use async_trait::async_trait;
+
+use super::sink::{PublishedChange, Sink};
+use crate::{
+    utils::{retention::env_number, Network},
+    AppResult,
+};
+
+pub const DEFAULT_NDJSON_MAX_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Appends changes as one JSON object per line to `<dir>/<network>-<first seq>.ndjson`,
+/// starting a new file once the current one reaches `max_bytes`. The zero padded sequence
+/// number keeps the files of a network sorted by name. Every batch is synced to disk before
+/// it counts as delivered.
+pub struct NdjsonSink {
+    dir: PathBuf,
+    network: Network,
+    max_bytes: u64,
+    current: Option<(File, u64)>,
+}
+
+impl NdjsonSink {
+    pub fn new(dir: PathBuf, network: Network, max_bytes: u64) -> AppResult<Self> {
+        fs::create_dir_all(&dir)?;
+
+        Ok(NdjsonSink {
+            dir,
+            network,
+            max_bytes,
+            current: None,
+        })
+    }
+
+    /// Reads `RISH_SINK_NDJSON_DIR` and `RISH_SINK_NDJSON_MAX_BYTES`, `None` when no directory
+    /// is configured.
+    pub fn from_env(network: Network) -> AppResult<Option<Self>> {
+        let Ok(dir) = std::env::var("RISH_SINK_NDJSON_DIR") else {
+            return Ok(None);
+        };
+        let max_bytes =
+            env_number("RISH_SINK_NDJSON_MAX_BYTES")?.unwrap_or(DEFAULT_NDJSON_MAX_BYTES);
+
+        Ok(Some(NdjsonSink::new(
+            PathBuf::from(dir),
+            network,
+            max_bytes,
+        )?))
+    }
+
+    /// The file to append to, rotating to one named after `seq` when the current one is full.
+    fn file_for(&mut self, seq: i64) -> AppResult<&mut (File, u64)> {
+        if matches!(&self.current, Some((_, size)) if *size >= self.max_bytes) {
+            self.current = None;
+        }
+
+        if self.current.is_none() {
+            let path = self.dir.join(format!("{}-{seq:020}.ndjson", self.network));
+            // A retried batch may find the file it was written to before, so append.
+            let file = OpenOptions::new().create(true).append(true).open(&path)?;
+            let size = file.metadata()?.len();
+            println!("NDJSON SINK: Writing to {}", path.display());
+            self.current = Some((file, size));
+        }
+
+        Ok(self.current.as_mut().expect("file was opened above"))
+    }
+}
+
+#[async_trait]
+impl Sink for NdjsonSink {
+    fn name(&self) -> String {
+        "ndjson".to_string()
+    }
+
+    async fn publish(&mut self, changes: &[PublishedChange]) -> AppResult<()> {
+        let max_bytes = self.max_bytes;
+        let mut index = 0;
+
+        while index < changes.len() {
+            let (file, size) = self.file_for(changes[index].change.seq)?;
+            let mut writer = BufWriter::new(&*file);
+
+            while index < changes.len() && *size < max_bytes {
+                let line = changes[index].to_json()? + "\n";
+                writer.write_all(line.as_bytes())?;
+                *size += line.len() as u64;
+                index += 1;
+            }
+
+            writer.flush()?;
+            drop(writer);
+            file.sync_data()?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,79 @@
+use async_trait::async_trait;
+use redis::aio::ConnectionManager;
+
+use super::sink::{env_string, PublishedChange, Sink};
+use crate::{utils::retention::env_number, AppResult};
+
+pub const DEFAULT_REDIS_STREAM: &str = "rish:changes";
+
+/// Appends every change to a Redis stream with `XADD`, one atomic pipeline per batch. Entries
+/// hold `id`, `network`, `entity`, `key`, `kind` and the full change as `change`. The stream is
+/// trimmed to about `max_len` entries when set.
+pub struct RedisStreamSink {
+    connection: ConnectionManager,
+    stream: String,
+    max_len: Option<u64>,
+}
+
+impl RedisStreamSink {
+    pub async fn connect(url: &str, stream: String, max_len: Option<u64>) -> AppResult<Self> {
+        let client = redis::Client::open(url)?;
+        // Reconnects on its own after the connection drops.
+        let connection = client.get_connection_manager().await?;
+
+        Ok(RedisStreamSink {
+            connection,
+            stream,
+            max_len,
+        })
+    }
+
+    /// Reads `RISH_SINK_REDIS_URL`, `RISH_SINK_REDIS_STREAM` and `RISH_SINK_REDIS_MAXLEN`,
+    /// `None` when no server is configured.
+    pub async fn from_env() -> AppResult<Option<Self>> {
+        let Ok(url) = std::env::var("RISH_SINK_REDIS_URL") else {
+            return Ok(None);
+        };
+        let stream = env_string("RISH_SINK_REDIS_STREAM", DEFAULT_REDIS_STREAM);
+        let max_len = env_number("RISH_SINK_REDIS_MAXLEN")?;
+
+        Ok(Some(RedisStreamSink::connect(&url, stream, max_len).await?))
+    }
+}
+
+#[async_trait]
+impl Sink for RedisStreamSink {
+    fn name(&self) -> String {
+        format!("redis:{}", self.stream)
+    }
+
+    async fn publish(&mut self, changes: &[PublishedChange]) -> AppResult<()> {
+        let mut pipeline = redis::pipe();
+        pipeline.atomic();
+
+        for change in changes {
+            pipeline.cmd("XADD").arg(&self.stream);
+            if let Some(max_len) = self.max_len {
+                pipeline.arg("MAXLEN").arg("~").arg(max_len);
+            }
+            pipeline
+                .arg("*")
+                .arg("id")
+                .arg(change.id())
+                .arg("network")
+                .arg(change.network)
+                .arg("entity")
+                .arg(&change.change.entity)
+                .arg("key")
+                .arg(change.change.key)
+                .arg("kind")
+                .arg(&change.change.kind)
+                .arg("change")
+                .arg(change.to_json()?);
+        }
+
+        pipeline.query_async::<_, ()>(&mut self.connection).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,92 @@
+use std::future::Future;
+
+use tokio::time;
+
+use super::sink::{PublishedChange, Sink, SinkSettings};
+use crate::{db_ops::SharedStore, AppResult};
+
+/// Runs `operation` until it succeeds, backing off like a failed publish in between. Store
+/// errors are usually transient, a locked or restarting database, and must not end the relay.
+async fn retry_store<T, F, Fut>(tag: &str, what: &str, settings: &SinkSettings, operation: F) -> T
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = AppResult<T>>,
+{
+    let mut backoff = settings.retry_initial;
+    let mut attempt = 1;
+    loop {
+        match operation().await {
+            Ok(value) => return value,
+            Err(e) => {
+                println!(
+                    "{tag}: Attempt {attempt} to {what} failed: {e}, retrying in {}s",
+                    backoff.as_secs()
+                );
+                time::sleep(backoff).await;
+                backoff = (backoff * 2).min(settings.retry_max);
+                attempt += 1;
+            }
+        }
+    }
+}
+
+/// Feeds `sink` from the store's change outbox, starting after the sink's stored cursor.
+/// A batch that fails is retried with exponential backoff until it is delivered, the cursor
+/// only moves past it afterwards. Reading the outbox and storing the cursor are retried the
+/// same way.
+pub async fn relay_changes(
+    store: SharedStore,
+    mut sink: Box<dyn Sink>,
+    settings: SinkSettings,
+) -> AppResult<()> {
+    let network = store.network();
+    let name = sink.name();
+    let tag = format!("SINK {network} {name}");
+    let mut cursor = retry_store(&tag, "read the cursor", &settings, || {
+        store.get_sink_cursor(&name)
+    })
+    .await;
+
+    println!("{tag}: Started after seq {cursor}");
+
+    loop {
+        let changes = retry_store(&tag, "read changes", &settings, || {
+            store.get_changes(cursor, settings.batch_size)
+        })
+        .await;
+        let Some(last_seq) = changes.last().map(|change| change.seq) else {
+            time::sleep(settings.poll_interval).await;
+            continue;
+        };
+        let changes: Vec<PublishedChange> = changes
+            .into_iter()
+            .map(|change| PublishedChange {
+                network: network.name,
+                change,
+            })
+            .collect();
+
+        let mut backoff = settings.retry_initial;
+        let mut attempt = 1;
+        while let Err(e) = sink.publish(&changes).await {
+            println!(
+                "{tag}: Attempt {attempt} to deliver seq {} to {last_seq} failed: {e}, retrying in {}s",
+                cursor + 1,
+                backoff.as_secs()
+            );
+            time::sleep(backoff).await;
+            backoff = (backoff * 2).min(settings.retry_max);
+            attempt += 1;
+        }
+
+        retry_store(&tag, "store the cursor", &settings, || {
+            store.set_sink_cursor(&name, last_seq)
+        })
+        .await;
+        cursor = last_seq;
+        println!(
+            "{tag}: Delivered {} changes up to seq {last_seq}",
+            changes.len()
+        );
+    }
+}
@@ -0,0 +1,151 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::Serialize;
+
+use crate::{
+    models::ChangeEvent,
+    utils::{retention::env_number, Network},
+    AppResult,
+};
+
+pub const DEFAULT_SINK_BATCH_SIZE: i64 = 500;
+pub const DEFAULT_SINK_POLL_INTERVAL: Duration = Duration::from_secs(1);
+pub const DEFAULT_SINK_RETRY_INITIAL: Duration = Duration::from_secs(1);
+pub const DEFAULT_SINK_RETRY_MAX: Duration = Duration::from_secs(60);
+
+/// A change as published to a sink: the outbox event plus the network it belongs to, as
+/// several networks may share one topic, subject or stream.
+#[derive(Debug, Clone, Serialize)]
+pub struct PublishedChange {
+    pub network: &'static str,
+    #[serde(flatten)]
+    pub change: ChangeEvent,
+}
+
+impl PublishedChange {
+    /// Identifies the change across retries, consumers deduplicate on it.
+    pub fn id(&self) -> String {
+        format!("{}-{}", self.network, self.change.seq)
+    }
+
+    /// Partitioning key, keeping the changes of one epoch or slot in order.
+    pub fn key(&self) -> String {
+        format!(
+            "{}:{}:{}",
+            self.network, self.change.entity, self.change.key
+        )
+    }
+
+    pub fn to_json(&self) -> AppResult<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+}
+
+/// A destination ingested changes are published to, fed from the change outbox by
+/// [`relay_changes`](super::relay_changes).
+///
+/// Delivery is at least once: the relay only moves the sink's cursor after `publish` returned
+/// `Ok`, and retries the whole batch otherwise, so consumers must tolerate duplicates of
+/// [`PublishedChange::id`].
+#[async_trait]
+pub trait Sink: Send {
+    /// Key of the sink's cursor in `sink_cursors`, unique among the sinks of a database.
+    fn name(&self) -> String;
+    /// Delivers `changes` in order, returning once the destination acknowledged all of them.
+    async fn publish(&mut self, changes: &[PublishedChange]) -> AppResult<()>;
+}
+
+/// How the relay batches and retries, shared by every sink.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SinkSettings {
+    pub batch_size: i64,
+    pub poll_interval: Duration,
+    pub retry_initial: Duration,
+    pub retry_max: Duration,
+}
+
+impl Default for SinkSettings {
+    fn default() -> Self {
+        SinkSettings {
+            batch_size: DEFAULT_SINK_BATCH_SIZE,
+            poll_interval: DEFAULT_SINK_POLL_INTERVAL,
+            retry_initial: DEFAULT_SINK_RETRY_INITIAL,
+            retry_max: DEFAULT_SINK_RETRY_MAX,
+        }
+    }
+}
+
+#[cfg(any(feature = "kafka", feature = "nats", feature = "redis"))]
+pub(crate) fn env_string(name: &str, default: &str) -> String {
+    std::env::var(name).unwrap_or_else(|_| default.to_string())
+}
+
+impl SinkSettings {
+    /// Reads `RISH_SINK_BATCH_SIZE`, `RISH_SINK_POLL_INTERVAL_MS` and
+    /// `RISH_SINK_RETRY_MAX_SECS`.
+    pub fn from_env() -> AppResult<Self> {
+        let defaults = SinkSettings::default();
+
+        Ok(SinkSettings {
+            batch_size: env_number("RISH_SINK_BATCH_SIZE")?
+                .map(|size| size.max(1) as i64)
+                .unwrap_or(defaults.batch_size),
+            poll_interval: env_number("RISH_SINK_POLL_INTERVAL_MS")?
+                .map(Duration::from_millis)
+                .unwrap_or(defaults.poll_interval),
+            retry_initial: defaults.retry_initial,
+            retry_max: env_number("RISH_SINK_RETRY_MAX_SECS")?
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.retry_max),
+        })
+    }
+}
+
+#[cfg(not(all(feature = "kafka", feature = "nats", feature = "redis")))]
+fn ensure_unset(variable: &str, feature: &str) -> AppResult<()> {
+    match std::env::var(variable) {
+        Ok(_) => Err(format!(
+            "{variable} is set but rish was built without the `{feature}` feature"
+        )
+        .into()),
+        Err(_) => Ok(()),
+    }
+}
+
+/// The sinks configured through the environment for `network`, none by default:
+///
+/// - `RISH_SINK_NDJSON_DIR`: rotating NDJSON files, see [`NdjsonSink`](super::NdjsonSink).
+/// - `RISH_SINK_KAFKA_BROKERS`: a Kafka topic (`kafka` feature).
+/// - `RISH_SINK_NATS_URL`: a NATS JetStream subject (`nats` feature).
+/// - `RISH_SINK_REDIS_URL`: a Redis stream (`redis` feature).
+pub async fn sinks_from_env(network: Network) -> AppResult<Vec<Box<dyn Sink>>> {
+    let mut sinks: Vec<Box<dyn Sink>> = Vec::new();
+
+    if let Some(sink) = super::NdjsonSink::from_env(network)? {
+        sinks.push(Box::new(sink));
+    }
+
+    #[cfg(feature = "kafka")]
+    if let Some(sink) = super::KafkaSink::from_env()? {
+        sinks.push(Box::new(sink));
+    }
+    #[cfg(not(feature = "kafka"))]
+    ensure_unset("RISH_SINK_KAFKA_BROKERS", "kafka")?;
+
+    #[cfg(feature = "nats")]
+    if let Some(sink) = super::NatsSink::from_env(network).await? {
+        sinks.push(Box::new(sink));
+    }
+    #[cfg(not(feature = "nats"))]
+    ensure_unset("RISH_SINK_NATS_URL", "nats")?;
+
+    #[cfg(feature = "redis")]
+    if let Some(sink) = super::RedisStreamSink::from_env().await? {
+        sinks.push(Box::new(sink));
+    }
+    #[cfg(not(feature = "redis"))]
+    ensure_unset("RISH_SINK_REDIS_URL", "redis")?;
+
+    Ok(sinks)
+}
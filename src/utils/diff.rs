@@ -0,0 +1,180 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use super::{
+    external_api::get_specific_epoch_data, get_specific_epoch_slots, pipeline::enrich_slot,
+};
+use crate::{
+    cli::DiffRange,
+    db_ops::SharedStore,
+    dtos::SlotDataDto,
+    models::{EpochData, SlotData},
+    AppResult,
+};
+
+#[derive(Debug, Serialize)]
+pub struct FieldDifference {
+    pub table: &'static str,
+    pub key: i64,
+    pub field: String,
+    pub stored: Value,
+    pub upstream: Value,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct DiffReport {
+    pub rows_compared: u64,
+    pub rows_missing: u64,
+    /// Stored rows upstream no longer has, e.g. reorged or orphaned blocks.
+    pub rows_not_upstream: u64,
+    pub rows_with_differences: u64,
+    /// Number of differing rows per `table.field`.
+    pub fields: BTreeMap<String, u64>,
+    pub missing: Vec<MissingRow>,
+    pub not_upstream: Vec<MissingRow>,
+    pub differences: Vec<FieldDifference>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MissingRow {
+    pub table: &'static str,
+    pub key: i64,
+}
+
+impl DiffReport {
+    fn not_upstream(&mut self, table: &'static str, key: i64) {
+        self.rows_compared += 1;
+        self.rows_not_upstream += 1;
+        self.not_upstream.push(MissingRow { table, key });
+    }
+
+    fn compare<T: Serialize>(
+        &mut self,
+        table: &'static str,
+        key: i64,
+        stored: Option<&T>,
+        upstream: &T,
+    ) -> AppResult<()> {
+        self.rows_compared += 1;
+
+        let stored = match stored {
+            Some(stored) => serde_json::to_value(stored)?,
+            None => {
+                self.rows_missing += 1;
+                self.missing.push(MissingRow { table, key });
+                return Ok(());
+            }
+        };
+        let upstream = serde_json::to_value(upstream)?;
+
+        let (Value::Object(stored), Value::Object(upstream)) = (stored, upstream) else {
+            return Ok(());
+        };
+
+        let mut row_differs = false;
+        for (field, upstream_value) in upstream {
+            let stored_value = stored.get(&field).cloned().unwrap_or(Value::Null);
+            if stored_value != upstream_value {
+                row_differs = true;
+                *self.fields.entry(format!("{table}.{field}")).or_default() += 1;
+                self.differences.push(FieldDifference {
+                    table,
+                    key,
+                    field,
+                    stored: stored_value,
+                    upstream: upstream_value,
+                });
+            }
+        }
+
+        if row_differs {
+            self.rows_with_differences += 1;
+        }
+
+        Ok(())
+    }
+
+    /// Compares an upstream slot with the stored row. Stored rows went through the enrich
+    /// stage, so the upstream one is enriched the same way first.
+    pub fn compare_slot(
+        &mut self,
+        stored: Option<&SlotData>,
+        upstream: SlotDataDto,
+    ) -> AppResult<()> {
+        let mut upstream = SlotData::from(upstream);
+        enrich_slot(&mut upstream);
+        self.compare("slot_data", upstream.slot, stored, &upstream)
+    }
+}
+
+/// Names of the fields whose serialized values differ between `left` and `right`.
+pub fn differing_fields<T: Serialize>(left: &T, right: &T) -> AppResult<Vec<String>> {
+    let (Value::Object(left), Value::Object(right)) =
+        (serde_json::to_value(left)?, serde_json::to_value(right)?)
+    else {
+        return Ok(Vec::new());
+    };
+
+    Ok(right
+        .into_iter()
+        .filter(|(field, value)| left.get(field).unwrap_or(&Value::Null) != value)
+        .map(|(field, _)| field)
+        .collect())
+}
+
+/// Re-fetches `range` from upstream and compares it field by field with the stored rows, also
+/// listing stored rows upstream does not return. Nothing is written to the database.
+pub async fn diff_range(store: SharedStore, range: DiffRange) -> AppResult<DiffReport> {
+    let network = store.network();
+    let slots_per_epoch = network.slots_per_epoch;
+    let (first_slot, last_slot, compare_epochs) = match range {
+        DiffRange::Epochs(from, to) => {
+            (from * slots_per_epoch, (to + 1) * slots_per_epoch - 1, true)
+        }
+        DiffRange::Slots(from, to) => (from, to, false),
+    };
+    let mut report = DiffReport::default();
+
+    for epoch_number in network.epoch_of_slot(first_slot)..=network.epoch_of_slot(last_slot) {
+        println!("DIFF: Comparing epoch {epoch_number}");
+
+        if compare_epochs {
+            let upstream_epoch: EpochData =
+                get_specific_epoch_data(network, &epoch_number.to_string())
+                    .await?
+                    .into();
+            let stored_epoch = store.get_epoch(epoch_number).await?;
+            report.compare(
+                "epoch_data",
+                epoch_number,
+                stored_epoch.as_ref(),
+                &upstream_epoch,
+            )?;
+        }
+
+        let mut stored_slots: BTreeMap<i64, SlotData> = store
+            .get_epoch_slots(epoch_number)
+            .await?
+            .into_iter()
+            .map(|slot| (slot.slot, slot))
+            .collect();
+
+        for upstream_slot in get_specific_epoch_slots(network, epoch_number).await? {
+            if upstream_slot.slot < first_slot || upstream_slot.slot > last_slot {
+                continue;
+            }
+            let stored_slot = stored_slots.remove(&upstream_slot.slot);
+            report.compare_slot(stored_slot.as_ref(), upstream_slot)?;
+        }
+
+        for slot_number in stored_slots.into_keys() {
+            if (first_slot..=last_slot).contains(&slot_number) {
+                report.not_upstream("slot_data", slot_number);
+            }
+        }
+    }
+
+    Ok(report)
+}
@@ -1,21 +1,30 @@
-use std::sync::Arc;
-
-use super::BEACON_CHAIN_API_URL;
+use super::{
+    pipeline::{EpochTarget, IngestJob, IngestPipeline, PersistMode},
+    Network,
+};
 use reqwest::get;
-use sqlx::SqlitePool;
-use tokio::sync::Mutex;
 
 use crate::{
-    db_ops,
     dtos::{Epoch, EpochDataDto, EpochInfo, SlotDataDto},
-    models::SlotData,
     AppResult,
 };
 
-pub async fn get_specific_epoch_data(epoch_number: &str) -> AppResult<EpochDataDto> {
-    println!("Fetching {epoch_number} epoch from chain");
-    let api_key = env!("API_KEY");
-    let url = format!("{BEACON_CHAIN_API_URL}/epoch/{epoch_number}?apikey={api_key}");
+/// The runtime `API_KEY` wins over the one baked in at compile time, which crates using rish
+/// as a library usually do not have.
+fn api_key() -> AppResult<String> {
+    std::env::var("API_KEY")
+        .ok()
+        .or_else(|| option_env!("API_KEY").map(str::to_string))
+        .ok_or_else(|| "API_KEY is not set".into())
+}
+
+pub async fn get_specific_epoch_data(
+    network: Network,
+    epoch_number: &str,
+) -> AppResult<EpochDataDto> {
+    println!("Fetching {network} {epoch_number} epoch from chain");
+    let api_key = api_key()?;
+    let url = format!("{}/epoch/{epoch_number}?apikey={api_key}", network.api_url);
     let response = get(url).await?.text().await?;
     println!("{epoch_number} epoch fetched successfully");
     println!("Starting to parse fetched data into structure");
@@ -25,10 +34,16 @@ pub async fn get_specific_epoch_data(epoch_number: &str) -> AppResult<EpochDataD
     Ok(latest_epoch_data)
 }
 
-pub async fn get_specific_epoch_slots(epoch_number: i64) -> AppResult<Vec<SlotDataDto>> {
-    println!("Fetching {epoch_number} epoch slots from chain now");
-    let api_key = env!("API_KEY");
-    let url = format!("{BEACON_CHAIN_API_URL}/epoch/{epoch_number}/slots?apikey={api_key}");
+pub async fn get_specific_epoch_slots(
+    network: Network,
+    epoch_number: i64,
+) -> AppResult<Vec<SlotDataDto>> {
+    println!("Fetching {network} {epoch_number} epoch slots from chain now");
+    let api_key = api_key()?;
+    let url = format!(
+        "{}/epoch/{epoch_number}/slots?apikey={api_key}",
+        network.api_url
+    );
     let response = get(url).await?.text().await?;
     println!("Starting to parse fetched data into structure");
     let epoch_data = serde_json::from_str::<Epoch>(&response)?;
@@ -38,59 +53,37 @@ pub async fn get_specific_epoch_slots(epoch_number: i64) -> AppResult<Vec<SlotDa
     Ok(slots)
 }
 
-pub async fn fetch_recent_epoch_slots(
-    db_conn: Arc<Mutex<SqlitePool>>,
-    how_many: i64,
-) -> AppResult<()> {
+pub async fn fetch_recent_epoch_slots(pipeline: &IngestPipeline, how_many: i64) -> AppResult<()> {
     println!("Fetching latest epoch number on chain");
-    let current_epoch_number = get_specific_epoch_data("latest").await?.epoch;
-    let mut epochs = Vec::with_capacity(how_many as usize);
+    let current_epoch_number = get_specific_epoch_data(pipeline.network(), "latest")
+        .await?
+        .epoch;
 
     println!("Starting loop for fetching {how_many} epoch slots from chain");
     for epoch_number in current_epoch_number.saturating_sub(how_many - 1)..=current_epoch_number {
         println!("Fetching epoch {epoch_number} from chain");
-        let epoch_slots = get_specific_epoch_slots(epoch_number).await?;
-        // println!("Epoch Slots are : {:#?}", epoch_slots);
-        epochs.push(epoch_slots);
-        println!("{epoch_number} Epoch data pushed successfully into the vector");
-    }
-
-    // println!("Epochs : {:#?}", epochs);
-
-    for epoch in epochs {
-        // match epoch {
-        //     Ok(slots) => {
-        for slot in epoch {
-            let db_slot: SlotData = slot.into();
-            // println!("Slot is : {:?}", db_slot);
-            match db_ops::insert_slot(Arc::clone(&db_conn), db_slot.slot, &db_slot).await {
-                Ok(_) => println!("Slot {} inserted into database!", db_slot.slot),
-                Err(_) => continue,
-            }
-        }
-        // }
-        // Err(e) => eprintln!("Fetch Recent Epoch Task Panicked: {:?}", e),
-        // }
+        let report = pipeline
+            .run(IngestJob {
+                target: EpochTarget::Number(epoch_number),
+                with_epoch_data: false,
+                mode: PersistMode::Insert,
+            })
+            .await?;
+        println!(
+            "{epoch_number} Epoch slots inserted into database: {} persisted, {} rejected, {} failed",
+            report.slots_persisted, report.slots_rejected, report.slots_failed
+        );
     }
 
     Ok(())
 }
 
-pub async fn get_specific_slot(slot_number: i64) -> AppResult<SlotDataDto> {
-    let api_key = env!("API_KEY");
-    let url = format!("{BEACON_CHAIN_API_URL}/slot/{slot_number}?apikey={api_key}");
+pub async fn get_specific_slot(network: Network, slot_number: i64) -> AppResult<SlotDataDto> {
+    let api_key = api_key()?;
+    let url = format!("{}/slot/{slot_number}?apikey={api_key}", network.api_url);
     let response = get(url).await?.text().await?;
     println!("Slot {slot_number} fetched successfully from chain");
     let slot: SlotDataDto = serde_json::from_str(&response)?;
     println!("Slot deserialized successfully");
-    db_ops::db_ops::insert_slot(
-        Arc::new(Mutex::new(
-            SqlitePool::connect(env!("DATABASE_URL")).await.unwrap(),
-        )),
-        slot_number,
-        &slot.clone().into(),
-    )
-    .await?;
-    println!("Slot {slot_number} inserted into db successfully");
     Ok(slot)
 }
@@ -0,0 +1,223 @@
+use std::path::{Path, PathBuf};
+
+use chrono::DateTime;
+use serde::Serialize;
+
+use super::{
+    diff::differing_fields,
+    pipeline::{enrich_slot, ingest_decoded, validate_slot, PersistMode},
+};
+use crate::{
+    db_ops::{SharedStore, Store},
+    dtos::{Epoch, EpochInfo},
+    models::{EpochData, SlotData},
+    AppResult,
+};
+
+#[derive(Debug, Serialize)]
+pub struct ImportConflict {
+    pub file: String,
+    pub table: &'static str,
+    pub key: i64,
+    pub fields: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct InvalidRecord {
+    pub file: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct ImportReport {
+    pub files_read: u64,
+    pub epochs_inserted: u64,
+    pub slots_inserted: u64,
+    pub duplicates: u64,
+    pub conflicts: Vec<ImportConflict>,
+    pub invalid: Vec<InvalidRecord>,
+}
+
+enum Outcome {
+    Inserted,
+    Duplicate,
+    Conflict(Vec<String>),
+}
+
+/// Loads archived beaconcha.in `Epoch`/`EpochInfo` JSON responses and `slot_data` shaped CSV
+/// exports. Existing rows are never overwritten, differing ones are reported as conflicts.
+/// New rows are written through the ingestion pipeline's validate, enrich and persist stages.
+pub async fn import_paths(store: SharedStore, paths: &[String]) -> AppResult<ImportReport> {
+    let mut files = Vec::new();
+    for path in paths {
+        collect_files(Path::new(path), &mut files)?;
+    }
+    files.sort();
+
+    let mut report = ImportReport::default();
+    for file in files {
+        println!("IMPORT: Reading {}", file.display());
+        report.files_read += 1;
+
+        let file_name = file.display().to_string();
+        let records = match file.extension().and_then(|extension| extension.to_str()) {
+            Some("json") => read_json(&file).await,
+            Some("csv") => read_csv(&file).await,
+            _ => continue,
+        };
+        let (epochs, slots) = match records {
+            Ok(records) => records,
+            Err(e) => {
+                report.invalid.push(InvalidRecord {
+                    file: file_name,
+                    reason: e.to_string(),
+                });
+                continue;
+            }
+        };
+
+        for epoch_data in epochs {
+            if let Err(reason) = validate_epoch(&epoch_data) {
+                report.invalid.push(InvalidRecord {
+                    file: file_name.clone(),
+                    reason,
+                });
+                continue;
+            }
+            let epoch_number = epoch_data.epoch;
+            match import_epoch(&*store, epoch_data).await? {
+                Outcome::Inserted => report.epochs_inserted += 1,
+                Outcome::Duplicate => report.duplicates += 1,
+                Outcome::Conflict(fields) => report.conflicts.push(ImportConflict {
+                    file: file_name.clone(),
+                    table: "epoch_data",
+                    key: epoch_number,
+                    fields,
+                }),
+            }
+        }
+
+        for mut slot in slots {
+            // The epoch the slot number falls in, not the row's own and possibly wrong `epoch`.
+            let expected_epoch = store.network().epoch_of_slot(slot.slot);
+            if let Err(reason) = validate_slot(store.network(), expected_epoch, &slot) {
+                report.invalid.push(InvalidRecord {
+                    file: file_name.clone(),
+                    reason,
+                });
+                continue;
+            }
+            // Stored rows went through the enrich stage, compare them to an enriched import.
+            enrich_slot(&mut slot);
+            let slot_number = slot.slot;
+            match import_slot(&*store, expected_epoch, slot).await? {
+                Outcome::Inserted => report.slots_inserted += 1,
+                Outcome::Duplicate => report.duplicates += 1,
+                Outcome::Conflict(fields) => report.conflicts.push(ImportConflict {
+                    file: file_name.clone(),
+                    table: "slot_data",
+                    key: slot_number,
+                    fields,
+                }),
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+fn collect_files(path: &Path, files: &mut Vec<PathBuf>) -> AppResult<()> {
+    if path.is_dir() {
+        for entry in std::fs::read_dir(path)? {
+            collect_files(&entry?.path(), files)?;
+        }
+    } else {
+        files.push(path.to_path_buf());
+    }
+
+    Ok(())
+}
+
+async fn read_json(file: &Path) -> AppResult<(Vec<EpochData>, Vec<SlotData>)> {
+    let contents = tokio::fs::read_to_string(file).await?;
+
+    if let Ok(epoch_info) = serde_json::from_str::<EpochInfo>(&contents) {
+        return Ok((vec![epoch_info.data.into()], Vec::new()));
+    }
+
+    let epoch = serde_json::from_str::<Epoch>(&contents)?;
+    Ok((
+        Vec::new(),
+        epoch.data.into_iter().map(SlotData::from).collect(),
+    ))
+}
+
+async fn read_csv(file: &Path) -> AppResult<(Vec<EpochData>, Vec<SlotData>)> {
+    let contents = tokio::fs::read(file).await?;
+    let mut reader = csv::Reader::from_reader(contents.as_slice());
+    let slots = reader
+        .deserialize::<SlotData>()
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok((Vec::new(), slots))
+}
+
+fn validate_epoch(epoch_data: &EpochData) -> Result<(), String> {
+    if epoch_data.epoch < 0 {
+        return Err(format!("negative epoch number {}", epoch_data.epoch));
+    }
+    if DateTime::parse_from_rfc3339(&epoch_data.ts).is_err() {
+        return Err(format!(
+            "epoch {} has an invalid timestamp {}",
+            epoch_data.epoch, epoch_data.ts
+        ));
+    }
+
+    Ok(())
+}
+
+async fn import_epoch(store: &dyn Store, epoch_data: EpochData) -> AppResult<Outcome> {
+    match store.get_epoch(epoch_data.epoch).await? {
+        Some(existing) => {
+            let fields = differing_fields(&existing, &epoch_data)?;
+            if fields.is_empty() {
+                Ok(Outcome::Duplicate)
+            } else {
+                Ok(Outcome::Conflict(fields))
+            }
+        }
+        None => {
+            ingest_decoded(
+                store,
+                PersistMode::Insert,
+                epoch_data.epoch,
+                Some(epoch_data),
+                Vec::new(),
+            )
+            .await?;
+            Ok(Outcome::Inserted)
+        }
+    }
+}
+
+async fn import_slot(store: &dyn Store, epoch: i64, slot: SlotData) -> AppResult<Outcome> {
+    match store.get_slot(slot.slot).await? {
+        Some(existing) => {
+            let fields = differing_fields(&existing, &slot)?;
+            if fields.is_empty() {
+                Ok(Outcome::Duplicate)
+            } else {
+                Ok(Outcome::Conflict(fields))
+            }
+        }
+        None => {
+            let slot_number = slot.slot;
+            let report =
+                ingest_decoded(store, PersistMode::Insert, epoch, None, vec![slot]).await?;
+            if report.slots_persisted == 0 {
+                return Err(format!("Slot {slot_number} could not be stored").into());
+            }
+            Ok(Outcome::Inserted)
+        }
+    }
+}
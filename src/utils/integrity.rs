@@ -0,0 +1,305 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Serialize;
+
+use super::{
+    pipeline::{EpochTarget, IngestJob, IngestPipeline, PersistMode},
+    Network,
+};
+use crate::{
+    db_ops::SharedStore,
+    models::{EpochData, SlotData},
+    AppResult,
+};
+
+/// `status` of a slot whose block was proposed and is canonical.
+const STATUS_PROPOSED: &str = "1";
+const STATUS_MISSED: &str = "2";
+const STATUS_ORPHANED: &str = "3";
+
+#[derive(Debug, Serialize)]
+pub struct Violation {
+    pub check: &'static str,
+    pub epoch: i64,
+    pub slot: Option<i64>,
+    pub detail: String,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct IntegrityReport {
+    pub epochs_checked: u64,
+    pub slots_checked: u64,
+    /// Number of violations per check.
+    pub checks: BTreeMap<&'static str, u64>,
+    pub violations: Vec<Violation>,
+    /// Inclusive epoch ranges holding at least one violation.
+    pub bad_ranges: Vec<(i64, i64)>,
+    /// Epochs re-ingested from upstream, when repairing.
+    pub reingested: Vec<i64>,
+}
+
+impl IntegrityReport {
+    fn violation(&mut self, check: &'static str, epoch: i64, slot: Option<i64>, detail: String) {
+        *self.checks.entry(check).or_default() += 1;
+        self.violations.push(Violation {
+            check,
+            epoch,
+            slot,
+            detail,
+        });
+    }
+
+    fn bad_epochs(&self) -> BTreeSet<i64> {
+        self.violations
+            .iter()
+            .map(|violation| violation.epoch)
+            .collect()
+    }
+}
+
+/// Collapses sorted epochs into inclusive runs of consecutive epochs.
+fn ranges(epochs: &BTreeSet<i64>) -> Vec<(i64, i64)> {
+    let mut ranges: Vec<(i64, i64)> = Vec::new();
+    for &epoch in epochs {
+        match ranges.last_mut() {
+            Some((_, to)) if *to + 1 == epoch => *to = epoch,
+            _ => ranges.push((epoch, epoch)),
+        }
+    }
+    ranges
+}
+
+/// The last proposed slot seen, which the next proposed slot must build on. Missed and
+/// orphaned slots in between are skipped over.
+struct ChainTip {
+    slot: i64,
+    epoch: i64,
+    blockroot: String,
+    exec_block_number: Option<i64>,
+    exec_block_hash: Option<String>,
+}
+
+fn check_chain(report: &mut IntegrityReport, tip: &Option<ChainTip>, slot: &SlotData) {
+    let Some(tip) = tip else {
+        return;
+    };
+
+    if slot.parentroot != tip.blockroot {
+        report.violation(
+            "parentroot_chain",
+            slot.epoch,
+            Some(slot.slot),
+            format!(
+                "parentroot {} does not match blockroot {} of slot {}",
+                slot.parentroot, tip.blockroot, tip.slot
+            ),
+        );
+        report.violation(
+            "parentroot_chain",
+            tip.epoch,
+            Some(tip.slot),
+            format!("not the parent of the next proposed slot {}", slot.slot),
+        );
+    }
+
+    // Pre-merge slots carry no execution payload, the checks start with the first one that does.
+    if let (Some(number), Some(previous_number)) = (slot.exec_block_number, tip.exec_block_number) {
+        if number <= previous_number {
+            report.violation(
+                "exec_block_number_increasing",
+                slot.epoch,
+                Some(slot.slot),
+                format!(
+                    "exec_block_number {number} does not follow {previous_number} of slot {}",
+                    tip.slot
+                ),
+            );
+        }
+    }
+
+    if let (Some(parent_hash), Some(previous_hash)) = (&slot.exec_parent_hash, &tip.exec_block_hash)
+    {
+        if parent_hash != previous_hash {
+            report.violation(
+                "exec_parent_hash_chain",
+                slot.epoch,
+                Some(slot.slot),
+                format!(
+                    "exec_parent_hash {parent_hash} does not match exec_block_hash {previous_hash} of slot {}",
+                    tip.slot
+                ),
+            );
+        }
+    }
+}
+
+fn check_epoch_counters(
+    report: &mut IntegrityReport,
+    network: Network,
+    epoch_data: &EpochData,
+    slots: &[SlotData],
+) {
+    if slots.len() as i64 != network.slots_per_epoch {
+        report.violation(
+            "missing_slots",
+            epoch_data.epoch,
+            None,
+            format!(
+                "{} of {} slots stored",
+                slots.len(),
+                network.slots_per_epoch
+            ),
+        );
+        return;
+    }
+
+    let count = |status: &str| slots.iter().filter(|slot| slot.status == status).count() as i64;
+    let sum = |value: fn(&SlotData) -> i64| slots.iter().map(value).sum::<i64>();
+
+    let counters = [
+        (
+            "proposedblocks",
+            epoch_data.proposedblocks,
+            count(STATUS_PROPOSED),
+        ),
+        (
+            "missedblocks",
+            epoch_data.missedblocks,
+            count(STATUS_MISSED),
+        ),
+        (
+            "orphanedblocks",
+            epoch_data.orphanedblocks,
+            count(STATUS_ORPHANED),
+        ),
+        (
+            "attestationscount",
+            epoch_data.attestationscount,
+            sum(|slot| slot.attestationscount),
+        ),
+        (
+            "depositscount",
+            epoch_data.depositscount,
+            sum(|slot| slot.depositscount),
+        ),
+        (
+            "voluntaryexitscount",
+            epoch_data.voluntaryexitscount,
+            sum(|slot| slot.voluntaryexitscount),
+        ),
+        (
+            "proposerslashingscount",
+            epoch_data.proposerslashingscount,
+            sum(|slot| slot.proposerslashingscount),
+        ),
+        (
+            "attesterslashingscount",
+            epoch_data.attesterslashingscount,
+            sum(|slot| slot.attesterslashingscount),
+        ),
+        (
+            "withdrawalcount",
+            epoch_data.withdrawalcount,
+            sum(|slot| slot.withdrawalcount),
+        ),
+    ];
+
+    for (counter, stored, from_slots) in counters {
+        if stored != from_slots {
+            report.violation(
+                "epoch_counters",
+                epoch_data.epoch,
+                None,
+                format!("{counter} is {stored} but the slot rows add up to {from_slots}"),
+            );
+        }
+    }
+}
+
+/// Checks the invariants between stored epochs and slots of `from_epoch..=to_epoch`.
+pub async fn verify_range(
+    store: SharedStore,
+    from_epoch: i64,
+    to_epoch: i64,
+) -> AppResult<IntegrityReport> {
+    let network = store.network();
+    let mut report = IntegrityReport::default();
+    let mut tip: Option<ChainTip> = None;
+    let mut previous_slot: Option<i64> = None;
+
+    for epoch_number in from_epoch..=to_epoch {
+        let slots = store.get_epoch_slots(epoch_number).await?;
+        report.slots_checked += slots.len() as u64;
+
+        for slot in &slots {
+            if network.epoch_of_slot(slot.slot) != slot.epoch {
+                report.violation(
+                    "slot_epoch",
+                    slot.epoch,
+                    Some(slot.slot),
+                    format!("slot {} is stored under epoch {}", slot.slot, slot.epoch),
+                );
+            }
+
+            // A slot that is not stored at all may have been the parent, nothing to chain to.
+            if previous_slot.is_some_and(|previous| previous + 1 != slot.slot) {
+                tip = None;
+            }
+            previous_slot = Some(slot.slot);
+
+            if slot.status != STATUS_PROPOSED {
+                continue;
+            }
+            check_chain(&mut report, &tip, slot);
+            tip = Some(ChainTip {
+                slot: slot.slot,
+                epoch: slot.epoch,
+                blockroot: slot.blockroot.clone(),
+                exec_block_number: slot
+                    .exec_block_number
+                    .or(tip.as_ref().and_then(|tip| tip.exec_block_number)),
+                exec_block_hash: slot
+                    .exec_block_hash
+                    .clone()
+                    .or(tip.as_ref().and_then(|tip| tip.exec_block_hash.clone())),
+            });
+        }
+
+        match store.get_epoch(epoch_number).await? {
+            Some(epoch_data) => {
+                report.epochs_checked += 1;
+                check_epoch_counters(&mut report, network, &epoch_data, &slots);
+            }
+            None => report.violation(
+                "missing_epoch",
+                epoch_number,
+                None,
+                "epoch_data row is missing".to_string(),
+            ),
+        }
+    }
+
+    report.bad_ranges = ranges(&report.bad_epochs());
+
+    Ok(report)
+}
+
+/// Re-ingests every epoch with a violation from upstream, replacing the stored rows.
+pub async fn reingest_bad_epochs(
+    pipeline: &IngestPipeline,
+    report: &mut IntegrityReport,
+) -> AppResult<()> {
+    for epoch_number in report.bad_epochs() {
+        println!("VERIFY: Re-ingesting epoch {epoch_number}");
+        pipeline
+            .run(IngestJob {
+                target: EpochTarget::Number(epoch_number),
+                with_epoch_data: true,
+                mode: PersistMode::Replace,
+            })
+            .await?;
+        report.reingested.push(epoch_number);
+    }
+
+    Ok(())
+}
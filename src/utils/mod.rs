@@ -1,6 +1,16 @@
+pub mod diff;
 pub mod external_api;
+pub mod importer;
+pub mod integrity;
+pub mod network;
+pub mod parquet_export;
+pub mod pipeline;
+pub mod retention;
 pub mod scheduler;
 pub use external_api::*;
+pub use network::*;
+pub use pipeline::*;
+pub use retention::*;
 pub use scheduler::*;
 
-pub static BEACON_CHAIN_API_URL: &str = "https://beaconcha.in/api/v1";
+pub const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
@@ -0,0 +1,147 @@
+use crate::{db_ops, AppResult};
+
+/// Chain parameters of a network rish can follow. Each network is stored in its own database
+/// and served under its own `/<name>/...` routes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Network {
+    pub name: &'static str,
+    /// beaconcha.in API of the network.
+    pub api_url: &'static str,
+    pub genesis_timestamp: i64,
+    pub seconds_per_slot: i64,
+    pub slots_per_epoch: i64,
+}
+
+/// Genesis 2020-12-01T12:00:23Z.
+pub const MAINNET: Network = Network {
+    name: "mainnet",
+    api_url: "https://beaconcha.in/api/v1",
+    genesis_timestamp: 1_606_824_023,
+    seconds_per_slot: 12,
+    slots_per_epoch: 32,
+};
+
+/// Genesis 2023-09-28T12:00:00Z.
+pub const HOLESKY: Network = Network {
+    name: "holesky",
+    api_url: "https://holesky.beaconcha.in/api/v1",
+    genesis_timestamp: 1_695_902_400,
+    seconds_per_slot: 12,
+    slots_per_epoch: 32,
+};
+
+/// Genesis 2022-06-20T16:00:00Z.
+pub const SEPOLIA: Network = Network {
+    name: "sepolia",
+    api_url: "https://sepolia.beaconcha.in/api/v1",
+    genesis_timestamp: 1_655_733_600,
+    seconds_per_slot: 12,
+    slots_per_epoch: 32,
+};
+
+/// Genesis 2021-12-08T19:15:40Z, 5 second slots and 16 slot epochs.
+pub const GNOSIS: Network = Network {
+    name: "gnosis",
+    api_url: "https://gnosis.beaconcha.in/api/v1",
+    genesis_timestamp: 1_638_993_340,
+    seconds_per_slot: 5,
+    slots_per_epoch: 16,
+};
+
+pub const NETWORKS: &[Network] = &[MAINNET, HOLESKY, SEPOLIA, GNOSIS];
+
+/// Epochs the finalized checkpoint trails the current epoch by while the chain is healthy.
+pub const FINALITY_DELAY_EPOCHS: i64 = 2;
+
+impl Default for Network {
+    fn default() -> Self {
+        MAINNET
+    }
+}
+
+impl std::fmt::Display for Network {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name)
+    }
+}
+
+impl Network {
+    pub fn from_name(name: &str) -> AppResult<Network> {
+        NETWORKS
+            .iter()
+            .find(|network| network.name.eq_ignore_ascii_case(name.trim()))
+            .copied()
+            .ok_or_else(|| {
+                let known: Vec<&str> = NETWORKS.iter().map(|network| network.name).collect();
+                format!(
+                    "Unknown network {name}, expected one of {}",
+                    known.join(", ")
+                )
+                .into()
+            })
+    }
+
+    /// Networks listed in `RISH_NETWORKS` (comma separated), mainnet when unset. The first one
+    /// is the primary network: it keeps the unprefixed API routes and the one-off commands
+    /// (`diff`, `verify`, `import`, ...) act on it.
+    pub fn from_env() -> AppResult<Vec<Network>> {
+        let Ok(value) = std::env::var("RISH_NETWORKS") else {
+            return Ok(vec![MAINNET]);
+        };
+
+        let mut networks = Vec::new();
+        for name in value.split(',').filter(|name| !name.trim().is_empty()) {
+            let network = Network::from_name(name)?;
+            if !networks.contains(&network) {
+                networks.push(network);
+            }
+        }
+
+        if networks.is_empty() {
+            return Err("RISH_NETWORKS does not name any network".into());
+        }
+
+        Ok(networks)
+    }
+
+    /// `DATABASE_URL_<NAME>` when set. Otherwise the primary network uses `DATABASE_URL` and
+    /// the others `sqlite://rish-<name>.sqlite`.
+    pub fn database_url(&self, primary: bool) -> String {
+        let variable = format!("DATABASE_URL_{}", self.name.to_uppercase());
+
+        match std::env::var(variable) {
+            Ok(db_url) => db_url,
+            Err(_) if primary => db_ops::database_url(),
+            Err(_) => format!("sqlite://rish-{}.sqlite", self.name),
+        }
+    }
+
+    pub fn seconds_per_epoch(&self) -> i64 {
+        self.seconds_per_slot * self.slots_per_epoch
+    }
+
+    /// Unix timestamp at which `slot` starts.
+    pub fn slot_timestamp(&self, slot: i64) -> i64 {
+        self.genesis_timestamp + slot * self.seconds_per_slot
+    }
+
+    /// Unix timestamp at which `epoch` starts.
+    pub fn epoch_timestamp(&self, epoch: i64) -> i64 {
+        self.slot_timestamp(epoch * self.slots_per_epoch)
+    }
+
+    /// Epoch running at the unix timestamp, 0 before genesis.
+    pub fn epoch_at(&self, timestamp: i64) -> i64 {
+        ((timestamp - self.genesis_timestamp) / self.seconds_per_epoch()).max(0)
+    }
+
+    /// Newest epoch assumed finalized at the unix timestamp, [`FINALITY_DELAY_EPOCHS`] behind
+    /// the running one.
+    pub fn finalized_epoch_at(&self, timestamp: i64) -> i64 {
+        (self.epoch_at(timestamp) - FINALITY_DELAY_EPOCHS).max(0)
+    }
+
+    pub fn epoch_of_slot(&self, slot: i64) -> i64 {
+        slot / self.slots_per_epoch
+    }
+}
@@ -0,0 +1,448 @@
+use std::{
+    fs::File,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use arrow_array::{
+    ArrayRef, BooleanArray, Float64Array, Int64Array, RecordBatch, StringArray,
+    TimestampMillisecondArray,
+};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use parquet::{arrow::ArrowWriter, basic::Compression, file::properties::WriterProperties};
+use serde::Serialize;
+
+use super::{Network, SECONDS_PER_DAY};
+use crate::{
+    cli::ExportMode,
+    db_ops::SharedStore,
+    models::{ChangeSource, EpochData, RowVersion, SlotData},
+    AppResult,
+};
+
+const PARTITION_FILE: &str = "part-0.parquet";
+/// Directory holding one empty file per exported day, next to the table directories.
+const EXPORTED_DIR: &str = "_exported";
+
+#[derive(Debug, Default, Serialize)]
+pub struct ExportReport {
+    pub partitions_written: Vec<String>,
+    pub partitions_skipped: u64,
+    pub epochs_exported: u64,
+    pub slots_exported: u64,
+}
+
+/// Inclusive epoch and slot ranges starting on one UTC day.
+struct DayPartition {
+    date: NaiveDate,
+    epochs: (i64, i64),
+    slots: (i64, i64),
+}
+
+/// First `unit`-second period since genesis starting at or after `timestamp`.
+fn first_period_from(network: Network, timestamp: i64, unit: i64) -> i64 {
+    (timestamp - network.genesis_timestamp + unit - 1)
+        .div_euclid(unit)
+        .max(0)
+}
+
+impl DayPartition {
+    fn of_epoch(network: Network, epoch: i64) -> Self {
+        let day_start =
+            network.epoch_timestamp(epoch).div_euclid(SECONDS_PER_DAY) * SECONDS_PER_DAY;
+        let day_end = day_start + SECONDS_PER_DAY;
+        let seconds_per_epoch = network.seconds_per_epoch();
+        let seconds_per_slot = network.seconds_per_slot;
+
+        DayPartition {
+            date: NaiveDateTime::from_timestamp_opt(day_start, 0)
+                .expect("day start is a valid timestamp")
+                .date(),
+            epochs: (
+                first_period_from(network, day_start, seconds_per_epoch),
+                first_period_from(network, day_end, seconds_per_epoch) - 1,
+            ),
+            slots: (
+                first_period_from(network, day_start, seconds_per_slot),
+                first_period_from(network, day_end, seconds_per_slot) - 1,
+            ),
+        }
+    }
+
+    fn path(&self, output_dir: &Path, table: &str) -> PathBuf {
+        output_dir
+            .join(table)
+            .join(format!("date={}", self.date.format("%Y-%m-%d")))
+            .join(PARTITION_FILE)
+    }
+
+    fn marker_path(&self, output_dir: &Path) -> PathBuf {
+        output_dir
+            .join(EXPORTED_DIR)
+            .join(format!("date={}", self.date.format("%Y-%m-%d")))
+    }
+}
+
+/// Whether the newest version of a row is still the placeholder `setup_db` seeded.
+fn is_seed<T>(history: &[RowVersion<T>]) -> bool {
+    history
+        .last()
+        .is_some_and(|version| version.source == ChangeSource::Seed.as_str())
+}
+
+fn timestamp_millis(ts: &str) -> AppResult<i64> {
+    Ok(DateTime::parse_from_rfc3339(ts)?.timestamp_millis())
+}
+
+fn epoch_batch(epochs: &[EpochData]) -> AppResult<RecordBatch> {
+    let int = |value: fn(&EpochData) -> i64| -> ArrayRef {
+        Arc::new(Int64Array::from_iter_values(epochs.iter().map(value)))
+    };
+    let flag = |value: fn(&EpochData) -> i64| -> ArrayRef {
+        Arc::new(BooleanArray::from(
+            epochs
+                .iter()
+                .map(|epoch_data| value(epoch_data) == 1)
+                .collect::<Vec<_>>(),
+        ))
+    };
+    let ts = epochs
+        .iter()
+        .map(|epoch_data| timestamp_millis(&epoch_data.ts))
+        .collect::<AppResult<Vec<_>>>()?;
+
+    Ok(RecordBatch::try_from_iter_with_nullable([
+        ("attestationscount", int(|e| e.attestationscount), false),
+        (
+            "attesterslashingscount",
+            int(|e| e.attesterslashingscount),
+            false,
+        ),
+        (
+            "averagevalidatorbalance",
+            int(|e| e.averagevalidatorbalance),
+            false,
+        ),
+        ("blockscount", int(|e| e.blockscount), false),
+        ("depositscount", int(|e| e.depositscount), false),
+        ("eligibleether", int(|e| e.eligibleether), false),
+        ("epoch", int(|e| e.epoch), false),
+        ("finalized", flag(|e| e.finalized), false),
+        (
+            "globalparticipationrate",
+            Arc::new(Float64Array::from_iter_values(
+                epochs.iter().map(|e| e.globalparticipationrate),
+            )) as ArrayRef,
+            false,
+        ),
+        ("missedblocks", int(|e| e.missedblocks), false),
+        ("orphanedblocks", int(|e| e.orphanedblocks), false),
+        ("proposedblocks", int(|e| e.proposedblocks), false),
+        (
+            "proposerslashingscount",
+            int(|e| e.proposerslashingscount),
+            false,
+        ),
+        ("rewards_exported", flag(|e| e.rewards_exported), false),
+        ("scheduledblocks", int(|e| e.scheduledblocks), false),
+        (
+            "totalvalidatorbalance",
+            int(|e| e.totalvalidatorbalance),
+            false,
+        ),
+        (
+            "ts",
+            Arc::new(TimestampMillisecondArray::from(ts).with_timezone("UTC")) as ArrayRef,
+            false,
+        ),
+        ("validatorscount", int(|e| e.validatorscount), false),
+        ("voluntaryexitscount", int(|e| e.voluntaryexitscount), false),
+        ("votedether", int(|e| e.votedether), false),
+        ("withdrawalcount", int(|e| e.withdrawalcount), false),
+    ])?)
+}
+
+fn slot_batch(network: Network, slots: &[SlotData]) -> AppResult<RecordBatch> {
+    let int = |value: fn(&SlotData) -> i64| -> ArrayRef {
+        Arc::new(Int64Array::from_iter_values(slots.iter().map(value)))
+    };
+    let optional_int = |value: fn(&SlotData) -> Option<i64>| -> ArrayRef {
+        Arc::new(Int64Array::from(
+            slots.iter().map(value).collect::<Vec<_>>(),
+        ))
+    };
+    let text = |value: fn(&SlotData) -> &String| -> ArrayRef {
+        Arc::new(StringArray::from_iter_values(slots.iter().map(value)))
+    };
+    let optional_text = |value: fn(&SlotData) -> &Option<String>| -> ArrayRef {
+        Arc::new(StringArray::from(
+            slots
+                .iter()
+                .map(|slot| value(slot).as_deref())
+                .collect::<Vec<_>>(),
+        ))
+    };
+    let exec_timestamp = TimestampMillisecondArray::from(
+        slots
+            .iter()
+            .map(|slot| {
+                slot.exec_timestamp
+                    .filter(|ts| *ts != 0)
+                    .map(|ts| ts * 1000)
+            })
+            .collect::<Vec<_>>(),
+    )
+    .with_timezone("UTC");
+    let timestamp = TimestampMillisecondArray::from_iter_values(
+        slots
+            .iter()
+            .map(|slot| network.slot_timestamp(slot.slot) * 1000),
+    )
+    .with_timezone("UTC");
+
+    Ok(RecordBatch::try_from_iter_with_nullable([
+        ("attestationscount", int(|s| s.attestationscount), false),
+        (
+            "attesterslashingscount",
+            int(|s| s.attesterslashingscount),
+            false,
+        ),
+        (
+            "blob_kzg_commitments_count",
+            optional_int(|s| s.blob_kzg_commitments_count),
+            true,
+        ),
+        ("blockroot", text(|s| &s.blockroot), false),
+        ("depositscount", int(|s| s.depositscount), false),
+        ("epoch", int(|s| s.epoch), false),
+        ("eth1data_blockhash", text(|s| &s.eth1data_blockhash), false),
+        (
+            "eth1data_depositcount",
+            int(|s| s.eth1data_depositcount),
+            false,
+        ),
+        (
+            "eth1data_depositroot",
+            text(|s| &s.eth1data_depositroot),
+            false,
+        ),
+        (
+            "exec_base_fee_per_gas",
+            optional_int(|s| s.exec_base_fee_per_gas),
+            true,
+        ),
+        (
+            "exec_blob_gas_used",
+            optional_int(|s| s.exec_blob_gas_used),
+            true,
+        ),
+        (
+            "exec_block_hash",
+            optional_text(|s| &s.exec_block_hash),
+            true,
+        ),
+        (
+            "exec_block_number",
+            optional_int(|s| s.exec_block_number),
+            true,
+        ),
+        (
+            "exec_excess_blob_gas",
+            optional_int(|s| s.exec_excess_blob_gas),
+            true,
+        ),
+        (
+            "exec_extra_data",
+            optional_text(|s| &s.exec_extra_data),
+            true,
+        ),
+        (
+            "exec_fee_recipient",
+            optional_text(|s| &s.exec_fee_recipient),
+            true,
+        ),
+        ("exec_gas_limit", optional_int(|s| s.exec_gas_limit), true),
+        ("exec_gas_used", optional_int(|s| s.exec_gas_used), true),
+        (
+            "exec_logs_bloom",
+            optional_text(|s| &s.exec_logs_bloom),
+            true,
+        ),
+        (
+            "exec_parent_hash",
+            optional_text(|s| &s.exec_parent_hash),
+            true,
+        ),
+        ("exec_random", optional_text(|s| &s.exec_random), true),
+        (
+            "exec_receipts_root",
+            optional_text(|s| &s.exec_receipts_root),
+            true,
+        ),
+        (
+            "exec_state_root",
+            optional_text(|s| &s.exec_state_root),
+            true,
+        ),
+        ("exec_timestamp", Arc::new(exec_timestamp) as ArrayRef, true),
+        (
+            "exec_transactions_count",
+            int(|s| s.exec_transactions_count),
+            false,
+        ),
+        ("graffiti", text(|s| &s.graffiti), false),
+        ("graffiti_text", text(|s| &s.graffiti_text), false),
+        (
+            "parent_beacon_block_root",
+            optional_text(|s| &s.parent_beacon_block_root),
+            true,
+        ),
+        ("parentroot", text(|s| &s.parentroot), false),
+        ("proposer", int(|s| s.proposer), false),
+        (
+            "proposerslashingscount",
+            int(|s| s.proposerslashingscount),
+            false,
+        ),
+        ("randaoreveal", text(|s| &s.randaoreveal), false),
+        ("signature", text(|s| &s.signature), false),
+        ("slot", int(|s| s.slot), false),
+        ("stateroot", text(|s| &s.stateroot), false),
+        ("status", text(|s| &s.status), false),
+        (
+            "syncaggregate_bits",
+            optional_text(|s| &s.syncaggregate_bits),
+            true,
+        ),
+        (
+            "syncaggregate_participation",
+            Arc::new(Float64Array::from_iter_values(
+                slots.iter().map(|s| s.syncaggregate_participation),
+            )) as ArrayRef,
+            false,
+        ),
+        (
+            "syncaggregate_signature",
+            optional_text(|s| &s.syncaggregate_signature),
+            true,
+        ),
+        ("timestamp", Arc::new(timestamp) as ArrayRef, false),
+        ("voluntaryexitscount", int(|s| s.voluntaryexitscount), false),
+        ("withdrawalcount", int(|s| s.withdrawalcount), false),
+    ])?)
+}
+
+/// Writes next to `path` first and renames, so a partition file is either complete or absent.
+fn write_parquet(path: &Path, batch: &RecordBatch) -> AppResult<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let tmp_path = path.with_extension("parquet.tmp");
+
+    let props = WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .build();
+    let mut writer = ArrowWriter::try_new(File::create(&tmp_path)?, batch.schema(), Some(props))?;
+    writer.write(batch)?;
+    writer.close()?;
+
+    std::fs::rename(tmp_path, path)?;
+    Ok(())
+}
+
+/// Exports `epoch_data` and `slot_data` as Parquet files partitioned by the UTC day the epoch
+/// or slot starts on, e.g. `<output_dir>/slot_data/date=2023-08-01/part-0.parquet`.
+///
+/// `ExportMode::Incremental` only writes days that are entirely finalized and not exported yet,
+/// so it can run repeatedly against the same directory. A day is marked as exported by an empty
+/// `<output_dir>/_exported/date=YYYY-MM-DD` file once its tables are written. Directories
+/// exported before those markers existed are recognised by their epoch file.
+///
+/// The placeholder epoch and slot `setup_db` seeds on an empty database are left out.
+pub async fn export_parquet(
+    store: SharedStore,
+    output_dir: &str,
+    mode: ExportMode,
+) -> AppResult<ExportReport> {
+    let network = store.network();
+    let mut report = ExportReport::default();
+    let output_dir = Path::new(output_dir);
+
+    let (Some(earliest), Ok(latest)) = (
+        store.get_earliest_epoch().await?,
+        store.get_latest_epoch().await,
+    ) else {
+        return Ok(report);
+    };
+    // Same finality as pruning, the stored `finalized` flags lag behind.
+    let finalized_epoch = network.finalized_epoch_at(Utc::now().timestamp());
+
+    // The seeds are the earliest rows, a real epoch or slot replacing them is kept.
+    let seed_epoch =
+        is_seed(&store.get_epoch_history(earliest.epoch).await?).then_some(earliest.epoch);
+    let seed_slot = match store.get_epoch_slots(earliest.epoch).await?.first() {
+        Some(slot) if is_seed(&store.get_slot_history(slot.slot).await?) => Some(slot.slot),
+        _ => None,
+    };
+
+    let mut partition = DayPartition::of_epoch(network, earliest.epoch);
+    while partition.epochs.0 <= latest.epoch {
+        let next = DayPartition::of_epoch(network, partition.epochs.1 + 1);
+
+        let epoch_path = partition.path(output_dir, "epoch_data");
+        let slot_path = partition.path(output_dir, "slot_data");
+        let marker_path = partition.marker_path(output_dir);
+
+        if mode == ExportMode::Incremental
+            && (partition.epochs.1 > finalized_epoch || marker_path.exists() || epoch_path.exists())
+        {
+            report.partitions_skipped += 1;
+            partition = next;
+            continue;
+        }
+
+        let mut epochs = store
+            .get_epochs_in_range(partition.epochs.0, partition.epochs.1)
+            .await?;
+        epochs.retain(|epoch| Some(epoch.epoch) != seed_epoch);
+        let mut slots = store
+            .get_slots_in_range(partition.slots.0, partition.slots.1)
+            .await?;
+        slots.retain(|slot| Some(slot.slot) != seed_slot);
+
+        if epochs.is_empty() && slots.is_empty() {
+            partition = next;
+            continue;
+        }
+
+        if !slots.is_empty() {
+            write_parquet(&slot_path, &slot_batch(network, &slots)?)?;
+            report.slots_exported += slots.len() as u64;
+            report
+                .partitions_written
+                .push(slot_path.display().to_string());
+        }
+        if !epochs.is_empty() {
+            write_parquet(&epoch_path, &epoch_batch(&epochs)?)?;
+            report.epochs_exported += epochs.len() as u64;
+            report
+                .partitions_written
+                .push(epoch_path.display().to_string());
+        }
+        if let Some(dir) = marker_path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        File::create(&marker_path)?;
+
+        println!(
+            "Exported {}: {} epochs, {} slots",
+            partition.date,
+            epochs.len(),
+            slots.len()
+        );
+
+        partition = next;
+    }
+
+    Ok(report)
+}
@@ -0,0 +1,563 @@
+use std::{
+    fmt,
+    future::Future,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Instant,
+};
+
+use serde::Serialize;
+use tokio::sync::{mpsc, oneshot};
+
+use super::{external_api::get_specific_epoch_data, get_specific_epoch_slots, Network};
+use crate::{
+    db_ops::{SharedStore, Store},
+    dtos::{EpochDataDto, SlotDataDto},
+    models::{EpochData, SlotData, GAS_PER_BLOB},
+    AppResult,
+};
+
+/// Number of in-flight epochs buffered between two stages before the upstream stage waits.
+pub const STAGE_CHANNEL_CAPACITY: usize = 4;
+
+#[derive(Debug, Clone, Copy)]
+pub enum EpochTarget {
+    Latest,
+    Number(i64),
+}
+
+impl EpochTarget {
+    fn as_param(&self) -> String {
+        match self {
+            EpochTarget::Latest => "latest".to_string(),
+            EpochTarget::Number(epoch_number) => epoch_number.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PersistMode {
+    Insert,
+    Update,
+    /// Inserts missing rows and overwrites existing ones, used to repair damaged ranges.
+    Replace,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct IngestJob {
+    pub target: EpochTarget,
+    pub with_epoch_data: bool,
+    pub mode: PersistMode,
+}
+
+#[derive(Debug, Default)]
+pub struct IngestReport {
+    pub epoch: i64,
+    pub epoch_data: Option<EpochData>,
+    pub slots_persisted: u64,
+    pub slots_rejected: u64,
+    pub slots_failed: u64,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Stage {
+    Fetch,
+    Decode,
+    Validate,
+    Enrich,
+    Persist,
+}
+
+impl Stage {
+    fn name(&self) -> &'static str {
+        match self {
+            Stage::Fetch => "fetch",
+            Stage::Decode => "decode",
+            Stage::Validate => "validate",
+            Stage::Enrich => "enrich",
+            Stage::Persist => "persist",
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct StageMetrics {
+    pub processed: AtomicU64,
+    pub failed: AtomicU64,
+    pub busy_micros: AtomicU64,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct StageSnapshot {
+    pub processed: u64,
+    pub failed: u64,
+    pub busy_micros: u64,
+}
+
+impl StageMetrics {
+    pub fn snapshot(&self) -> StageSnapshot {
+        StageSnapshot {
+            processed: self.processed.load(Ordering::Relaxed),
+            failed: self.failed.load(Ordering::Relaxed),
+            busy_micros: self.busy_micros.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct PipelineMetrics {
+    pub fetch: StageMetrics,
+    pub decode: StageMetrics,
+    pub validate: StageMetrics,
+    pub enrich: StageMetrics,
+    pub persist: StageMetrics,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct PipelineSnapshot {
+    pub fetch: StageSnapshot,
+    pub decode: StageSnapshot,
+    pub validate: StageSnapshot,
+    pub enrich: StageSnapshot,
+    pub persist: StageSnapshot,
+}
+
+impl PipelineMetrics {
+    fn stage(&self, stage: Stage) -> &StageMetrics {
+        match stage {
+            Stage::Fetch => &self.fetch,
+            Stage::Decode => &self.decode,
+            Stage::Validate => &self.validate,
+            Stage::Enrich => &self.enrich,
+            Stage::Persist => &self.persist,
+        }
+    }
+
+    pub fn snapshot(&self) -> PipelineSnapshot {
+        PipelineSnapshot {
+            fetch: self.fetch.snapshot(),
+            decode: self.decode.snapshot(),
+            validate: self.validate.snapshot(),
+            enrich: self.enrich.snapshot(),
+            persist: self.persist.snapshot(),
+        }
+    }
+}
+
+impl fmt::Display for PipelineMetrics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for stage in [
+            Stage::Fetch,
+            Stage::Decode,
+            Stage::Validate,
+            Stage::Enrich,
+            Stage::Persist,
+        ] {
+            let snapshot = self.stage(stage).snapshot();
+            write!(
+                f,
+                "[{}: ok={} failed={} busy={}ms] ",
+                stage.name(),
+                snapshot.processed,
+                snapshot.failed,
+                snapshot.busy_micros / 1000
+            )?;
+        }
+        Ok(())
+    }
+}
+
+struct Envelope<T> {
+    job: IngestJob,
+    report: IngestReport,
+    reply: oneshot::Sender<AppResult<IngestReport>>,
+    payload: T,
+}
+
+struct Fetched {
+    epoch: i64,
+    epoch_data: Option<EpochDataDto>,
+    slots: Vec<SlotDataDto>,
+}
+
+struct Decoded {
+    epoch: i64,
+    epoch_data: Option<EpochData>,
+    slots: Vec<SlotData>,
+}
+
+/// Fetch -> decode -> validate -> enrich -> persist, each stage running on its own task and
+/// connected to the next one by a bounded channel so a slow database throttles the fetcher.
+#[derive(Clone)]
+pub struct IngestPipeline {
+    network: Network,
+    jobs: mpsc::Sender<Envelope<()>>,
+    metrics: Arc<PipelineMetrics>,
+}
+
+impl IngestPipeline {
+    pub fn spawn(store: SharedStore) -> Self {
+        let network = store.network();
+        let metrics = Arc::new(PipelineMetrics::default());
+
+        let (jobs, fetch_rx) = mpsc::channel(STAGE_CHANNEL_CAPACITY);
+        let (fetch_tx, decode_rx) = mpsc::channel(STAGE_CHANNEL_CAPACITY);
+        let (decode_tx, validate_rx) = mpsc::channel(STAGE_CHANNEL_CAPACITY);
+        let (validate_tx, enrich_rx) = mpsc::channel(STAGE_CHANNEL_CAPACITY);
+        let (enrich_tx, persist_rx) = mpsc::channel(STAGE_CHANNEL_CAPACITY);
+        let (persist_tx, mut done_rx) = mpsc::channel::<Envelope<()>>(STAGE_CHANNEL_CAPACITY);
+
+        spawn_stage(
+            Stage::Fetch,
+            Arc::clone(&metrics),
+            fetch_rx,
+            fetch_tx,
+            move |job, report, payload| fetch(network, job, report, payload),
+        );
+        spawn_stage(
+            Stage::Decode,
+            Arc::clone(&metrics),
+            decode_rx,
+            decode_tx,
+            decode,
+        );
+        spawn_stage(
+            Stage::Validate,
+            Arc::clone(&metrics),
+            validate_rx,
+            validate_tx,
+            move |job, report, decoded| validate(network, job, report, decoded),
+        );
+        spawn_stage(
+            Stage::Enrich,
+            Arc::clone(&metrics),
+            enrich_rx,
+            enrich_tx,
+            enrich,
+        );
+        spawn_stage(
+            Stage::Persist,
+            Arc::clone(&metrics),
+            persist_rx,
+            persist_tx,
+            move |job, report, decoded| {
+                let store = Arc::clone(&store);
+                async move { persist(&*store, job, report, decoded).await }
+            },
+        );
+
+        tokio::spawn(async move {
+            while let Some(envelope) = done_rx.recv().await {
+                let _ = envelope.reply.send(Ok(envelope.report));
+            }
+        });
+
+        IngestPipeline {
+            network,
+            jobs,
+            metrics,
+        }
+    }
+
+    /// Queues a job and waits until its last stage has finished.
+    pub async fn run(&self, job: IngestJob) -> AppResult<IngestReport> {
+        let (reply, response) = oneshot::channel();
+
+        self.jobs
+            .send(Envelope {
+                job,
+                report: IngestReport::default(),
+                reply,
+                payload: (),
+            })
+            .await
+            .map_err(|_| pipeline_closed())?;
+
+        response.await.map_err(|_| pipeline_closed())?
+    }
+
+    pub fn metrics(&self) -> Arc<PipelineMetrics> {
+        Arc::clone(&self.metrics)
+    }
+
+    /// Network the pipeline fetches from and its store holds.
+    pub fn network(&self) -> Network {
+        self.network
+    }
+}
+
+fn pipeline_closed() -> Box<dyn std::error::Error + Send + Sync> {
+    Box::new(std::io::Error::new(
+        std::io::ErrorKind::BrokenPipe,
+        "Ingestion pipeline is closed.",
+    ))
+}
+
+fn invalid_data(message: String) -> Box<dyn std::error::Error + Send + Sync> {
+    Box::new(std::io::Error::new(
+        std::io::ErrorKind::InvalidData,
+        message,
+    ))
+}
+
+fn spawn_stage<I, O, F, Fut>(
+    stage: Stage,
+    metrics: Arc<PipelineMetrics>,
+    mut input: mpsc::Receiver<Envelope<I>>,
+    output: mpsc::Sender<Envelope<O>>,
+    process: F,
+) where
+    I: Send + 'static,
+    O: Send + 'static,
+    F: Fn(IngestJob, IngestReport, I) -> Fut + Send + 'static,
+    Fut: Future<Output = AppResult<(IngestReport, O)>> + Send + 'static,
+{
+    tokio::spawn(async move {
+        while let Some(Envelope {
+            job,
+            report,
+            reply,
+            payload,
+        }) = input.recv().await
+        {
+            let stage_metrics = metrics.stage(stage);
+            let started = Instant::now();
+            let result = process(job, report, payload).await;
+
+            stage_metrics
+                .busy_micros
+                .fetch_add(started.elapsed().as_micros() as u64, Ordering::Relaxed);
+
+            match result {
+                Ok((report, payload)) => {
+                    stage_metrics.processed.fetch_add(1, Ordering::Relaxed);
+
+                    let envelope = Envelope {
+                        job,
+                        report,
+                        reply,
+                        payload,
+                    };
+                    if output.send(envelope).await.is_err() {
+                        break;
+                    }
+                }
+                Err(e) => {
+                    stage_metrics.failed.fetch_add(1, Ordering::Relaxed);
+                    println!("PIPELINE: {} stage failed: {e}", stage.name());
+                    let _ = reply.send(Err(e));
+                }
+            }
+        }
+    });
+}
+
+async fn fetch(
+    network: Network,
+    job: IngestJob,
+    mut report: IngestReport,
+    _: (),
+) -> AppResult<(IngestReport, Fetched)> {
+    let epoch_data = if job.with_epoch_data {
+        Some(get_specific_epoch_data(network, &job.target.as_param()).await?)
+    } else {
+        None
+    };
+
+    let epoch = match (job.target, &epoch_data) {
+        (EpochTarget::Number(epoch_number), _) => epoch_number,
+        (EpochTarget::Latest, Some(epoch_data)) => epoch_data.epoch,
+        (EpochTarget::Latest, None) => get_specific_epoch_data(network, "latest").await?.epoch,
+    };
+
+    let slots = get_specific_epoch_slots(network, epoch).await?;
+    report.epoch = epoch;
+
+    Ok((
+        report,
+        Fetched {
+            epoch,
+            epoch_data,
+            slots,
+        },
+    ))
+}
+
+async fn decode(
+    _: IngestJob,
+    report: IngestReport,
+    fetched: Fetched,
+) -> AppResult<(IngestReport, Decoded)> {
+    Ok((
+        report,
+        Decoded {
+            epoch: fetched.epoch,
+            epoch_data: fetched.epoch_data.map(EpochData::from),
+            slots: fetched.slots.into_iter().map(SlotData::from).collect(),
+        },
+    ))
+}
+
+pub(crate) fn validate_slot(
+    network: Network,
+    epoch_number: i64,
+    slot: &SlotData,
+) -> Result<(), String> {
+    if slot.slot < 0 {
+        return Err(format!("negative slot number {}", slot.slot));
+    }
+    if network.epoch_of_slot(slot.slot) != slot.epoch {
+        return Err(format!(
+            "slot {} does not belong to epoch {}",
+            slot.slot, slot.epoch
+        ));
+    }
+    if slot.epoch != epoch_number {
+        return Err(format!(
+            "slot {} was returned for epoch {epoch_number}",
+            slot.slot
+        ));
+    }
+    if let (Some(gas_used), Some(gas_limit)) = (slot.exec_gas_used, slot.exec_gas_limit) {
+        if gas_used > gas_limit {
+            return Err(format!(
+                "slot {} uses {gas_used} gas over its {gas_limit} limit",
+                slot.slot
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+async fn validate(
+    network: Network,
+    _: IngestJob,
+    mut report: IngestReport,
+    mut decoded: Decoded,
+) -> AppResult<(IngestReport, Decoded)> {
+    if let Some(epoch_data) = &decoded.epoch_data {
+        if epoch_data.epoch != decoded.epoch {
+            return Err(invalid_data(format!(
+                "Epoch data for {} was returned for epoch {}",
+                epoch_data.epoch, decoded.epoch
+            )));
+        }
+    }
+
+    let epoch_number = decoded.epoch;
+    decoded
+        .slots
+        .retain(|slot| match validate_slot(network, epoch_number, slot) {
+            Ok(_) => true,
+            Err(reason) => {
+                println!("PIPELINE: Rejecting slot: {reason}");
+                report.slots_rejected += 1;
+                false
+            }
+        });
+
+    Ok((report, decoded))
+}
+
+pub(crate) fn enrich_slot(slot: &mut SlotData) {
+    if slot.blob_kzg_commitments_count.is_none() {
+        slot.blob_kzg_commitments_count = slot
+            .exec_blob_gas_used
+            .map(|blob_gas_used| blob_gas_used / GAS_PER_BLOB);
+    }
+}
+
+async fn enrich(
+    _: IngestJob,
+    report: IngestReport,
+    mut decoded: Decoded,
+) -> AppResult<(IngestReport, Decoded)> {
+    decoded.slots.iter_mut().for_each(enrich_slot);
+
+    Ok((report, decoded))
+}
+
+async fn persist(
+    store: &dyn Store,
+    job: IngestJob,
+    mut report: IngestReport,
+    decoded: Decoded,
+) -> AppResult<(IngestReport, ())> {
+    if let Some(epoch_data) = decoded.epoch_data {
+        match job.mode {
+            PersistMode::Insert => store.insert_epoch(epoch_data.clone()).await?,
+            PersistMode::Update => {
+                store
+                    .update_epoch(epoch_data.epoch, epoch_data.clone())
+                    .await?
+            }
+            PersistMode::Replace => match store.get_epoch(epoch_data.epoch).await? {
+                Some(_) => {
+                    store
+                        .update_epoch(epoch_data.epoch, epoch_data.clone())
+                        .await?
+                }
+                None => store.insert_epoch(epoch_data.clone()).await?,
+            },
+        }
+        report.epoch_data = Some(epoch_data);
+    }
+
+    for slot in decoded.slots {
+        let slot_number = slot.slot;
+        let result = match job.mode {
+            PersistMode::Insert => store.insert_slot(&slot).await,
+            PersistMode::Update => store.update_slot(slot).await,
+            PersistMode::Replace => match store.get_slot(slot_number).await {
+                Ok(Some(_)) => store.update_slot(slot).await,
+                Ok(None) => store.insert_slot(&slot).await,
+                Err(e) => Err(e),
+            },
+        };
+
+        match result {
+            Ok(_) => report.slots_persisted += 1,
+            Err(e) => {
+                println!("PIPELINE: Failed to persist slot {slot_number}: {e}");
+                report.slots_failed += 1;
+            }
+        }
+    }
+
+    Ok((report, ()))
+}
+
+/// Runs rows fetched or read outside the pipeline, all belonging to `epoch`, through its
+/// validate, enrich and persist stages. Used by the store's fallback fetchers and the importer
+/// so every write gets the same checks as the scheduler's.
+pub(crate) async fn ingest_decoded(
+    store: &dyn Store,
+    mode: PersistMode,
+    epoch: i64,
+    epoch_data: Option<EpochData>,
+    slots: Vec<SlotData>,
+) -> AppResult<IngestReport> {
+    let job = IngestJob {
+        target: EpochTarget::Number(epoch),
+        with_epoch_data: epoch_data.is_some(),
+        mode,
+    };
+    let decoded = Decoded {
+        epoch,
+        epoch_data,
+        slots,
+    };
+    let report = IngestReport {
+        epoch,
+        ..IngestReport::default()
+    };
+
+    let (report, decoded) = validate(store.network(), job, report, decoded).await?;
+    let (report, decoded) = enrich(job, report, decoded).await?;
+    let (report, ()) = persist(store, job, report, decoded).await?;
+
+    Ok(report)
+}
@@ -0,0 +1,163 @@
+use std::time::Duration;
+
+use chrono::Utc;
+use serde::Serialize;
+
+use super::Network;
+use crate::{db_ops::SharedStore, AppResult};
+
+pub const DEFAULT_PRUNE_BATCH_SIZE: i64 = 500;
+pub const DEFAULT_PRUNE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+/// Free pages handed back to the filesystem after each batch, 4 MiB with the default page size.
+pub const RECLAIM_PAGES_PER_BATCH: i64 = 1024;
+/// Pause between batches so ingestion can take the write lock in between.
+pub const PAUSE_BETWEEN_BATCHES: Duration = Duration::from_millis(200);
+
+/// How long rows are kept per table. `None` keeps them forever. `epoch_data` is always kept,
+/// it is small and everything else is derived from it.
+///
+/// Blob sidecar summaries reference their slot, so they never outlive it: pruning a slot
+/// also prunes its summary even when `blob_sidecar_summary` is kept longer.
+///
+/// `change_outbox` events past their retention are only pruned once every sink with a cursor
+/// has read them. Without any cursor, events within the retention window are still kept, so a
+/// sink configured later starts from the oldest of those rather than missing them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetentionPolicy {
+    pub slot_data: Option<Duration>,
+    pub blob_sidecar_summary: Option<Duration>,
+    pub change_outbox: Option<Duration>,
+    pub batch_size: i64,
+    pub interval: Duration,
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        RetentionPolicy {
+            slot_data: None,
+            blob_sidecar_summary: None,
+            change_outbox: None,
+            batch_size: DEFAULT_PRUNE_BATCH_SIZE,
+            interval: DEFAULT_PRUNE_INTERVAL,
+        }
+    }
+}
+
+pub(crate) fn env_number(name: &str) -> AppResult<Option<u64>> {
+    match std::env::var(name) {
+        Ok(value) => Ok(Some(
+            value
+                .parse::<u64>()
+                .map_err(|err| format!("Invalid {name}={value}: {err}"))?,
+        )),
+        Err(_) => Ok(None),
+    }
+}
+
+fn days(count: u64) -> Duration {
+    Duration::from_secs(count * 24 * 60 * 60)
+}
+
+impl RetentionPolicy {
+    /// Reads `RISH_RETAIN_SLOT_DATA_DAYS`, `RISH_RETAIN_BLOB_SIDECAR_SUMMARY_DAYS`,
+    /// `RISH_RETAIN_CHANGE_OUTBOX_DAYS`, `RISH_PRUNE_BATCH_SIZE` and `RISH_PRUNE_INTERVAL_SECS`.
+    /// Unset retentions keep everything.
+    pub fn from_env() -> AppResult<Self> {
+        let defaults = RetentionPolicy::default();
+
+        Ok(RetentionPolicy {
+            slot_data: env_number("RISH_RETAIN_SLOT_DATA_DAYS")?.map(days),
+            blob_sidecar_summary: env_number("RISH_RETAIN_BLOB_SIDECAR_SUMMARY_DAYS")?.map(days),
+            change_outbox: env_number("RISH_RETAIN_CHANGE_OUTBOX_DAYS")?.map(days),
+            batch_size: env_number("RISH_PRUNE_BATCH_SIZE")?
+                .map(|size| size.max(1) as i64)
+                .unwrap_or(defaults.batch_size),
+            interval: env_number("RISH_PRUNE_INTERVAL_SECS")?
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.interval),
+        })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.slot_data.is_some()
+            || self.blob_sidecar_summary.is_some()
+            || self.change_outbox.is_some()
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct PruneReport {
+    /// Epoch assumed finalized when pruning started, nothing at or after it is ever pruned.
+    pub finalized_epoch: i64,
+    pub slots_pruned: u64,
+    pub blob_summaries_pruned: u64,
+    pub changes_pruned: u64,
+}
+
+/// Epoch of `network` that was current `age` ago.
+pub fn epoch_older_than(network: Network, age: Duration) -> i64 {
+    network.epoch_at(Utc::now().timestamp() - age.as_secs() as i64)
+}
+
+/// First epoch to keep for a table retained for `retention`, capped at the finalized epoch.
+fn prune_before(
+    network: Network,
+    retention: Option<Duration>,
+    finalized_epoch: i64,
+) -> Option<i64> {
+    retention.map(|age| epoch_older_than(network, age).min(finalized_epoch))
+}
+
+/// Deletes everything past its retention in batches of `policy.batch_size`, giving the freed
+/// pages back after every batch.
+pub async fn prune_expired(store: SharedStore, policy: &RetentionPolicy) -> AppResult<PruneReport> {
+    let network = store.network();
+    // The stored `finalized` flags lag behind, only the epoch being ingested gets updated.
+    let finalized_epoch = network.finalized_epoch_at(Utc::now().timestamp());
+    let mut report = PruneReport {
+        finalized_epoch,
+        ..PruneReport::default()
+    };
+
+    if let Some(before_epoch) = prune_before(network, policy.slot_data, finalized_epoch) {
+        loop {
+            let pruned = store.prune_slots(before_epoch, policy.batch_size).await?;
+            report.slots_pruned += pruned;
+            if pruned == 0 {
+                break;
+            }
+            store.reclaim_space(RECLAIM_PAGES_PER_BATCH).await?;
+            tokio::time::sleep(PAUSE_BETWEEN_BATCHES).await;
+        }
+    }
+
+    if let Some(before_epoch) = prune_before(network, policy.blob_sidecar_summary, finalized_epoch)
+    {
+        loop {
+            let pruned = store
+                .prune_blob_sidecar_summaries(before_epoch, policy.batch_size)
+                .await?;
+            report.blob_summaries_pruned += pruned;
+            if pruned == 0 {
+                break;
+            }
+            store.reclaim_space(RECLAIM_PAGES_PER_BATCH).await?;
+            tokio::time::sleep(PAUSE_BETWEEN_BATCHES).await;
+        }
+    }
+
+    if let Some(age) = policy.change_outbox {
+        let before = Utc::now().timestamp_millis() - age.as_millis() as i64;
+        loop {
+            let pruned = store.prune_changes(before, policy.batch_size).await?;
+            report.changes_pruned += pruned;
+            if pruned == 0 {
+                break;
+            }
+            store.reclaim_space(RECLAIM_PAGES_PER_BATCH).await?;
+            tokio::time::sleep(PAUSE_BETWEEN_BATCHES).await;
+        }
+    }
+
+    Ok(report)
+}
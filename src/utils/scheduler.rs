@@ -1,102 +1,41 @@
 use std::{sync::Arc, time::SystemTime};
 
-use super::{external_api::get_specific_epoch_data, get_specific_epoch_slots};
-use crate::{db_ops, models::SlotData, AppResult};
+use super::{
+    pipeline::{EpochTarget, IngestJob, IngestPipeline, PersistMode},
+    retention::{self, RetentionPolicy},
+};
+use crate::{db_ops::SharedStore, AppResult};
 use chrono::{DateTime, Utc};
-use sqlx::SqlitePool;
 use std::time::UNIX_EPOCH;
-use tokio::{
-    sync::Mutex,
-    time::{self, Duration},
-};
-
-pub async fn fetch_latest_epoch(db_conn: Arc<Mutex<SqlitePool>>) -> AppResult<()> {
-    println!("SCHEDULER_1: Started");
-
-    let interval = Duration::from_secs(384);
-
-    println!("SCHEDULER_1: Fetching latest Epoch Data from Chain");
-
-    let latest_epoch_on_chain = get_specific_epoch_data("latest").await?;
-
-    println!("SCHEDULER_1: Inserting latest Epoch Data into DB");
-
-    db_ops::insert_epoch(Arc::clone(&db_conn), latest_epoch_on_chain.clone().into()).await?;
-
-    println!("SCHEDULER_1: Fetching latest Epoch Slots from Chain");
-
-    let latest_epoch_slots_on_chain = get_specific_epoch_slots(latest_epoch_on_chain.epoch).await?;
-
-    println!("SCHEDULER_1: Inserting latest Epoch Slots into DB");
-
-    for slot in latest_epoch_slots_on_chain {
-        let db_slot: SlotData = slot.into();
-        db_ops::insert_slot(Arc::clone(&db_conn), db_slot.slot, &db_slot).await?;
-    }
+use tokio::time::{self, Duration};
 
-    let latest_epoch_timestamp = DateTime::<Utc>::from_utc(
-        DateTime::parse_from_rfc3339(&latest_epoch_on_chain.ts)
-            .expect("Failed to parse epoch timestamp")
-            .naive_utc(),
-        Utc,
-    )
-    .timestamp();
-
-    println!(
-        "SCHEDULER_1: Latest Epoch TimeStamp = {}",
-        latest_epoch_timestamp,
-    );
-    println!(
-        "SCHEDULER_1: Current System Timestamp: {}",
-        Utc::now().timestamp()
-    );
-
-    let time_since_update = SystemTime::now()
-        .duration_since(UNIX_EPOCH + Duration::from_secs(latest_epoch_timestamp as u64))
-        .unwrap_or_else(|_| Duration::from_secs(0));
-
-    println!(
-        "SCHEDULER_1: Time since update: {}",
-        time_since_update.as_secs()
-    );
-
-    let time_remaining = if time_since_update > interval {
-        Duration::from_secs(12)
-    } else {
-        interval - time_since_update
-    };
-
-    println!(
-        "SCHEDULER_1: Time Remaining for Next Epoch : {}",
-        time_remaining.as_secs()
-    );
-
-    println!("SCHEDULER_1: Sleeping");
-
-    time::sleep(time_remaining).await;
+pub async fn fetch_latest_epoch(pipeline: IngestPipeline) -> AppResult<()> {
+    let network = pipeline.network();
+    let tag = format!("SCHEDULER_1 {network}");
+    println!("{tag}: Started");
 
-    println!("SCHEDULER_1: Awake");
+    let interval = Duration::from_secs(network.seconds_per_epoch() as u64);
 
     loop {
-        println!("SCHEDULER_1: Fetching latest Epoch Data from Chain");
+        println!("{tag}: Ingesting latest Epoch Data and Slots from Chain");
 
-        let latest_epoch_on_chain = get_specific_epoch_data("latest").await?;
+        let report = pipeline
+            .run(IngestJob {
+                target: EpochTarget::Latest,
+                with_epoch_data: true,
+                mode: PersistMode::Insert,
+            })
+            .await?;
 
-        println!("SCHEDULER_1: Inserting latest Epoch Data in DB");
-
-        db_ops::insert_epoch(Arc::clone(&db_conn), latest_epoch_on_chain.clone().into()).await?;
-
-        println!("SCHEDULER_1: Fetching latest Epoch Slots from Chain");
-
-        let latest_epoch_slots_on_chain =
-            get_specific_epoch_slots(latest_epoch_on_chain.epoch).await?;
-
-        println!("SCHEDULER_1: Inserting latest Epoch Slots into DB");
+        println!(
+            "{tag}: Epoch {} ingested, {} slots persisted, {} rejected, {} failed",
+            report.epoch, report.slots_persisted, report.slots_rejected, report.slots_failed
+        );
+        println!("{tag}: Pipeline metrics {}", pipeline.metrics());
 
-        for slot in latest_epoch_slots_on_chain {
-            let db_slot: SlotData = slot.into();
-            db_ops::insert_slot(Arc::clone(&db_conn), db_slot.slot, &db_slot).await?;
-        }
+        let latest_epoch_on_chain = report
+            .epoch_data
+            .expect("Pipeline did not return the latest epoch data");
 
         let latest_epoch_timestamp = DateTime::<Utc>::from_utc(
             DateTime::parse_from_rfc3339(&latest_epoch_on_chain.ts)
@@ -106,13 +45,10 @@ pub async fn fetch_latest_epoch(db_conn: Arc<Mutex<SqlitePool>>) -> AppResult<()
         )
         .timestamp();
 
-        println!(
-            "SCHEDULER_1: Latest Epoch TimeStamp = {}",
-            latest_epoch_timestamp,
-        );
+        println!("{tag}: Latest Epoch TimeStamp = {}", latest_epoch_timestamp,);
 
         println!(
-            "SCHEDULER_1: Current System TimeStamp = {}",
+            "{tag}: Current System TimeStamp = {}",
             Utc::now().timestamp()
         );
 
@@ -120,39 +56,40 @@ pub async fn fetch_latest_epoch(db_conn: Arc<Mutex<SqlitePool>>) -> AppResult<()
             .duration_since(UNIX_EPOCH + Duration::from_secs(latest_epoch_timestamp as u64))
             .unwrap_or_else(|_| Duration::from_secs(0));
 
-        println!(
-            "SCHEDULER_1: Time since update: {}",
-            time_since_update.as_secs()
-        );
+        println!("{tag}: Time since update: {}", time_since_update.as_secs());
 
         let time_remaining = if time_since_update > interval {
-            Duration::from_secs(12)
+            Duration::from_secs(network.seconds_per_slot as u64)
         } else {
             interval - time_since_update
         };
 
         println!(
-            "SCHEDULER_1: Time Remaining for Next Epoch : {}",
+            "{tag}: Time Remaining for Next Epoch : {}",
             time_remaining.as_secs()
         );
 
-        println!("SCHEDULER_1: Sleeping");
+        println!("{tag}: Sleeping");
 
         time::sleep(time_remaining).await;
 
-        println!("SCHEDULER_1: Awake");
+        println!("{tag}: Awake");
     }
 }
 
-pub async fn update_current_epoch_and_slots(db_conn: Arc<Mutex<SqlitePool>>) -> AppResult<()> {
-    println!("SCHEDULER_2: Started");
+pub async fn update_current_epoch_and_slots(
+    store: SharedStore,
+    pipeline: IngestPipeline,
+) -> AppResult<()> {
+    let network = store.network();
+    let tag = format!("SCHEDULER_2 {network}");
+    println!("{tag}: Started");
 
-    let interval = Duration::from_secs(12);
+    let interval = Duration::from_secs(network.seconds_per_slot as u64);
 
-    println!("SCHEDULER_2: Pulling latest epoch from DB");
+    println!("{tag}: Pulling latest epoch from DB");
 
-    let latest_unexecuted_slot_in_db =
-        db_ops::get_latest_unexecuted_slot(Arc::clone(&db_conn)).await?;
+    let latest_unexecuted_slot_in_db = store.get_latest_unexecuted_slot().await?;
 
     let latest_unexecuted_slot_timestamp = latest_unexecuted_slot_in_db.exec_timestamp.unwrap();
 
@@ -165,45 +102,69 @@ pub async fn update_current_epoch_and_slots(db_conn: Arc<Mutex<SqlitePool>>) ->
         interval - time_since_update
     };
 
-    println!("SCHEDULER_2: Sleeping");
+    println!("{tag}: Sleeping");
 
     time::sleep(time_remaining).await;
 
-    println!("SCHEDULER_2: Awake");
+    println!("{tag}: Awake");
 
     loop {
-        println!("SCHEDULER_2: Pulling latest epoch from DB");
+        println!("{tag}: Pulling latest epoch from DB");
 
-        let current_epoch_data_in_db = db_ops::get_latest_epoch_data(Arc::clone(&db_conn)).await?;
+        let current_epoch_data_in_db = store.get_latest_epoch().await?;
         let current_epoch_number = current_epoch_data_in_db.epoch;
 
-        println!("SCHEDULER_2: Fetching Epoch {current_epoch_number} Data from chain");
-        let updated_current_epoch_data =
-            get_specific_epoch_data(&format!("{current_epoch_number}")).await?;
+        println!("{tag}: Updating Epoch {current_epoch_number} Data and Slots from chain");
 
-        println!("SCHEDULER_2: Fetching Epoch {current_epoch_number} Slots from chain");
+        let report = pipeline
+            .run(IngestJob {
+                target: EpochTarget::Number(current_epoch_number),
+                with_epoch_data: true,
+                mode: PersistMode::Update,
+            })
+            .await?;
 
-        let updated_current_epoch_slots = get_specific_epoch_slots(current_epoch_number).await?;
+        println!(
+            "{tag}: Epoch {current_epoch_number} updated, {} slots persisted, {} rejected, {} failed",
+            report.slots_persisted, report.slots_rejected, report.slots_failed
+        );
+        println!("{tag}: Pipeline metrics {}", pipeline.metrics());
 
-        println!("SCHEDULER_2: Updating the Epoch {current_epoch_number} in DB");
+        println!("{tag}: Sleeping");
 
-        db_ops::update_epoch(
-            Arc::clone(&db_conn),
-            current_epoch_number,
-            updated_current_epoch_data.into(),
-        )
-        .await?;
+        time::sleep(interval).await;
 
-        println!("SCHEDULER_2: Updating the slots for Epoch {current_epoch_number} into the DB");
+        println!("{tag}: Awake");
+    }
+}
 
-        for slot in updated_current_epoch_slots {
-            db_ops::update_slot(Arc::clone(&db_conn), slot.slot, slot.clone().into()).await?;
-        }
+pub async fn prune_expired_data(store: SharedStore, policy: RetentionPolicy) -> AppResult<()> {
+    let tag = format!("SCHEDULER_3 {}", store.network());
 
-        println!("SCHEDULER_2: Sleeping");
+    if !policy.is_enabled() {
+        println!("{tag}: No retention configured, keeping all data");
+        return Ok(());
+    }
 
-        time::sleep(interval).await;
+    println!("{tag}: Started with {policy:?}");
+
+    loop {
+        println!("{tag}: Pruning data past its retention");
+
+        let report = retention::prune_expired(Arc::clone(&store), &policy).await?;
+
+        println!(
+            "{tag}: Pruned {} slots, {} blob summaries and {} changes, finalized epoch {}",
+            report.slots_pruned,
+            report.blob_summaries_pruned,
+            report.changes_pruned,
+            report.finalized_epoch
+        );
+
+        println!("{tag}: Sleeping");
+
+        time::sleep(policy.interval).await;
 
-        println!("SCHEDULER_2: Awake");
+        println!("{tag}: Awake");
     }
 }
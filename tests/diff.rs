@@ -0,0 +1,29 @@
+//! `rish diff` compares upstream rows with stored ones, which went through the ingest pipeline.
+
+use rish::{
+    dtos::SlotDataDto,
+    models::{SlotData, GAS_PER_BLOB},
+    utils::diff::DiffReport,
+};
+
+#[test]
+fn enriched_slots_match_their_raw_upstream_row() {
+    // Upstream leaves the blob count out, the enrich stage derives it from the blob gas used.
+    let upstream = SlotDataDto {
+        slot: 32_000,
+        epoch: 1_000,
+        status: "1".to_string(),
+        blockroot: format!("0x{:064x}", 32_000),
+        exec_blob_gas_used: Some(2 * GAS_PER_BLOB),
+        blob_kzg_commitments_count: None,
+        ..SlotDataDto::default()
+    };
+    let mut stored = SlotData::from(upstream.clone());
+    stored.blob_kzg_commitments_count = Some(2);
+
+    let mut report = DiffReport::default();
+    report.compare_slot(Some(&stored), upstream).unwrap();
+
+    assert_eq!(report.rows_compared, 1);
+    assert_eq!(report.rows_with_differences, 0, "{:?}", report.differences);
+}
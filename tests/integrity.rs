@@ -0,0 +1,85 @@
+//! `rish verify` chains every proposed slot to the previous proposed one, across missed and
+//! orphaned slots but not across slots that are not stored.
+
+use std::sync::Arc;
+
+use rish::{
+    db_ops::MemoryStore, dtos::SlotDataDto, models::SlotData, utils::integrity::verify_range,
+    utils::MAINNET, SharedStore,
+};
+
+const CHAIN_CHECKS: [&str; 3] = [
+    "parentroot_chain",
+    "exec_block_number_increasing",
+    "exec_parent_hash_chain",
+];
+
+fn root(slot_number: i64) -> String {
+    format!("0x{slot_number:064x}")
+}
+
+fn hash(slot_number: i64) -> String {
+    format!("0x{:064x}", slot_number + 1_000_000)
+}
+
+/// Slot `slot_number` built on the block of slot `parent`.
+fn slot(slot_number: i64, parent: i64, status: &str) -> SlotData {
+    SlotData::from(SlotDataDto {
+        slot: slot_number,
+        epoch: MAINNET.epoch_of_slot(slot_number),
+        status: status.to_string(),
+        blockroot: root(slot_number),
+        parentroot: Some(root(parent)),
+        exec_block_number: Some(slot_number),
+        exec_block_hash: Some(hash(slot_number)),
+        exec_parent_hash: Some(hash(parent)),
+        ..SlotDataDto::default()
+    })
+}
+
+#[tokio::test]
+async fn chain_checks_skip_missed_slots_and_reset_on_gaps() {
+    let store: SharedStore = Arc::new(MemoryStore::new().with_network(MAINNET));
+    let mut parent = 319;
+    for slot_number in 320..384 {
+        let stored = match slot_number {
+            // Missed and orphaned, the next proposed slot builds on the one before them.
+            330 => slot(330, parent, "2"),
+            335 => slot(335, parent, "3"),
+            // Not stored at all, its child cannot be checked.
+            360 => {
+                parent = 360;
+                continue;
+            }
+            // Build on the wrong block, right after a proposed and after a missed slot.
+            340 => slot(340, 338, "1"),
+            346 => slot(346, parent, "2"),
+            347 => slot(347, 344, "1"),
+            _ => slot(slot_number, parent, "1"),
+        };
+        if stored.status == "1" {
+            parent = slot_number;
+        }
+        store.insert_slot(&stored).await.unwrap();
+    }
+
+    let report = verify_range(store, 10, 11).await.unwrap();
+    let chain_violations: Vec<(&str, Option<i64>)> = report
+        .violations
+        .iter()
+        .filter(|violation| CHAIN_CHECKS.contains(&violation.check))
+        .map(|violation| (violation.check, violation.slot))
+        .collect();
+
+    assert_eq!(
+        chain_violations,
+        vec![
+            ("parentroot_chain", Some(340)),
+            ("parentroot_chain", Some(339)),
+            ("exec_parent_hash_chain", Some(340)),
+            ("parentroot_chain", Some(347)),
+            ("parentroot_chain", Some(345)),
+            ("exec_parent_hash_chain", Some(347)),
+        ]
+    );
+}
@@ -0,0 +1,83 @@
+//! `rish export --incremental` marks every day it wrote, so a day with slots but no epoch rows
+//! is not rewritten by the next run.
+
+use std::sync::Arc;
+
+use chrono::{SecondsFormat, TimeZone, Utc};
+use rish::{
+    cli::ExportMode,
+    db_ops::MemoryStore,
+    dtos::SlotDataDto,
+    models::{EpochData, SlotData},
+    utils::{parquet_export::export_parquet, MAINNET},
+    SharedStore,
+};
+
+fn epoch(epoch_number: i64) -> EpochData {
+    let ts = Utc
+        .timestamp_opt(MAINNET.epoch_timestamp(epoch_number), 0)
+        .unwrap();
+    EpochData {
+        attestationscount: 3_000,
+        attesterslashingscount: 0,
+        averagevalidatorbalance: 32_000_000_000,
+        blockscount: 32,
+        depositscount: 0,
+        eligibleether: 30_000_000_000_000_000,
+        epoch: epoch_number,
+        finalized: 1,
+        globalparticipationrate: 0.99,
+        missedblocks: 0,
+        orphanedblocks: 0,
+        proposedblocks: 32,
+        proposerslashingscount: 0,
+        rewards_exported: 0,
+        scheduledblocks: 0,
+        totalvalidatorbalance: 30_000_000_000_000_000,
+        ts: ts.to_rfc3339_opts(SecondsFormat::Secs, true),
+        validatorscount: 900_000,
+        voluntaryexitscount: 0,
+        votedether: 29_000_000_000_000_000,
+        withdrawalcount: 16,
+    }
+}
+
+fn slot(slot_number: i64) -> SlotData {
+    SlotData::from(SlotDataDto {
+        slot: slot_number,
+        epoch: MAINNET.epoch_of_slot(slot_number),
+        status: "1".to_string(),
+        ..SlotDataDto::default()
+    })
+}
+
+#[tokio::test]
+async fn incremental_export_skips_days_it_already_wrote() {
+    let dir = std::env::temp_dir().join(format!("rish-parquet-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let store: SharedStore = Arc::new(MemoryStore::new().with_network(MAINNET));
+    // Epochs on the first and third day, only slots on the second one.
+    store.insert_epoch(epoch(1_000)).await.unwrap();
+    for slot_number in 1_300 * 32..1_300 * 32 + 4 {
+        store.insert_slot(&slot(slot_number)).await.unwrap();
+    }
+    store.insert_epoch(epoch(1_600)).await.unwrap();
+
+    let output_dir = dir.to_str().unwrap();
+    let first = export_parquet(Arc::clone(&store), output_dir, ExportMode::Incremental)
+        .await
+        .unwrap();
+    let second = export_parquet(Arc::clone(&store), output_dir, ExportMode::Incremental)
+        .await
+        .unwrap();
+    let markers = std::fs::read_dir(dir.join("_exported")).unwrap().count();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(
+        (first.partitions_written.len(), first.slots_exported),
+        (3, 4)
+    );
+    assert_eq!(markers, 3);
+    assert!(second.partitions_written.is_empty());
+    assert_eq!(second.partitions_skipped, 3);
+}
@@ -0,0 +1,185 @@
+//! The relay hands outbox events to sinks with at-least-once delivery: a sink's cursor only
+//! moves once it acknowledged a batch. The NDJSON sink rotates its files by size.
+
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use chrono::{SecondsFormat, TimeZone, Utc};
+use rish::{
+    db_ops::MemoryStore,
+    models::{ChangeEvent, EpochData},
+    sinks::{relay_changes, NdjsonSink, PublishedChange, Sink, SinkSettings},
+    utils::MAINNET,
+    AppResult, SharedStore,
+};
+use serde_json::Value;
+
+fn epoch(epoch_number: i64) -> EpochData {
+    let ts = Utc
+        .timestamp_opt(MAINNET.epoch_timestamp(epoch_number), 0)
+        .unwrap();
+    EpochData {
+        attestationscount: 3_000,
+        attesterslashingscount: 0,
+        averagevalidatorbalance: 32_000_000_000,
+        blockscount: 32,
+        depositscount: 0,
+        eligibleether: 30_000_000_000_000_000,
+        epoch: epoch_number,
+        finalized: 0,
+        globalparticipationrate: 0.99,
+        missedblocks: 0,
+        orphanedblocks: 0,
+        proposedblocks: 32,
+        proposerslashingscount: 0,
+        rewards_exported: 0,
+        scheduledblocks: 0,
+        totalvalidatorbalance: 30_000_000_000_000_000,
+        ts: ts.to_rfc3339_opts(SecondsFormat::Secs, true),
+        validatorscount: 900_000,
+        voluntaryexitscount: 0,
+        votedether: 29_000_000_000_000_000,
+        withdrawalcount: 16,
+    }
+}
+
+/// One call to [`FlakySink::publish`]: the seqs it was handed, the sink's stored cursor at
+/// that moment and whether it acknowledged them.
+type Attempt = (Vec<i64>, i64, bool);
+
+/// Fails the first attempt at every batch and acknowledges the retry.
+struct FlakySink {
+    store: SharedStore,
+    attempts: Arc<Mutex<Vec<Attempt>>>,
+    fail_next: bool,
+}
+
+#[async_trait]
+impl Sink for FlakySink {
+    fn name(&self) -> String {
+        "flaky".to_string()
+    }
+
+    async fn publish(&mut self, changes: &[PublishedChange]) -> AppResult<()> {
+        let cursor = self.store.get_sink_cursor("flaky").await?;
+        let seqs = changes.iter().map(|change| change.change.seq).collect();
+        let failed = self.fail_next;
+        self.attempts.lock().unwrap().push((seqs, cursor, !failed));
+        self.fail_next = !failed;
+
+        if failed {
+            Err("destination unavailable".into())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[tokio::test]
+async fn relay_moves_the_cursor_only_after_a_delivery() {
+    let store: SharedStore = Arc::new(MemoryStore::new().with_network(MAINNET));
+    for epoch_number in 1_000..1_005 {
+        store.insert_epoch(epoch(epoch_number)).await.unwrap();
+    }
+
+    let attempts = Arc::new(Mutex::new(Vec::new()));
+    let sink = FlakySink {
+        store: Arc::clone(&store),
+        attempts: Arc::clone(&attempts),
+        fail_next: true,
+    };
+    let settings = SinkSettings {
+        batch_size: 2,
+        poll_interval: Duration::from_millis(10),
+        retry_initial: Duration::from_millis(1),
+        retry_max: Duration::from_millis(5),
+    };
+    let relay = tokio::spawn(relay_changes(Arc::clone(&store), Box::new(sink), settings));
+
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while store.get_sink_cursor("flaky").await.unwrap() < 5 {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    })
+    .await
+    .expect("relay did not deliver every change");
+    relay.abort();
+
+    assert_eq!(
+        *attempts.lock().unwrap(),
+        vec![
+            (vec![1, 2], 0, false),
+            (vec![1, 2], 0, true),
+            (vec![3, 4], 2, false),
+            (vec![3, 4], 2, true),
+            (vec![5], 4, false),
+            (vec![5], 4, true),
+        ]
+    );
+    assert_eq!(store.get_sink_cursor("flaky").await.unwrap(), 5);
+}
+
+fn change(seq: i64) -> PublishedChange {
+    PublishedChange {
+        network: MAINNET.name,
+        change: ChangeEvent::new(
+            seq,
+            1_700_000_000_000,
+            "slot".to_string(),
+            seq,
+            "inserted".to_string(),
+            "{}",
+        ),
+    }
+}
+
+#[tokio::test]
+async fn ndjson_rotates_full_files() {
+    let dir = std::env::temp_dir().join(format!("rish-ndjson-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    // Room for two lines per file, seqs 1 to 5 all serialize to the same length.
+    let line_bytes = change(1).to_json().unwrap().len() as u64 + 1;
+    let mut sink = NdjsonSink::new(dir.clone(), MAINNET, 2 * line_bytes).unwrap();
+
+    sink.publish(&[change(1), change(2), change(3)])
+        .await
+        .unwrap();
+    sink.publish(&[change(4), change(5)]).await.unwrap();
+
+    let mut files: Vec<_> = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    files.sort();
+    let contents: Vec<(String, Vec<i64>)> = files
+        .iter()
+        .map(|path| {
+            let seqs = std::fs::read_to_string(path)
+                .unwrap()
+                .lines()
+                .map(|line| {
+                    serde_json::from_str::<Value>(line).unwrap()["seq"]
+                        .as_i64()
+                        .unwrap()
+                })
+                .collect();
+            (
+                path.file_name().unwrap().to_string_lossy().into_owned(),
+                seqs,
+            )
+        })
+        .collect();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(
+        contents,
+        vec![
+            (format!("{MAINNET}-{:020}.ndjson", 1), vec![1, 2]),
+            (format!("{MAINNET}-{:020}.ndjson", 3), vec![3, 4]),
+            (format!("{MAINNET}-{:020}.ndjson", 5), vec![5]),
+        ]
+    );
+}
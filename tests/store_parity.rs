@@ -0,0 +1,452 @@
+//! The in-memory store stands in for SQLite in tests and tools, so both must answer the same
+//! writes with the same reads. Every test runs its writes against a `MemoryStore` and a
+//! migrated SQLite file, compares the two, then checks the expected result once.
+
+use std::{io::ErrorKind, path::PathBuf, sync::Arc};
+
+use chrono::{SecondsFormat, TimeZone, Utc};
+use rish::{
+    db_ops::{get_recent_epochs, MemoryStore},
+    models::{ChangeSource, EpochData, RollupGranularity, SlotData, SlotOrder, SlotQuery},
+    open_store,
+    utils::MAINNET,
+    SharedStore, Store,
+};
+use serde_json::Value;
+
+/// SQLite database file removed with its WAL files on drop.
+struct TempDb(PathBuf);
+
+impl TempDb {
+    fn new(name: &str) -> Self {
+        let path =
+            std::env::temp_dir().join(format!("rish-parity-{name}-{}.sqlite", std::process::id()));
+        let db = TempDb(path);
+        db.remove();
+        db
+    }
+
+    fn url(&self) -> String {
+        format!("sqlite://{}", self.0.display())
+    }
+
+    fn remove(&self) {
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{suffix}", self.0.display()));
+        }
+    }
+}
+
+impl Drop for TempDb {
+    fn drop(&mut self) {
+        self.remove();
+    }
+}
+
+/// A memory and a SQLite store, in that order. SQLite starts with the epoch 0 and slot 0 seed
+/// rows, the tests only look at epochs from 1000 on.
+async fn stores(db: &TempDb) -> Vec<SharedStore> {
+    let memory: SharedStore = Arc::new(MemoryStore::new().with_network(MAINNET));
+    let sqlite = open_store(&db.url(), MAINNET, ChangeSource::BeaconChain)
+        .await
+        .unwrap();
+    vec![memory, sqlite]
+}
+
+/// Asserts every store returned the same value and hands it back.
+fn same<T: std::fmt::Debug + PartialEq>(mut results: Vec<T>) -> T {
+    let first = results.remove(0);
+    for other in results {
+        assert_eq!(first, other, "stores disagree");
+    }
+    first
+}
+
+fn epoch(epoch_number: i64) -> EpochData {
+    let ts = Utc
+        .timestamp_opt(MAINNET.epoch_timestamp(epoch_number), 0)
+        .unwrap();
+    EpochData {
+        attestationscount: 3_000,
+        attesterslashingscount: 0,
+        averagevalidatorbalance: 32_000_000_000,
+        blockscount: 32,
+        depositscount: 0,
+        eligibleether: 30_000_000_000_000_000,
+        epoch: epoch_number,
+        finalized: 0,
+        globalparticipationrate: 0.99,
+        missedblocks: 0,
+        orphanedblocks: 0,
+        proposedblocks: 32,
+        proposerslashingscount: 0,
+        rewards_exported: 0,
+        scheduledblocks: 0,
+        totalvalidatorbalance: 30_000_000_000_000_000,
+        ts: ts.to_rfc3339_opts(SecondsFormat::Secs, true),
+        validatorscount: 900_000,
+        voluntaryexitscount: 0,
+        votedether: 29_000_000_000_000_000,
+        withdrawalcount: 16,
+    }
+}
+
+/// A proposed post-Deneb slot with one blob, proposed by `slot % 4` and paying
+/// `fee_recipient(slot % 2)`.
+fn slot(slot_number: i64) -> SlotData {
+    SlotData {
+        attestationscount: 128,
+        attesterslashingscount: 0,
+        blob_kzg_commitments_count: Some(1),
+        blockroot: format!("0x{slot_number:064x}"),
+        depositscount: 0,
+        epoch: MAINNET.epoch_of_slot(slot_number),
+        eth1data_blockhash: format!("0x{:064x}", 1),
+        eth1data_depositcount: 1_000,
+        eth1data_depositroot: format!("0x{:064x}", 2),
+        exec_base_fee_per_gas: Some(7),
+        exec_blob_gas_used: Some(131_072),
+        exec_block_hash: Some(format!("0x{:064x}", slot_number + 1_000_000)),
+        exec_block_number: Some(slot_number + 1_000),
+        exec_excess_blob_gas: Some(0),
+        exec_extra_data: Some("0x".to_string()),
+        exec_fee_recipient: Some(fee_recipient(slot_number % 2)),
+        exec_gas_limit: Some(30_000_000),
+        exec_gas_used: Some(15_000_000),
+        exec_logs_bloom: Some(format!("0x{}", "00".repeat(256))),
+        exec_parent_hash: Some(format!("0x{:064x}", slot_number + 999_999)),
+        exec_random: Some(format!("0x{:064x}", 3)),
+        exec_receipts_root: Some(format!("0x{:064x}", 4)),
+        exec_state_root: Some(format!("0x{:064x}", 5)),
+        exec_timestamp: Some(MAINNET.slot_timestamp(slot_number)),
+        exec_transactions_count: 150,
+        graffiti: format!("0x{:064x}", 0),
+        graffiti_text: String::new(),
+        parent_beacon_block_root: Some(format!("0x{:064x}", slot_number - 1)),
+        parentroot: format!("0x{:064x}", slot_number - 1),
+        proposer: slot_number % 4,
+        proposerslashingscount: 0,
+        randaoreveal: format!("0x{}", "ab".repeat(96)),
+        signature: format!("0x{}", "cd".repeat(96)),
+        slot: slot_number,
+        stateroot: format!("0x{:064x}", 6),
+        status: "1".to_string(),
+        syncaggregate_bits: Some(format!("0x{}", "ff".repeat(64))),
+        syncaggregate_participation: 0.98,
+        syncaggregate_signature: Some(format!("0x{}", "ef".repeat(96))),
+        voluntaryexitscount: 0,
+        withdrawalcount: 16,
+    }
+}
+
+fn fee_recipient(index: i64) -> String {
+    format!("0x{index:040x}")
+}
+
+fn slot_numbers(slots: &[SlotData]) -> Vec<i64> {
+    slots.iter().map(|slot| slot.slot).collect()
+}
+
+/// Inserts epochs `from..=to` and all of their slots.
+async fn insert_epochs(store: &dyn Store, from: i64, to: i64) {
+    for epoch_number in from..=to {
+        store.insert_epoch(epoch(epoch_number)).await.unwrap();
+        for slot_number in epoch_number * 32..(epoch_number + 1) * 32 {
+            store.insert_slot(&slot(slot_number)).await.unwrap();
+        }
+    }
+}
+
+#[tokio::test]
+async fn insert_and_update_agree() {
+    let db = TempDb::new("insert-update");
+    let mut results = Vec::new();
+
+    for store in stores(&db).await {
+        store.insert_slot(&slot(32_000)).await.unwrap();
+
+        // A second insert keeps the first row.
+        let mut ignored = slot(32_000);
+        ignored.proposer = 99;
+        store.insert_slot(&ignored).await.unwrap();
+        let inserted = store.get_slot(32_000).await.unwrap().unwrap();
+
+        // An update dropping the blob data drops the blob summary too.
+        let mut updated = slot(32_000);
+        updated.status = "3".to_string();
+        updated.blob_kzg_commitments_count = None;
+        updated.exec_blob_gas_used = None;
+        store.update_slot(updated).await.unwrap();
+        let updated = store.get_slot(32_000).await.unwrap().unwrap();
+        let blob_summary = store.get_blob_sidecar_summary(32_000).await.unwrap();
+
+        let missing = store.update_slot(slot(32_001)).await.unwrap_err();
+        let missing = missing.downcast_ref::<std::io::Error>().map(|e| e.kind());
+
+        results.push((inserted, updated, blob_summary.is_none(), missing));
+    }
+
+    let (inserted, updated, blob_summary_dropped, missing) = same(results);
+    assert_eq!(inserted, slot(32_000));
+    assert_eq!(updated.status, "3");
+    assert!(blob_summary_dropped);
+    assert_eq!(missing, Some(ErrorKind::NotFound));
+}
+
+#[tokio::test]
+async fn query_slots_agree() {
+    let db = TempDb::new("query-slots");
+    let queries = [
+        (
+            SlotQuery {
+                from_slot: Some(32_000),
+                ..SlotQuery::default()
+            },
+            None,
+            5,
+        ),
+        (
+            SlotQuery {
+                order: SlotOrder::Descending,
+                ..SlotQuery::default()
+            },
+            Some(32_040),
+            3,
+        ),
+        (
+            SlotQuery {
+                proposer: Some(2),
+                from_slot: Some(32_010),
+                to_slot: Some(32_030),
+                ..SlotQuery::default()
+            },
+            Some(32_018),
+            10,
+        ),
+        (
+            SlotQuery {
+                epoch: Some(1_001),
+                fee_recipient: Some(fee_recipient(1)),
+                ..SlotQuery::default()
+            },
+            None,
+            4,
+        ),
+        (
+            SlotQuery {
+                status: Some("3".to_string()),
+                ..SlotQuery::default()
+            },
+            None,
+            10,
+        ),
+        (
+            SlotQuery {
+                epoch: Some(1_001),
+                fee_recipient: Some(fee_recipient(1).trim_start_matches("0x").to_string()),
+                ..SlotQuery::default()
+            },
+            Some(32_035),
+            3,
+        ),
+        // No slot number follows the cursor.
+        (SlotQuery::default(), Some(i64::MAX), 10),
+        (
+            SlotQuery {
+                order: SlotOrder::Descending,
+                ..SlotQuery::default()
+            },
+            Some(i64::MIN),
+            10,
+        ),
+    ];
+    let mut results = Vec::new();
+
+    for store in stores(&db).await {
+        insert_epochs(&*store, 1_000, 1_001).await;
+        let mut orphaned = slot(32_033);
+        orphaned.status = "3".to_string();
+        store.update_slot(orphaned).await.unwrap();
+
+        let mut pages = Vec::new();
+        for (query, cursor, limit) in &queries {
+            let page = store.query_slots(query, *cursor, *limit).await.unwrap();
+            pages.push(slot_numbers(&page));
+        }
+        results.push(pages);
+    }
+
+    assert_eq!(
+        same(results),
+        vec![
+            vec![32_000, 32_001, 32_002, 32_003, 32_004],
+            vec![32_039, 32_038, 32_037],
+            vec![32_022, 32_026, 32_030],
+            vec![32_033, 32_035, 32_037, 32_039],
+            vec![32_033],
+            vec![32_037, 32_039, 32_041],
+            vec![],
+            vec![],
+        ]
+    );
+}
+
+#[tokio::test]
+async fn get_changes_agree() {
+    let db = TempDb::new("changes");
+    let mut results = Vec::new();
+
+    for store in stores(&db).await {
+        // SQLite already holds the seed rows' events.
+        let since = store
+            .get_changes(0, i64::MAX)
+            .await
+            .unwrap()
+            .last()
+            .map_or(0, |change| change.seq);
+
+        store.insert_epoch(epoch(1_000)).await.unwrap();
+        store.insert_slot(&slot(32_000)).await.unwrap();
+        store.insert_slot(&slot(32_000)).await.unwrap();
+        let mut updated = slot(32_000);
+        updated.exec_transactions_count = 151;
+        store.update_slot(updated).await.unwrap();
+        let mut reorged = slot(32_000);
+        reorged.blockroot = format!("0x{:064x}", 7);
+        store.update_slot(reorged).await.unwrap();
+
+        let first_page = store.get_changes(since, 2).await.unwrap();
+        let rest = store
+            .get_changes(first_page.last().unwrap().seq, 10)
+            .await
+            .unwrap();
+        let changes: Vec<(String, i64, String, Value)> = first_page
+            .into_iter()
+            .chain(rest)
+            .map(|change| (change.entity, change.key, change.kind, change.payload))
+            .collect();
+        results.push(changes);
+    }
+
+    let changes = same(results);
+    let summary: Vec<(&str, i64, &str)> = changes
+        .iter()
+        .map(|(entity, key, kind, _)| (entity.as_str(), *key, kind.as_str()))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("epoch", 1_000, "inserted"),
+            ("slot", 32_000, "inserted"),
+            ("slot", 32_000, "updated"),
+            ("slot", 32_000, "reorged"),
+        ]
+    );
+    assert_eq!(changes[2].3["exec_transactions_count"], 151);
+}
+
+#[tokio::test]
+async fn get_recent_epochs_agree() {
+    let db = TempDb::new("recent");
+    let mut results = Vec::new();
+
+    for store in stores(&db).await {
+        // Epoch 1_002 is missing, the three newest stored ones reach back past the gap.
+        insert_epochs(&*store, 1_000, 1_001).await;
+        insert_epochs(&*store, 1_003, 1_004).await;
+        let recent = get_recent_epochs(&*store, 3).await.unwrap();
+        results.push(serde_json::to_value(recent).unwrap());
+    }
+
+    let recent = same(results);
+    let recent = recent.as_array().unwrap();
+    let epochs: Vec<i64> = recent
+        .iter()
+        .map(|epoch| epoch["epoch"]["epoch"].as_i64().unwrap())
+        .collect();
+    assert_eq!(epochs, vec![1_004, 1_003, 1_001]);
+    for epoch in recent {
+        assert_eq!(epoch["slots"].as_array().unwrap().len(), 32);
+    }
+}
+
+#[tokio::test]
+async fn prune_changes_agree() {
+    let db = TempDb::new("prune-changes");
+    let mut results = Vec::new();
+
+    for store in stores(&db).await {
+        let since = store
+            .get_changes(0, i64::MAX)
+            .await
+            .unwrap()
+            .last()
+            .map_or(0, |change| change.seq);
+        insert_epochs(&*store, 1_000, 1_000).await;
+        let changes = store.get_changes(since, i64::MAX).await.unwrap();
+
+        // Without any sink cursor, events within the retention window stay for later sinks.
+        let an_hour_ago = Utc::now().timestamp_millis() - 60 * 60 * 1000;
+        let within_window = store.prune_changes(an_hour_ago, i64::MAX).await.unwrap();
+
+        // A sink that has read up to the tenth new event holds back everything after it.
+        store
+            .set_sink_cursor("ndjson", changes[9].seq)
+            .await
+            .unwrap();
+        let pruned = store.prune_changes(i64::MAX, i64::MAX).await.unwrap() as i64;
+        let kept = store.get_changes(0, i64::MAX).await.unwrap();
+
+        // Numbering carries on after the pruned events.
+        store.insert_slot(&slot(32_032)).await.unwrap();
+        let next = store.get_changes(changes[32].seq, 10).await.unwrap();
+
+        results.push((
+            within_window,
+            pruned - since,
+            kept.first().unwrap().seq - since,
+            next.len(),
+            next[0].seq - changes[32].seq,
+        ));
+    }
+
+    assert_eq!(same(results), (0, 10, 11, 1, 1));
+}
+
+#[tokio::test]
+async fn refetched_pruned_slots_keep_rollups() {
+    let db = TempDb::new("prune-rollups");
+    let mut results = Vec::new();
+
+    for store in stores(&db).await {
+        insert_epochs(&*store, 1_000, 1_001).await;
+        let daily = |store: SharedStore| async move {
+            store
+                .get_rollups(
+                    RollupGranularity::Daily,
+                    MAINNET.epoch_timestamp(1_000) - 86_400,
+                    MAINNET.epoch_timestamp(1_002),
+                )
+                .await
+                .unwrap()
+        };
+        let before = daily(Arc::clone(&store)).await;
+
+        // Epoch 1000 is pruned and fetched again, epoch 1002 is new.
+        store.prune_slots(1_001, i64::MAX).await.unwrap();
+        for slot_number in 32_000..32_032 {
+            store.insert_slot(&slot(slot_number)).await.unwrap();
+        }
+        let refetched = daily(Arc::clone(&store)).await;
+        store.insert_slot(&slot(32_064)).await.unwrap();
+        let after = daily(Arc::clone(&store)).await;
+
+        results.push((
+            refetched == before,
+            after.iter().map(|rollup| rollup.slots).sum::<i64>()
+                - before.iter().map(|rollup| rollup.slots).sum::<i64>(),
+        ));
+    }
+
+    assert_eq!(same(results), (true, 1));
+}